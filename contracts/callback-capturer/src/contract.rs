@@ -1,21 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response,
-    StdResult, WasmMsg,
+    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    QueryRequest, Response, StdResult, WasmMsg,
 };
 
 use cw2::set_contract_version;
 use simple_ica::ReceiveIcaResponseMsg;
 
 use crate::error::ContractError;
-use crate::msg::{AdminResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ResultResponse};
-use crate::state::{Config, CONFIG, RESULTS};
+use crate::msg::{
+    AdminResponse, CallersResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ResultResponse, ResultsResponse,
+};
+use crate::state::{Config, CALLERS, CONFIG, LEGACY_RESULTS, RESULTS};
+use crate::AsyncResultStore;
 use client_osmo_bindings::{OsmosisMsg, OsmosisQuery};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:callback-capturer";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// max amount of results removed in one prune call
+const PRUNE_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -30,6 +36,7 @@ pub fn instantiate(
         simple_ica_client: deps.api.addr_validate(&msg.simple_ica_client)?,
     };
     CONFIG.save(deps.storage, &cfg)?;
+    RESULTS.set_ttl(deps.storage, msg.result_ttl)?;
     Ok(Response::default())
 }
 
@@ -59,7 +66,40 @@ pub fn execute(
             transfer_channel_id,
         } => execute_send_funds(deps, env, info, ica_channel_id, transfer_channel_id),
         ExecuteMsg::ReceiveIcaResponse(resp) => execute_receive_ibc_response(deps, env, info, resp),
+        ExecuteMsg::PruneResults { limit } => execute_prune_results(deps, env, limit),
+        ExecuteMsg::UpdateCallers { to_add, to_remove } => {
+            execute_update_callers(deps, info, to_add, to_remove)
+        }
+    }
+}
+
+/// The admin and the registered callers can send requests, each in their own result namespace.
+fn assert_caller(deps: Deps, cfg: &Config, sender: &Addr) -> Result<(), ContractError> {
+    if cfg.admin.eq(sender) || CALLERS.has(deps.storage, sender) {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized {})
+    }
+}
+
+pub fn execute_update_callers(
+    deps: DepsMut,
+    info: MessageInfo,
+    to_add: Vec<String>,
+    to_remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if !cfg.admin.eq(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    for caller in &to_add {
+        let caller = deps.api.addr_validate(caller)?;
+        CALLERS.save(deps.storage, &caller, &Empty {})?;
     }
+    for caller in &to_remove {
+        CALLERS.remove(deps.storage, &deps.api.addr_validate(caller)?);
+    }
+    Ok(Response::new().add_attribute("action", "update_callers"))
 }
 
 pub fn execute_send_msgs(
@@ -71,14 +111,15 @@ pub fn execute_send_msgs(
     callback_id: String,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
-    if !cfg.admin.eq(&info.sender) {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_caller(deps.as_ref(), &cfg, &info.sender)?;
 
     let ica_msg = simple_ica_client::msg::ExecuteMsg::SendMsgs {
         channel_id,
         msgs,
-        callback_id: Some(callback_id),
+        callback_id: Some(AsyncResultStore::request_id(
+            info.sender.as_str(),
+            &callback_id,
+        )?),
     };
     let msg = WasmMsg::Execute {
         contract_addr: cfg.simple_ica_client.into(),
@@ -99,14 +140,15 @@ pub fn execute_ibc_query(
     callback_id: String,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
-    if !cfg.admin.eq(&info.sender) {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_caller(deps.as_ref(), &cfg, &info.sender)?;
 
     let ica_msg = simple_ica_client::msg::ExecuteMsg::IbcQuery {
         channel_id,
         msgs,
-        callback_id: Some(callback_id),
+        callback_id: Some(AsyncResultStore::request_id(
+            info.sender.as_str(),
+            &callback_id,
+        )?),
     };
     let msg = WasmMsg::Execute {
         contract_addr: cfg.simple_ica_client.into(),
@@ -168,7 +210,7 @@ pub fn execute_send_funds(
 
 pub fn execute_receive_ibc_response(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     resp: ReceiveIcaResponseMsg,
 ) -> Result<Response, ContractError> {
@@ -178,19 +220,60 @@ pub fn execute_receive_ibc_response(
         return Err(ContractError::Unauthorized {});
     }
 
-    RESULTS.save(deps.storage, &resp.id, &resp.msg)?;
+    // requests sent before results were namespaced were sent by the admin
+    let id = if AsyncResultStore::parse_request_id(&resp.id).is_ok() {
+        resp.id
+    } else {
+        AsyncResultStore::request_id(cfg.admin.as_str(), &resp.id)?
+    };
+    RESULTS.save(deps.storage, &env, &id, resp.msg)?;
 
     let res = Response::new()
         .add_attribute("action", "receive_callback")
-        .add_attribute("id", resp.id);
+        .add_attribute("id", id);
     Ok(res)
 }
 
+pub fn execute_prune_results(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let pruned = RESULTS.prune(deps.storage, env.block.time, limit.unwrap_or(PRUNE_LIMIT))?;
+    Ok(Response::new()
+        .add_attribute("action", "prune_results")
+        .add_attribute("pruned", pruned.to_string()))
+}
+
+/// Moves the results that were stored before namespacing into the namespace of the admin.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let legacy = LEGACY_RESULTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, ack) in &legacy {
+        let request_id = AsyncResultStore::request_id(cfg.admin.as_str(), id)?;
+        RESULTS.save(deps.storage, &env, &request_id, ack.clone())?;
+        LEGACY_RESULTS.remove(deps.storage, id);
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_results", legacy.len().to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
-        QueryMsg::Result { id } => to_binary(&query_result(deps, id)?),
+        QueryMsg::Callers {} => to_binary(&query_callers(deps)?),
+        QueryMsg::Result { namespace, id } => to_binary(&query_result(deps, namespace, id)?),
+        QueryMsg::Results {
+            namespace,
+            start_after,
+            limit,
+        } => to_binary(&query_results(deps, namespace, start_after, limit)?),
     }
 }
 
@@ -201,9 +284,31 @@ pub fn query_admin(deps: Deps) -> StdResult<AdminResponse> {
     })
 }
 
-pub fn query_result(deps: Deps, id: String) -> StdResult<ResultResponse> {
-    let result = RESULTS.load(deps.storage, &id)?;
-    Ok(ResultResponse { result })
+pub fn query_callers(deps: Deps) -> StdResult<CallersResponse> {
+    let callers = CALLERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|caller| caller.map(String::from))
+        .collect::<StdResult<_>>()?;
+    Ok(CallersResponse { callers })
+}
+
+pub fn query_result(deps: Deps, namespace: String, id: String) -> StdResult<ResultResponse> {
+    let result = RESULTS.load(deps.storage, &namespace, &id)?;
+    Ok(result.into())
+}
+
+pub fn query_results(
+    deps: Deps,
+    namespace: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ResultsResponse> {
+    let results = RESULTS
+        .page(deps.storage, &namespace, start_after.as_deref(), limit)?
+        .into_iter()
+        .map(|(id, result)| (id, result.into()))
+        .collect();
+    Ok(ResultsResponse { results })
 }
 
 #[cfg(test)]
@@ -226,6 +331,7 @@ mod tests {
         // instantiate the contract
         let instantiate_msg = InstantiateMsg {
             simple_ica_client: ica.to_string(),
+            result_ttl: None,
         };
         let info = mock_info(alice, &[]);
         instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
@@ -249,18 +355,56 @@ mod tests {
 
         // but alice can (original owner)
         let info = mock_info(alice, &[]);
-        let res = execute(deps.as_mut(), mock_env(), info, execute_msg).unwrap();
-        let expected = vec![SubMsg::new(WasmMsg::Execute {
-            contract_addr: ica.to_string(),
-            msg: to_binary(&simple_ica_client::msg::ExecuteMsg::SendMsgs {
-                channel_id: channel.to_string(),
-                msgs,
-                callback_id: Some("test".to_string()),
-            })
-            .unwrap(),
-            funds: vec![],
-        })];
-        assert_eq!(res.messages, expected);
+        let res = execute(deps.as_mut(), mock_env(), info, execute_msg.clone()).unwrap();
+        let expected = |namespace: &str| {
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: ica.to_string(),
+                msg: to_binary(&simple_ica_client::msg::ExecuteMsg::SendMsgs {
+                    channel_id: channel.to_string(),
+                    msgs: msgs.clone(),
+                    callback_id: Some(format!("{}/test", namespace)),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        };
+        assert_eq!(res.messages, expected(alice));
+
+        // only alice can register callers
+        let update_callers = ExecuteMsg::UpdateCallers {
+            to_add: vec![bob.to_string()],
+            to_remove: vec![],
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(bob, &[]),
+            update_callers.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(alice, &[]),
+            update_callers,
+        )
+        .unwrap();
+        let callers = query_callers(deps.as_ref()).unwrap();
+        assert_eq!(callers.callers, vec![bob.to_string()]);
+
+        // a registered caller sends in its own namespace
+        let info = mock_info(bob, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, execute_msg.clone()).unwrap();
+        assert_eq!(res.messages, expected(bob));
+
+        let remove_bob = ExecuteMsg::UpdateCallers {
+            to_add: vec![],
+            to_remove: vec![bob.to_string()],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(alice, &[]), remove_bob).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info(bob, &[]), execute_msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
     }
 
     #[test]
@@ -276,6 +420,7 @@ mod tests {
         // instantiate the contract
         let instantiate_msg = InstantiateMsg {
             simple_ica_client: ica.to_string(),
+            result_ttl: None,
         };
         let info = mock_info(alice, &[]);
         instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
@@ -300,7 +445,7 @@ mod tests {
             msg: to_binary(&simple_ica_client::msg::ExecuteMsg::IbcQuery {
                 channel_id: channel.to_string(),
                 msgs: queries,
-                callback_id: Some(callback.to_string()),
+                callback_id: Some(format!("{}/{}", alice, callback)),
             })
             .unwrap(),
             funds: vec![],
@@ -316,16 +461,126 @@ mod tests {
         );
         let info = mock_info(ica, &[]);
         let msg = ExecuteMsg::ReceiveIcaResponse(ReceiveIcaResponseMsg {
-            id: callback.to_string(),
+            id: format!("{}/{}", alice, callback),
             msg: ack.clone(),
         });
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // now make sure we can query this
-        let data = query_result(deps.as_ref(), callback.to_string()).unwrap();
+        let data = query_result(deps.as_ref(), alice.to_string(), callback.to_string()).unwrap();
         assert_eq!(data.result, ack);
         // and show how to parse those results
         let result: IbcQueryResponse = data.result.unwrap_into();
         assert_eq!(result.results, vec![Binary::from(b"{}")]);
+        // or decode them directly from the store
+        let result: IbcQueryResponse = RESULTS
+            .load_as(deps.as_ref().storage, alice, callback)
+            .unwrap();
+        assert_eq!(result.results, vec![Binary::from(b"{}")]);
+    }
+
+    #[test]
+    fn results_are_namespaced_and_pruned() {
+        let mut deps = mock_dependencies();
+
+        let alice = "alice";
+        let ica = "simple_ica";
+
+        // results expire after one minute
+        let instantiate_msg = InstantiateMsg {
+            simple_ica_client: ica.to_string(),
+            result_ttl: Some(60),
+        };
+        let info = mock_info(alice, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        for id in ["alice/first", "alice/second", "bob/first"] {
+            let msg = ExecuteMsg::ReceiveIcaResponse(ReceiveIcaResponseMsg {
+                id: id.to_string(),
+                msg: StdAck::Error("failed".to_string()),
+            });
+            execute(deps.as_mut(), mock_env(), mock_info(ica, &[]), msg).unwrap();
+        }
+
+        // results are paged per namespace
+        let page = query_results(deps.as_ref(), alice.to_string(), None, None).unwrap();
+        assert_eq!(page.results.len(), 2);
+        assert_eq!(page.results[0].0, "first");
+        let page = query_results(
+            deps.as_ref(),
+            alice.to_string(),
+            Some("first".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.results[0].0, "second");
+
+        // errors can't be decoded
+        RESULTS
+            .load_as::<IbcQueryResponse>(deps.as_ref().storage, "bob", "first")
+            .unwrap_err();
+
+        // nothing is pruned before the results expire
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::PruneResults { limit: None };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        let page = query_results(deps.as_ref(), "bob".to_string(), None, None).unwrap();
+        assert_eq!(page.results.len(), 1);
+
+        // but everything is pruned after
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        let page = query_results(deps.as_ref(), alice.to_string(), None, None).unwrap();
+        assert!(page.results.is_empty());
+        let page = query_results(deps.as_ref(), "bob".to_string(), None, None).unwrap();
+        assert!(page.results.is_empty());
+    }
+
+    #[test]
+    fn migrate_moves_results_to_the_admin_namespace() {
+        let mut deps = mock_dependencies();
+
+        let alice = "alice";
+        let ica = "simple_ica";
+
+        let instantiate_msg = InstantiateMsg {
+            simple_ica_client: ica.to_string(),
+            result_ttl: None,
+        };
+        let info = mock_info(alice, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        // results of the previous version are keyed by callback id only
+        let ack = StdAck::Error("failed".to_string());
+        for id in ["first", "second"] {
+            LEGACY_RESULTS.save(&mut deps.storage, id, &ack).unwrap();
+        }
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+        let page = query_results(deps.as_ref(), alice.to_string(), None, None).unwrap();
+        assert_eq!(
+            page.results
+                .iter()
+                .map(|(id, result)| (id.as_str(), &result.result))
+                .collect::<Vec<_>>(),
+            vec![("first", &ack), ("second", &ack)]
+        );
+        assert_eq!(
+            LEGACY_RESULTS
+                .keys(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            0
+        );
+
+        // callbacks of requests sent before the migration also end up with the admin
+        let msg = ExecuteMsg::ReceiveIcaResponse(ReceiveIcaResponseMsg {
+            id: "third".to_string(),
+            msg: ack.clone(),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info(ica, &[]), msg).unwrap();
+        let data = query_result(deps.as_ref(), alice.to_string(), "third".to_string()).unwrap();
+        assert_eq!(data.result, ack);
     }
 }
@@ -4,3 +4,4 @@ pub mod msg;
 pub mod state;
 
 pub use crate::error::ContractError;
+pub use simple_ica::result_store::{AsyncResultStore, StoredResult};
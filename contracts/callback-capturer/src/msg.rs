@@ -2,12 +2,17 @@ use client_osmo_bindings::{OsmosisMsg, OsmosisQuery};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CosmosMsg, QueryRequest};
+use cosmwasm_std::{CosmosMsg, QueryRequest, Timestamp};
 use simple_ica::{ReceiveIcaResponseMsg, StdAck};
 
+use crate::StoredResult;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub simple_ica_client: String,
+    /// Seconds after which a received result can be pruned.
+    /// Results are kept forever if not set.
+    pub result_ttl: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -41,17 +46,38 @@ pub enum ExecuteMsg {
         transfer_channel_id: String,
     },
     ReceiveIcaResponse(ReceiveIcaResponseMsg),
+    /// Remove up to `limit` expired results. Callable by anyone.
+    PruneResults {
+        limit: Option<u32>,
+    },
+    /// Allow or disallow callers to send messages and queries. Only callable by the admin.
+    UpdateCallers {
+        to_add: Vec<String>,
+        to_remove: Vec<String>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     /// Shows admin
     Admin {},
-    // Get result for the given callback id
+    /// Shows the callers that can send requests besides the admin
+    Callers {},
+    // Get result for the given callback id in the namespace of the requester
     Result {
+        namespace: String,
         id: String,
     },
+    // Page over all results in the namespace of a requester
+    Results {
+        namespace: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -59,7 +85,30 @@ pub struct AdminResponse {
     pub admin: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CallersResponse {
+    pub callers: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ResultResponse {
     pub result: StdAck,
+    pub received_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ResultsResponse {
+    /// (callback id, result)
+    pub results: Vec<(String, ResultResponse)>,
+}
+
+impl From<StoredResult> for ResultResponse {
+    fn from(stored: StoredResult) -> Self {
+        Self {
+            result: stored.ack,
+            received_at: stored.received_at,
+            expires_at: stored.expires_at,
+        }
+    }
 }
@@ -1,12 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Empty};
 use cw_storage_plus::{Item, Map};
-use simple_ica::StdAck;
+use simple_ica::{AsyncResultStore, StdAck};
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const RESULTS: Map<&str, StdAck> = Map::new("results");
+/// Contracts that can send requests besides the admin, their results are stored in their own namespace
+pub const CALLERS: Map<&Addr, Empty> = Map::new("callers");
+pub const RESULTS: AsyncResultStore =
+    AsyncResultStore::new("async_results", "results_ttl", "result_expiries");
+/// Results stored before they were namespaced, keyed by callback id. Moved to [`RESULTS`] on migration.
+pub const LEGACY_RESULTS: Map<&str, StdAck> = Map::new("results");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
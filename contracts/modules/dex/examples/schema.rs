@@ -3,7 +3,10 @@ use std::fs::create_dir_all;
 
 use abstract_os::{
     api::{ApiConfigResponse, ExecuteMsg, TradersResponse},
-    dex::{ApiQueryMsg, RequestMsg, SimulateSwapResponse},
+    dex::{
+        ApiQueryMsg, LpTokenValueResponse, PoolInfoResponse, RequestMsg, SimulateSwapResponse,
+        SpotPriceResponse,
+    },
 };
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
@@ -16,6 +19,9 @@ fn main() {
     export_schema_with_title(&schema_for!(ApiQueryMsg), &out_dir, "QueryMsg");
 
     export_schema_with_title(&schema_for!(SimulateSwapResponse), &out_dir, "ApiResponse");
+    export_schema(&schema_for!(PoolInfoResponse), &out_dir);
+    export_schema(&schema_for!(SpotPriceResponse), &out_dir);
+    export_schema(&schema_for!(LpTokenValueResponse), &out_dir);
 
     export_schema(&schema_for!(ExecuteMsg<RequestMsg>), &out_dir);
     export_schema_with_title(&schema_for!(TradersResponse), &out_dir, "TradersResponse");
@@ -1,14 +1,16 @@
 use abstract_sdk::MemoryOperation;
-use cosmwasm_std::{Decimal, Deps, Env, MessageInfo};
+use cosmwasm_std::{wasm_execute, Decimal, Deps, Env, MessageInfo, Uint128};
 use cw_asset::{Asset, AssetInfo};
 
 use crate::{
     contract::{DexApi, DexResult},
+    dex_trait::order_reserves,
     error::DexError,
     DEX,
 };
 use abstract_os::{
-    dex::OfferAsset,
+    api::{ApiRequestMsg, ExecuteMsg},
+    dex::{OfferAsset, RequestMsg},
     objects::{AssetEntry, UncheckedContractEntry},
 };
 
@@ -23,6 +25,9 @@ pub use crate::exchanges::osmosis_router::{OsmosisRouter, OSMOSISROUTER};
 #[cfg(feature = "terra")]
 pub use crate::exchanges::terraswap::{Terraswap, TERRASWAP};
 
+/// Max deviation of the pool price from the belief price of a symmetric liquidity provision, when not specified
+const DEFAULT_MAX_SPREAD: Decimal = Decimal::permille(5);
+
 pub(crate) fn resolve_exchange(value: String) -> Result<&'static dyn DEX, DexError> {
     match value.as_str() {
         // #[cfg(feature = "juno")]
@@ -57,19 +62,103 @@ pub fn swap(
     let ask_asset_info = api.resolve(deps, &ask_asset)?;
 
     let pair_address = exchange.pair_address(deps, &api, &mut vec![&offer_asset, &ask_asset])?;
-    let offer_asset: Asset = Asset::new(offer_asset_info, offer_amount);
+    let offer = (&offer_asset, Asset::new(offer_asset_info, offer_amount));
 
-    exchange.swap(
+    exchange.swap_entries(
         deps,
         api,
         pair_address,
-        offer_asset,
-        ask_asset_info,
+        offer,
+        (&ask_asset, ask_asset_info),
         belief_price,
         max_spread,
     )
 }
 
+/// Swaps the offer asset for the first asset of the path.
+/// The remaining hops are requested from the API itself, so each of them swaps the amount that the previous hop returned.
+#[allow(clippy::too_many_arguments)]
+pub fn route_swap(
+    deps: Deps,
+    env: Env,
+    _info: MessageInfo,
+    api: DexApi,
+    offer_asset: OfferAsset,
+    mut path: Vec<AssetEntry>,
+    dex: String,
+    max_spread: Option<Decimal>,
+) -> DexResult {
+    let exchange = resolve_exchange(dex.clone())?;
+    let (mut offer_entry, offer_amount) = offer_asset;
+    offer_entry.format();
+    let mut ask_entry = path.remove(0);
+    ask_entry.format();
+    let offer_asset_info = api.resolve(deps, &offer_entry)?;
+    let ask_asset_info = api.resolve(deps, &ask_entry)?;
+    let pair_address = exchange.pair_address(deps, &api, &mut vec![&offer_entry, &ask_entry])?;
+
+    let proxy = api.target()?.clone();
+    let balance_before = ask_asset_info.query_balance(&deps.querier, &proxy)?;
+    let response = exchange.swap_entries(
+        deps,
+        api,
+        pair_address,
+        (&offer_entry, Asset::new(offer_asset_info, offer_amount)),
+        (&ask_entry, ask_asset_info),
+        None,
+        max_spread,
+    )?;
+    if path.is_empty() {
+        return Ok(response);
+    }
+    let next_hop = ApiRequestMsg::new(
+        Some(proxy.into_string()),
+        RequestMsg::RouteSwapHop {
+            offer_asset: ask_entry,
+            balance_before,
+            path,
+            dex,
+            max_spread,
+        },
+    );
+    Ok(response.add_message(wasm_execute(
+        env.contract.address,
+        &ExecuteMsg::from(next_hop),
+        vec![],
+    )?))
+}
+
+/// Continues a route swap with the amount of `offer_entry` that the previous hop returned to the proxy.
+#[allow(clippy::too_many_arguments)]
+pub fn route_swap_hop(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    api: DexApi,
+    offer_entry: AssetEntry,
+    balance_before: Uint128,
+    path: Vec<AssetEntry>,
+    dex: String,
+    max_spread: Option<Decimal>,
+) -> DexResult {
+    let offer_asset_info = api.resolve(deps, &offer_entry)?;
+    let balance = offer_asset_info.query_balance(&deps.querier, api.target()?)?;
+    let returned = balance.saturating_sub(balance_before);
+    if returned.is_zero() {
+        return Err(DexError::NoSwapReturn(offer_entry.to_string()));
+    }
+    route_swap(
+        deps,
+        env,
+        info,
+        api,
+        (offer_entry, returned),
+        path,
+        dex,
+        max_spread,
+    )
+}
+
 pub fn provide_liquidity(
     deps: Deps,
     _env: Env,
@@ -98,6 +187,7 @@ pub fn provide_liquidity(
     exchange.provide_liquidity(deps, api, pair_address, assets, max_spread)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity_symmetric(
     deps: Deps,
     _env: Env,
@@ -106,16 +196,43 @@ pub fn provide_liquidity_symmetric(
     offer_asset: OfferAsset,
     mut paired_assets: Vec<AssetEntry>,
     dex: String,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
 ) -> DexResult {
     let exchange = resolve_exchange(dex)?;
-    let paired_asset_infos: Result<Vec<AssetInfo>, _> = paired_assets
+    let paired_asset_infos = paired_assets
         .iter()
         .map(|entry| api.resolve(deps, entry))
-        .collect();
+        .collect::<Result<Vec<AssetInfo>, _>>()?;
     paired_assets.push(offer_asset.0.clone());
     let pair_address = exchange.pair_address(deps, &api, &mut paired_assets.iter().collect())?;
     let offer_asset = Asset::new(api.resolve(deps, &offer_asset.0)?, offer_asset.1);
-    exchange.provide_liquidity_symmetric(deps, api, pair_address, offer_asset, paired_asset_infos?)
+    if let Some(belief_price) = belief_price {
+        // the paired amounts follow the pool price, so a moved pool would take the difference
+        let (pool_assets, _) = exchange.pool_reserves(deps, pair_address.clone())?;
+        let reserves = order_reserves(
+            &pool_assets,
+            &[offer_asset.info.clone(), paired_asset_infos[0].clone()],
+        )?;
+        if reserves[0].is_zero() {
+            return Err(DexError::EmptyPool(pair_address.into_string()));
+        }
+        let price = Decimal::from_ratio(reserves[1], reserves[0]);
+        let max_spread = max_spread.unwrap_or(DEFAULT_MAX_SPREAD);
+        let deviation = if price > belief_price {
+            price - belief_price
+        } else {
+            belief_price - price
+        };
+        if deviation > belief_price * max_spread {
+            return Err(DexError::MaxSpreadAssertion {
+                price,
+                belief_price,
+                max_spread,
+            });
+        }
+    }
+    exchange.provide_liquidity_symmetric(deps, api, pair_address, offer_asset, paired_asset_infos)
 }
 
 pub fn withdraw_liquidity(
@@ -125,6 +242,7 @@ pub fn withdraw_liquidity(
     api: DexApi,
     lp_token: OfferAsset,
     dex: String,
+    min_assets: Option<Vec<OfferAsset>>,
 ) -> DexResult {
     let exchange = resolve_exchange(dex.clone())?;
 
@@ -133,5 +251,26 @@ pub fn withdraw_liquidity(
     let pair_entry = UncheckedContractEntry::new(dex, lp_token.0.to_string()).check();
 
     let pair_address = api.resolve(deps, &pair_entry)?;
+    if let Some(min_assets) = min_assets {
+        let (pool_assets, lp_supply) = exchange.pool_reserves(deps, pair_address.clone())?;
+        if lp_supply.is_zero() {
+            return Err(DexError::EmptyPool(pair_address.into_string()));
+        }
+        let min_asset_infos = min_assets
+            .iter()
+            .map(|(entry, _)| api.resolve(deps, entry))
+            .collect::<Result<Vec<AssetInfo>, _>>()?;
+        let reserves = order_reserves(&pool_assets, &min_asset_infos)?;
+        for ((entry, min), reserve) in min_assets.into_iter().zip(reserves) {
+            let returned = reserve.multiply_ratio(lp_asset.amount, lp_supply);
+            if returned < min {
+                return Err(DexError::MinimumNotReturned {
+                    asset: entry.to_string(),
+                    min,
+                    returned,
+                });
+            }
+        }
+    }
     exchange.withdraw_liquidity(deps, &api, pair_address, lp_asset)
 }
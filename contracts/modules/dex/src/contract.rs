@@ -8,9 +8,12 @@ use abstract_os::{
 use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response};
 
 use crate::{
-    commands::{provide_liquidity, provide_liquidity_symmetric, swap, withdraw_liquidity},
+    commands::{
+        provide_liquidity, provide_liquidity_symmetric, route_swap, route_swap_hop, swap,
+        withdraw_liquidity,
+    },
     error::DexError,
-    queries::simulate_swap,
+    queries::{lp_token_value, pool_info, simulate_swap, spot_price},
 };
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -55,7 +58,7 @@ pub fn handle_api_request(
             dex,
             max_spread,
         } => {
-            let dex_name = dex.unwrap();
+            let dex_name = required_dex(dex)?;
             if assets.len() < 2 {
                 return Err(DexError::TooFewAssets {});
             }
@@ -65,8 +68,10 @@ pub fn handle_api_request(
             offer_asset,
             paired_assets,
             dex,
+            max_spread,
+            belief_price,
         } => {
-            let dex_name = dex.unwrap();
+            let dex_name = required_dex(dex)?;
             if paired_assets.is_empty() {
                 return Err(DexError::TooFewAssets {});
             }
@@ -78,15 +83,26 @@ pub fn handle_api_request(
                 offer_asset,
                 paired_assets,
                 dex_name,
+                max_spread,
+                belief_price,
             )
         }
         RequestMsg::WithdrawLiquidity {
             lp_token,
             amount,
             dex,
+            min_assets,
         } => {
-            let dex_name = dex.unwrap();
-            withdraw_liquidity(deps.as_ref(), env, info, api, (lp_token, amount), dex_name)
+            let dex_name = required_dex(dex)?;
+            withdraw_liquidity(
+                deps.as_ref(),
+                env,
+                info,
+                api,
+                (lp_token, amount),
+                dex_name,
+                min_assets,
+            )
         }
 
         RequestMsg::Swap {
@@ -97,7 +113,7 @@ pub fn handle_api_request(
             belief_price,
         } => {
             // add default dex in future (osmosis??)
-            let dex_name = dex.unwrap();
+            let dex_name = required_dex(dex)?;
             swap(
                 deps.as_ref(),
                 env,
@@ -110,6 +126,52 @@ pub fn handle_api_request(
                 belief_price,
             )
         }
+        RequestMsg::RouteSwap {
+            offer_asset,
+            path,
+            dex,
+            max_spread,
+        } => {
+            let dex_name = required_dex(dex)?;
+            if path.is_empty() {
+                return Err(DexError::EmptyRoute {});
+            }
+            route_swap(
+                deps.as_ref(),
+                env,
+                info,
+                api,
+                offer_asset,
+                path,
+                dex_name,
+                max_spread,
+            )
+        }
+        RequestMsg::RouteSwapHop {
+            offer_asset,
+            balance_before,
+            path,
+            dex,
+            max_spread,
+        } => {
+            if info.sender != env.contract.address {
+                return Err(DexError::UnauthorizedRouteSwapHop {});
+            }
+            if path.is_empty() {
+                return Err(DexError::EmptyRoute {});
+            }
+            route_swap_hop(
+                deps.as_ref(),
+                env,
+                info,
+                api,
+                offer_asset,
+                balance_before,
+                path,
+                dex,
+                max_spread,
+            )
+        }
     }
 }
 
@@ -124,6 +186,20 @@ fn query_handler(deps: Deps, env: Env, msg: ApiQueryMsg) -> Result<Binary, DexEr
             offer_asset,
             ask_asset,
             dex,
-        } => simulate_swap(deps, env, offer_asset, ask_asset, dex.unwrap()),
+        } => simulate_swap(deps, env, offer_asset, ask_asset, required_dex(dex)?),
+        ApiQueryMsg::PoolInfo { assets, dex } => pool_info(deps, env, assets, required_dex(dex)?),
+        ApiQueryMsg::SpotPrice {
+            offer_asset,
+            ask_asset,
+            dex,
+        } => spot_price(deps, env, offer_asset, ask_asset, required_dex(dex)?),
+        ApiQueryMsg::LpTokenValue { lp_token, dex } => {
+            lp_token_value(deps, env, lp_token, required_dex(dex)?)
+        }
     }
 }
+
+/// The dex of a request or query, there's no default dex yet
+fn required_dex(dex: Option<String>) -> Result<String, DexError> {
+    dex.ok_or(DexError::MissingDex {})
+}
@@ -12,6 +12,9 @@ type Return = Uint128;
 type Spread = Uint128;
 type Fee = Uint128;
 type FeeOnInput = bool;
+type Reserves = Vec<Uint128>;
+type LpSupply = Uint128;
+type FeeTier = Decimal;
 /// DEX trait resolves asset names and dex to pair and lp address and ensures supported dexes support swaps and liquidity provisioning.
 pub trait DEX {
     fn pair_address(
@@ -38,6 +41,29 @@ pub trait DEX {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
     ) -> DexResult;
+    /// Swap with the names of the offered and asked assets.
+    /// Exchanges on another chain override this to look up the denoms of the assets on that chain.
+    #[allow(clippy::too_many_arguments)]
+    fn swap_entries(
+        &self,
+        deps: Deps,
+        api: DexApi,
+        pair_address: Addr,
+        offer: (&AssetEntry, Asset),
+        ask: (&AssetEntry, AssetInfo),
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+    ) -> DexResult {
+        self.swap(
+            deps,
+            api,
+            pair_address,
+            offer.1,
+            ask.1,
+            belief_price,
+            max_spread,
+        )
+    }
     fn provide_liquidity(
         &self,
         deps: Deps,
@@ -73,4 +99,51 @@ pub trait DEX {
         offer_asset: Asset,
         ask_asset: AssetInfo,
     ) -> Result<(Return, Spread, Fee, FeeOnInput), DexError>;
+    /// Assets held by the pair, as queried from the pair, and the total LP token supply.
+    fn pool_reserves(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+    ) -> Result<(Vec<Asset>, LpSupply), DexError>;
+    /// Fee that the pair charges on swaps, derived from a simulation on the pair.
+    fn fee_tier(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+        reserves: &[Asset],
+    ) -> Result<FeeTier, DexError>;
+    /// Pool reserves (ordered like `assets`), total LP token supply and swap fee of the pair.
+    fn pool_info(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+        assets: &[AssetInfo],
+    ) -> Result<(Reserves, LpSupply, FeeTier), DexError> {
+        let (pool_assets, lp_supply) = self.pool_reserves(deps, pair_address.clone())?;
+        let reserves = order_reserves(&pool_assets, assets)?;
+        let fee = self.fee_tier(deps, pair_address, &pool_assets)?;
+        Ok((reserves, lp_supply, fee))
+    }
+}
+
+/// Amounts of `pool_assets`, ordered like `assets`.
+pub(crate) fn order_reserves(
+    pool_assets: &[Asset],
+    assets: &[AssetInfo],
+) -> Result<Reserves, DexError> {
+    assets
+        .iter()
+        .map(|info| {
+            pool_assets
+                .iter()
+                .find(|pool_asset| &pool_asset.info == info)
+                .map(|pool_asset| pool_asset.amount)
+                .ok_or_else(|| {
+                    DexError::ArgumentMismatch(
+                        info.to_string(),
+                        pool_assets.iter().map(|a| a.info.to_string()).collect(),
+                    )
+                })
+        })
+        .collect()
 }
@@ -1,5 +1,5 @@
 use abstract_api::ApiError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -13,12 +13,38 @@ pub enum DexError {
     #[error("DEX {0} is not a known dex on this network.")]
     UnknownDex(String),
 
+    #[error("No DEX specified, the API has no default DEX")]
+    MissingDex {},
+
     #[error("Cw1155 is unsupported.")]
     Cw1155Unsupported,
 
     #[error("Can't provide liquidity less than two assets")]
     TooFewAssets {},
 
+    #[error("A route swap requires at least one asset in its path")]
+    EmptyRoute {},
+
+    #[error("Only the dex API can continue a route swap")]
+    UnauthorizedRouteSwapHop {},
+
+    #[error("Pool price {price} is more than {max_spread} away from belief price {belief_price}")]
+    MaxSpreadAssertion {
+        price: Decimal,
+        belief_price: Decimal,
+        max_spread: Decimal,
+    },
+
+    #[error("Withdrawing liquidity returns {returned} {asset}, less than the minimum of {min}")]
+    MinimumNotReturned {
+        asset: String,
+        min: Uint128,
+        returned: Uint128,
+    },
+
+    #[error("The previous hop of the route swap returned no {0}")]
+    NoSwapReturn(String),
+
     #[error("Can't provide liquidity with more than {0} assets")]
     TooManyAssets(u8),
 
@@ -27,4 +53,16 @@ pub enum DexError {
 
     #[error("Pair {0} on DEX {1} does not match with pair address {2}")]
     DexMismatch(String, String, String),
+
+    #[error("DEX {0} needs the names of the swapped assets")]
+    AssetNamesRequired(String),
+
+    #[error("DEX {0} does not support this query")]
+    UnsupportedQuery(String),
+
+    #[error("Pool {0} has no liquidity")]
+    EmptyPool(String),
+
+    #[error("{0} is not an LP token minted by a pair")]
+    NotLpToken(String),
 }
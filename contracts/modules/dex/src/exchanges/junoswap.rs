@@ -288,6 +288,67 @@ impl DEX for JunoSwap {
             };
         Ok((return_amount, spread_amount, Uint128::zero(), true))
     }
+
+    fn pool_reserves(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+    ) -> Result<(Vec<Asset>, Uint128), DexError> {
+        let pair_config: InfoResponse =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: pair_address.to_string(),
+                msg: to_binary(&QueryMsg::Info {})?,
+            }))?;
+        let assets = vec![
+            Asset::new(
+                denom_to_asset_info(&pair_config.token1_denom),
+                pair_config.token1_reserve,
+            ),
+            Asset::new(
+                denom_to_asset_info(&pair_config.token2_denom),
+                pair_config.token2_reserve,
+            ),
+        ];
+        Ok((assets, pair_config.lp_token_supply))
+    }
+
+    fn fee_tier(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+        reserves: &[Asset],
+    ) -> Result<Decimal, DexError> {
+        let (token1_reserve, token2_reserve) = match reserves {
+            [token1, token2] if !token1.amount.is_zero() && !token2.amount.is_zero() => {
+                (token1.amount, token2.amount)
+            }
+            _ => return Err(DexError::EmptyPool(pair_address.to_string())),
+        };
+        // The fee is taken from the input: out = r2 * in' / (r1 + in') with in' = in * (1 - fee).
+        // Offering r1 gives in' / r1 = out / (r2 - out).
+        let sim_resp: Token1ForToken2PriceResponse = deps.querier.query_wasm_smart(
+            pair_address.as_str(),
+            &QueryMsg::Token1ForToken2Price {
+                token1_amount: token1_reserve,
+            },
+        )?;
+        let out = sim_resp.token2_amount;
+        if out >= token2_reserve {
+            return Err(DexError::EmptyPool(pair_address.to_string()));
+        }
+        let kept = Decimal::from_ratio(out, token2_reserve - out);
+        if kept >= Decimal::one() {
+            return Ok(Decimal::zero());
+        }
+        Ok(Decimal::one() - kept)
+    }
+}
+
+fn denom_to_asset_info(denom: &Denom) -> AssetInfo {
+    match denom {
+        Denom::Native(denom) => AssetInfo::native(denom),
+        Denom::Cw20(addr) => AssetInfo::cw20(addr.clone()),
+    }
 }
 
 fn denom_and_asset_match(denom: &Denom, asset: &AssetInfo) -> Result<bool, DexError> {
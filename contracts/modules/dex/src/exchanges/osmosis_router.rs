@@ -4,19 +4,21 @@ use crate::{
     DEX,
 };
 
-use abstract_os::{
-    manager::state::OS_MODULES,
-    objects::{AssetEntry, ContractEntry},
-    PROXY,
-};
-use abstract_sdk::{MemoryOperation, OsExecute, Resolve};
+use abstract_os::{manager::state::OS_MODULES, objects::AssetEntry, PROXY};
+use abstract_sdk::{MemoryOperation, OsExecute};
 use client_osmo_bindings::{OsmosisMsg, SwapAmountWithLimit};
-use cosmwasm_std::{wasm_execute, Coin, CosmosMsg, Decimal, Response, Uint128, Addr};
+use cosmwasm_std::{wasm_execute, Addr, Coin, CosmosMsg, Decimal, Response, Uint128};
 use cw_asset::{Asset, AssetInfo};
-use simple_ica_client::msg::ExecuteMsg as ProxyExecute;
+use simple_ica_client::{
+    commands::{host_denoms, parse_pool_id, HOST_CHAIN},
+    msg::ExecuteMsg as ProxyExecute,
+};
+use std::collections::BTreeSet;
 // use simple_ica::osmosis_router_msg::ExecuteMsg;
 
 pub const OSMOSISROUTER: &str = "osmosisrouter";
+/// Chain that the dex API runs on, funds are sent from here to [`HOST_CHAIN`] to be swapped.
+const LOCAL_CHAIN: &str = "juno";
 pub struct OsmosisRouter {}
 
 impl DEX for OsmosisRouter {
@@ -25,15 +27,31 @@ impl DEX for OsmosisRouter {
     }
 
     fn swap(
+        &self,
+        _deps: cosmwasm_std::Deps,
+        _api: DexApi,
+        _pair_address: Addr,
+        _offer_asset: cw_asset::Asset,
+        _ask_asset: cw_asset::AssetInfo,
+        _belief_price: Option<cosmwasm_std::Decimal>,
+        _max_spread: Option<cosmwasm_std::Decimal>,
+    ) -> DexResult {
+        // The denoms on osmosis are looked up by asset name.
+        Err(DexError::AssetNamesRequired(self.name().into()))
+    }
+
+    fn swap_entries(
         &self,
         deps: cosmwasm_std::Deps,
         api: DexApi,
         pair_address: Addr,
-        offer_asset: cw_asset::Asset,
-        ask_asset: cw_asset::AssetInfo,
-        belief_price: Option<cosmwasm_std::Decimal>,
-        max_spread: Option<cosmwasm_std::Decimal>,
+        offer: (&AssetEntry, cw_asset::Asset),
+        ask: (&AssetEntry, cw_asset::AssetInfo),
+        _belief_price: Option<cosmwasm_std::Decimal>,
+        _max_spread: Option<cosmwasm_std::Decimal>,
     ) -> DexResult {
+        let (offer_entry, offer_asset) = offer;
+        let (ask_entry, _) = ask;
         let input_coin = match &offer_asset.info {
             cw_asset::AssetInfoBase::Native(denom) => {
                 Ok(Coin::new(offer_asset.amount.u128(), denom))
@@ -42,31 +60,16 @@ impl DEX for OsmosisRouter {
         }?;
 
         let memory = api.load_memory(deps.storage)?;
-
-        let (denom_in, denom_out) = if input_coin.denom == "ujunox" {
-            (
-                memory.query_asset(deps, &AssetEntry::new(&format!("osmo>junox")))?,
-                memory.query_asset(deps, &AssetEntry::new(&format!("osmo>osmo")))?,
-            )
-        } else {
-            (
-                memory.query_asset(deps, &AssetEntry::new(&format!("osmo>osmo")))?,
-                memory.query_asset(deps, &AssetEntry::new(&format!("osmo>junox")))?,
-            )
-        };
-
-        let output_denom = match &denom_out {
-            cw_asset::AssetInfoBase::Native(denom) => Ok(denom.to_string()),
-            _ => Err(DexError::Cw1155Unsupported),
-        }?;
-
-        let input_denom = match &denom_in {
-            cw_asset::AssetInfoBase::Native(denom) => Ok(denom.to_string()),
-            _ => Err(DexError::Cw1155Unsupported),
-        }?;
+        let denoms = host_denoms(
+            deps,
+            &memory,
+            BTreeSet::from([offer_entry.to_string(), ask_entry.to_string()]),
+        )?;
+        let input_denom = denoms[offer_entry.as_str()].clone();
+        let output_denom = denoms[ask_entry.as_str()].clone();
 
         let swap_msg = CosmosMsg::Custom(OsmosisMsg::simple_swap(
-            pair_address.to_string().parse().unwrap(),
+            parse_pool_id(&pair_address)?,
             input_denom,
             output_denom,
             SwapAmountWithLimit::ExactIn {
@@ -83,12 +86,9 @@ impl DEX for OsmosisRouter {
         let swap_msg = wasm_execute(proxy_addr.clone(), &proxy_msg, vec![])?;
 
         // send over transfer channel
-        let transfer_channel_id = ContractEntry {
-            protocol: "hermes".to_string(),
-            contract: "juno>osmo".to_string(),
-        }
-        .resolve(deps, &memory)?
-        .to_string();
+        let transfer_channel_id = memory
+            .query_channel(deps, LOCAL_CHAIN, HOST_CHAIN)?
+            .channel_id;
         // send msg
         let proxy_msg = ProxyExecute::SendFunds {
             transfer_channel_id,
@@ -140,6 +140,24 @@ impl DEX for OsmosisRouter {
     ) -> std::result::Result<(Uint128, Uint128, Uint128, bool), DexError> {
         todo!()
     }
+
+    fn pool_reserves(
+        &self,
+        _deps: cosmwasm_std::Deps,
+        _pair_address: cosmwasm_std::Addr,
+    ) -> std::result::Result<(Vec<Asset>, Uint128), DexError> {
+        // Osmosis pools live on a remote chain and can't be queried synchronously.
+        Err(DexError::UnsupportedQuery(self.name().into()))
+    }
+
+    fn fee_tier(
+        &self,
+        _deps: cosmwasm_std::Deps,
+        _pair_address: cosmwasm_std::Addr,
+        _reserves: &[Asset],
+    ) -> std::result::Result<Decimal, DexError> {
+        Err(DexError::UnsupportedQuery(self.name().into()))
+    }
 }
 
 fn coins_in_assets(assets: &[Asset]) -> Vec<Coin> {
@@ -6,7 +6,7 @@ use crate::{
 
 use abstract_sdk::OsExecute;
 use cosmwasm_std::{
-    to_binary, wasm_execute, Addr, Coin, CosmosMsg, Decimal, Deps, QueryRequest, StdResult,
+    to_binary, wasm_execute, Addr, Api, Coin, CosmosMsg, Decimal, Deps, QueryRequest, StdResult,
     Uint128, WasmMsg, WasmQuery,
 };
 use cw20::Cw20ExecuteMsg;
@@ -200,6 +200,53 @@ impl DEX for Terraswap {
         // commission paid in result asset
         Ok((return_amount, spread_amount, commission_amount, false))
     }
+
+    fn pool_reserves(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+    ) -> Result<(Vec<Asset>, Uint128), DexError> {
+        let pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pair_address.to_string(),
+            msg: to_binary(&terraswap::pair::QueryMsg::Pool {})?,
+        }))?;
+        let assets = pool
+            .assets
+            .iter()
+            .map(|asset| terraswap_to_cw_asset(deps.api, asset))
+            .collect::<StdResult<Vec<Asset>>>()?;
+        Ok((assets, pool.total_share))
+    }
+
+    fn fee_tier(
+        &self,
+        deps: Deps,
+        pair_address: Addr,
+        reserves: &[Asset],
+    ) -> Result<Decimal, DexError> {
+        // The commission is taken from the return, so simulating a swap of the full reserve
+        // gives the commission rate with a negligible rounding error.
+        let offer = reserves
+            .first()
+            .filter(|reserve| !reserve.amount.is_zero())
+            .ok_or_else(|| DexError::EmptyPool(pair_address.to_string()))?;
+        let (return_amount, _, commission_amount, _) =
+            self.simulate_swap(deps, pair_address, offer.clone(), offer.info.clone())?;
+        Ok(Decimal::from_ratio(
+            commission_amount,
+            return_amount + commission_amount,
+        ))
+    }
+}
+
+fn terraswap_to_cw_asset(api: &dyn Api, asset: &terraswap::asset::Asset) -> StdResult<Asset> {
+    let info = match &asset.info {
+        terraswap::asset::AssetInfo::NativeToken { denom } => AssetInfo::native(denom),
+        terraswap::asset::AssetInfo::Token { contract_addr } => {
+            AssetInfo::cw20(api.addr_validate(contract_addr)?)
+        }
+    };
+    Ok(Asset::new(info, asset.amount))
 }
 
 fn cw_asset_to_terraswap(asset: &Asset) -> Result<terraswap::asset::Asset, DexError> {
@@ -7,7 +7,6 @@ pub(crate) mod queries;
 
 pub use dex_trait::DEX;
 
-// TODO: FIX
-// #[cfg(test)]
-// #[cfg(not(target_arch = "wasm32"))]
-// mod tests;
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests;
@@ -1,12 +1,17 @@
 use abstract_os::{
-    dex::{OfferAsset, SimulateSwapResponse},
-    objects::AssetEntry,
+    dex::{
+        LpTokenValueResponse, OfferAsset, PoolInfoResponse, SimulateSwapResponse, SpotPriceResponse,
+    },
+    objects::{AssetEntry, ContractEntry},
 };
 use abstract_sdk::MemoryOperation;
-use cosmwasm_std::{to_binary, Binary, Deps, Env};
-use cw_asset::Asset;
+use cosmwasm_std::{to_binary, Addr, Binary, Decimal, Deps, Env};
+use cw20::{Cw20QueryMsg, MinterResponse};
+use cw_asset::{Asset, AssetInfo};
 
-use crate::{commands::resolve_exchange, contract::DexApi, error::DexError};
+use crate::{
+    commands::resolve_exchange, contract::DexApi, dex_trait::order_reserves, error::DexError, DEX,
+};
 
 pub fn simulate_swap(
     deps: Deps,
@@ -39,3 +44,115 @@ pub fn simulate_swap(
     };
     to_binary(&resp).map_err(From::from)
 }
+
+pub fn pool_info(
+    deps: Deps,
+    _env: Env,
+    mut assets: Vec<AssetEntry>,
+    dex: String,
+) -> Result<Binary, DexError> {
+    to_binary(&query_pool_info(deps, &mut assets, dex)?).map_err(From::from)
+}
+
+pub fn spot_price(
+    deps: Deps,
+    _env: Env,
+    offer_asset: AssetEntry,
+    ask_asset: AssetEntry,
+    dex: String,
+) -> Result<Binary, DexError> {
+    let exchange = resolve_exchange(dex)?;
+    let (pool, pair_address, asset_infos) =
+        resolve_pair(deps, exchange, &mut [offer_asset, ask_asset])?;
+    // only the reserves are needed, the fee would take a simulation on the pair
+    let (pool_assets, _) = exchange.pool_reserves(deps, pair_address)?;
+    let reserves = order_reserves(&pool_assets, &asset_infos)?;
+    let (offer_reserve, ask_reserve) = (reserves[0], reserves[1]);
+    if offer_reserve.is_zero() {
+        return Err(DexError::EmptyPool(pool.to_string()));
+    }
+    // ask / offer
+    let price = Decimal::from_ratio(ask_reserve, offer_reserve);
+    to_binary(&SpotPriceResponse { pool, price }).map_err(From::from)
+}
+
+pub fn lp_token_value(
+    deps: Deps,
+    _env: Env,
+    lp_token: OfferAsset,
+    dex: String,
+) -> Result<Binary, DexError> {
+    let exchange = resolve_exchange(dex)?;
+    let api = DexApi::default();
+    let (mut lp_token, amount) = lp_token;
+    lp_token.format();
+    let lp_token_info = api.resolve(deps, &lp_token)?;
+    let pair_address = lp_token_pair(deps, &lp_token_info)?;
+    let (reserves, total_lp_supply) = exchange.pool_reserves(deps, pair_address.clone())?;
+    if total_lp_supply.is_zero() {
+        return Err(DexError::EmptyPool(pair_address.to_string()));
+    }
+    let assets = reserves
+        .into_iter()
+        .map(|reserve| {
+            Asset::new(
+                reserve.info,
+                reserve.amount.multiply_ratio(amount, total_lp_supply),
+            )
+        })
+        .collect();
+    to_binary(&LpTokenValueResponse {
+        pair_address,
+        assets,
+    })
+    .map_err(From::from)
+}
+
+/// The pair that minted the LP token
+fn lp_token_pair(deps: Deps, lp_token: &AssetInfo) -> Result<Addr, DexError> {
+    let not_lp_token = || DexError::NotLpToken(lp_token.to_string());
+    let token_addr = match lp_token {
+        AssetInfo::Cw20(addr) => addr,
+        _ => return Err(not_lp_token()),
+    };
+    let minter: Option<MinterResponse> = deps
+        .querier
+        .query_wasm_smart(token_addr, &Cw20QueryMsg::Minter {})?;
+    let minter = minter.ok_or_else(not_lp_token)?;
+    Ok(deps.api.addr_validate(&minter.minter)?)
+}
+
+/// Resolves the pair of the provided assets and queries its state through the exchange adapter.
+/// Reserves are returned in the same order as the provided assets.
+pub(crate) fn query_pool_info(
+    deps: Deps,
+    assets: &mut [AssetEntry],
+    dex: String,
+) -> Result<PoolInfoResponse, DexError> {
+    let exchange = resolve_exchange(dex)?;
+    let (pool, pair_address, asset_infos) = resolve_pair(deps, exchange, assets)?;
+    let (reserves, total_lp_supply, fee) = exchange.pool_info(deps, pair_address, &asset_infos)?;
+    Ok(PoolInfoResponse {
+        pool,
+        reserves: assets.iter().cloned().zip(reserves).collect(),
+        total_lp_supply,
+        fee,
+    })
+}
+
+/// Formats and resolves the assets, and resolves the pair that trades them on the exchange.
+fn resolve_pair(
+    deps: Deps,
+    exchange: &dyn DEX,
+    assets: &mut [AssetEntry],
+) -> Result<(ContractEntry, Addr, Vec<AssetInfo>), DexError> {
+    let api = DexApi::default();
+    assets.iter_mut().for_each(AssetEntry::format);
+    let asset_infos = assets
+        .iter()
+        .map(|entry| api.resolve(deps, entry))
+        .collect::<Result<Vec<AssetInfo>, _>>()?;
+    let pair_address = exchange.pair_address(deps, &api, &mut assets.iter().collect())?;
+    let pool = exchange.pair_contract(&mut assets.iter().collect());
+    Ok((pool, pair_address, asset_infos))
+}
@@ -0,0 +1,610 @@
+use cosmwasm_std::{
+    testing::{mock_dependencies, MockApi, MockQuerier, MockStorage},
+    Binary, ContractResult, Decimal, OwnedDeps, SystemResult, Uint128, WasmQuery,
+};
+use cw_asset::{Asset, AssetInfo};
+
+use crate::{error::DexError, DEX};
+
+const PAIR: &str = "pair";
+const LP_SUPPLY: u128 = 1_500;
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+/// Mock dependencies where every smart query to [`PAIR`] is answered by `handler`
+fn pair_deps(handler: impl Fn(&Binary) -> Binary + 'static) -> MockDeps {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == PAIR => {
+            SystemResult::Ok(ContractResult::Ok(handler(msg)))
+        }
+        _ => panic!("unexpected query {:?}", query),
+    });
+    deps
+}
+
+#[cfg(feature = "juno")]
+mod junoswap {
+    use super::*;
+    use crate::exchanges::junoswap::JunoSwap;
+    use cosmwasm_std::{from_binary, to_binary, Addr};
+    use cw20_junoswap::Denom;
+    use wasmswap::msg::{InfoResponse, QueryMsg, Token1ForToken2PriceResponse};
+
+    const TOKEN1_RESERVE: u128 = 1_000;
+    // picked so that a 0.3% fee on the input gives an exact return
+    const TOKEN2_RESERVE: u128 = 1_997_000;
+
+    /// Junoswap pair with a 0.3% fee taken from the input
+    fn deps(token1_reserve: u128) -> MockDeps {
+        pair_deps(move |msg| match from_binary(msg).unwrap() {
+            QueryMsg::Info {} => to_binary(&InfoResponse {
+                token1_reserve: token1_reserve.into(),
+                token1_denom: Denom::Native("ujuno".into()),
+                token2_reserve: TOKEN2_RESERVE.into(),
+                token2_denom: Denom::Cw20(Addr::unchecked("token")),
+                lp_token_supply: LP_SUPPLY.into(),
+                lp_token_address: "lp_token".into(),
+            })
+            .unwrap(),
+            QueryMsg::Token1ForToken2Price { token1_amount } => {
+                let input = token1_amount.u128() * 997;
+                to_binary(&Token1ForToken2PriceResponse {
+                    token2_amount: Uint128::new(
+                        TOKEN2_RESERVE * input / (token1_reserve * 1000 + input),
+                    ),
+                })
+                .unwrap()
+            }
+            _ => panic!("unexpected pair query"),
+        })
+    }
+
+    #[test]
+    fn queries_pool_reserves() {
+        let deps = deps(TOKEN1_RESERVE);
+        let (assets, supply) = JunoSwap {}
+            .pool_reserves(deps.as_ref(), Addr::unchecked(PAIR))
+            .unwrap();
+        assert_eq!(
+            assets,
+            vec![
+                Asset::native("ujuno", TOKEN1_RESERVE),
+                Asset::cw20(Addr::unchecked("token"), TOKEN2_RESERVE),
+            ]
+        );
+        assert_eq!(supply.u128(), LP_SUPPLY);
+    }
+
+    #[test]
+    fn reads_fee_from_pool() {
+        let deps = deps(TOKEN1_RESERVE);
+        let (reserves, supply, fee) = JunoSwap {}
+            .pool_info(
+                deps.as_ref(),
+                Addr::unchecked(PAIR),
+                &[
+                    AssetInfo::cw20(Addr::unchecked("token")),
+                    AssetInfo::native("ujuno"),
+                ],
+            )
+            .unwrap();
+        // reserves follow the order of the requested assets
+        assert_eq!(
+            reserves,
+            vec![Uint128::new(TOKEN2_RESERVE), Uint128::new(TOKEN1_RESERVE)]
+        );
+        assert_eq!(supply.u128(), LP_SUPPLY);
+        assert_eq!(fee, Decimal::permille(3));
+    }
+
+    #[test]
+    fn rejects_unknown_asset() {
+        let deps = deps(TOKEN1_RESERVE);
+        let err = JunoSwap {}
+            .pool_info(
+                deps.as_ref(),
+                Addr::unchecked(PAIR),
+                &[AssetInfo::native("uatom")],
+            )
+            .unwrap_err();
+        assert!(matches!(err, DexError::ArgumentMismatch(..)));
+    }
+
+    #[test]
+    fn empty_pool_has_no_fee() {
+        let deps = deps(0);
+        let err = JunoSwap {}
+            .pool_info(deps.as_ref(), Addr::unchecked(PAIR), &[])
+            .unwrap_err();
+        assert_eq!(err, DexError::EmptyPool(PAIR.into()));
+    }
+}
+
+#[cfg(any(feature = "juno", feature = "terra"))]
+mod loop_dex {
+    use super::*;
+    use crate::exchanges::loop_dex::Loop;
+    use cosmwasm_std::{from_binary, to_binary, Addr};
+    use terraswap::{
+        asset::{Asset as TerraswapAsset, AssetInfo as TerraswapAssetInfo},
+        pair::{PoolResponse, QueryMsg, SimulationResponse},
+    };
+
+    const OFFER_RESERVE: u128 = 1_000;
+    const ASK_RESERVE: u128 = 2_000;
+
+    /// Terraswap-style pair with a 0.3% commission taken from the return
+    fn deps(offer_reserve: u128) -> MockDeps {
+        pair_deps(move |msg| match from_binary(msg).unwrap() {
+            QueryMsg::Pool {} => to_binary(&PoolResponse {
+                assets: [
+                    TerraswapAsset {
+                        info: TerraswapAssetInfo::NativeToken {
+                            denom: "ujuno".into(),
+                        },
+                        amount: offer_reserve.into(),
+                    },
+                    TerraswapAsset {
+                        info: TerraswapAssetInfo::Token {
+                            contract_addr: "token".into(),
+                        },
+                        amount: ASK_RESERVE.into(),
+                    },
+                ],
+                total_share: LP_SUPPLY.into(),
+            })
+            .unwrap(),
+            QueryMsg::Simulation { offer_asset } => {
+                let offer = offer_asset.amount.u128();
+                let ideal = ASK_RESERVE * offer / (offer_reserve + offer);
+                let commission = ideal * 3 / 1000;
+                to_binary(&SimulationResponse {
+                    return_amount: (ideal - commission).into(),
+                    spread_amount: (offer * ASK_RESERVE / offer_reserve - ideal).into(),
+                    commission_amount: commission.into(),
+                })
+                .unwrap()
+            }
+            _ => panic!("unexpected pair query"),
+        })
+    }
+
+    #[test]
+    fn reads_pool_info_from_pair() {
+        let deps = deps(OFFER_RESERVE);
+        let (reserves, supply, fee) = Loop {}
+            .pool_info(
+                deps.as_ref(),
+                Addr::unchecked(PAIR),
+                &[
+                    AssetInfo::native("ujuno"),
+                    AssetInfo::cw20(Addr::unchecked("token")),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            reserves,
+            vec![Uint128::new(OFFER_RESERVE), Uint128::new(ASK_RESERVE)]
+        );
+        assert_eq!(supply.u128(), LP_SUPPLY);
+        assert_eq!(fee, Decimal::permille(3));
+    }
+
+    #[test]
+    fn empty_pool_has_no_fee() {
+        let deps = deps(0);
+        let err = Loop {}
+            .pool_info(deps.as_ref(), Addr::unchecked(PAIR), &[])
+            .unwrap_err();
+        assert_eq!(err, DexError::EmptyPool(PAIR.into()));
+    }
+}
+
+#[cfg(feature = "terra")]
+mod terraswap_dex {
+    use super::*;
+    use crate::exchanges::terraswap::Terraswap;
+    use cosmwasm_std::{from_binary, to_binary, Addr};
+    use terraswap::{
+        asset::{Asset as TerraswapAsset, AssetInfo as TerraswapAssetInfo},
+        pair::{PoolResponse, QueryMsg},
+    };
+
+    #[test]
+    fn queries_pool_reserves() {
+        let deps = pair_deps(|msg| match from_binary(msg).unwrap() {
+            QueryMsg::Pool {} => to_binary(&PoolResponse {
+                assets: [
+                    TerraswapAsset {
+                        info: TerraswapAssetInfo::NativeToken {
+                            denom: "uluna".into(),
+                        },
+                        amount: 10u128.into(),
+                    },
+                    TerraswapAsset {
+                        info: TerraswapAssetInfo::Token {
+                            contract_addr: "token".into(),
+                        },
+                        amount: 20u128.into(),
+                    },
+                ],
+                total_share: LP_SUPPLY.into(),
+            })
+            .unwrap(),
+            _ => panic!("unexpected pair query"),
+        });
+        let (assets, supply) = Terraswap {}
+            .pool_reserves(deps.as_ref(), Addr::unchecked(PAIR))
+            .unwrap();
+        assert_eq!(
+            assets,
+            vec![
+                Asset::native("uluna", 10u128),
+                Asset::cw20(Addr::unchecked("token"), 20u128),
+            ]
+        );
+        assert_eq!(supply.u128(), LP_SUPPLY);
+    }
+}
+
+#[cfg(feature = "osmosis")]
+mod osmosis {
+    use super::*;
+    use crate::exchanges::osmosis_router::OsmosisRouter;
+    use cosmwasm_std::Addr;
+
+    #[test]
+    fn pool_queries_are_unsupported() {
+        let deps = mock_dependencies();
+        let router = OsmosisRouter {};
+        let err = router
+            .pool_reserves(deps.as_ref(), Addr::unchecked(PAIR))
+            .unwrap_err();
+        assert_eq!(err, DexError::UnsupportedQuery(router.name().into()));
+        let err = router
+            .pool_info(deps.as_ref(), Addr::unchecked(PAIR), &[])
+            .unwrap_err();
+        assert_eq!(err, DexError::UnsupportedQuery(router.name().into()));
+    }
+}
+
+mod queries {
+    use super::*;
+    use crate::contract::query;
+    use abstract_os::{api::QueryMsg, dex::ApiQueryMsg, objects::AssetEntry};
+    use cosmwasm_std::testing::mock_env;
+
+    #[test]
+    fn require_a_dex() {
+        let deps = mock_dependencies();
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Api(ApiQueryMsg::SpotPrice {
+                offer_asset: AssetEntry::new("juno"),
+                ask_asset: AssetEntry::new("osmo"),
+                dex: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, DexError::MissingDex {});
+    }
+}
+
+mod route_swap {
+    use super::*;
+    use crate::contract::{handle_api_request, DexApi};
+    use abstract_api::state::ApiState;
+    use abstract_os::{
+        dex::RequestMsg,
+        memory::state::{ASSET_ADDRESSES, CONTRACT_ADDRESSES},
+        objects::{AssetEntry, UncheckedContractEntry},
+        version_control::Core,
+    };
+    use abstract_sdk::memory::Memory;
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR},
+        Addr, Order, Storage,
+    };
+    use std::collections::BTreeMap;
+
+    const MEMORY: &str = "memory";
+    const PROXY: &str = "proxy";
+    const DEX_NAME: &str = "terraswap";
+
+    /// Dex API that acts on an OS whose proxy holds 150 uosmo.
+    /// Memory knows juno, osmo and atom and the juno_osmo and atom_osmo pairs.
+    fn api_deps() -> (MockDeps, DexApi<'static>) {
+        api_deps_with_pair(|msg| panic!("unexpected pair query {:?}", msg))
+    }
+
+    /// [`api_deps`] where every smart query to the juno_osmo pair is answered by `handler`.
+    /// Memory also knows the juno_osmo LP token.
+    pub(super) fn api_deps_with_pair(
+        handler: impl Fn(&Binary) -> Binary + 'static,
+    ) -> (MockDeps, DexApi<'static>) {
+        let mut memory_storage = MockStorage::new();
+        for (name, denom) in [("juno", "ujuno"), ("osmo", "uosmo"), ("atom", "uatom")] {
+            ASSET_ADDRESSES
+                .save(&mut memory_storage, name.into(), &AssetInfo::native(denom))
+                .unwrap();
+        }
+        ASSET_ADDRESSES
+            .save(
+                &mut memory_storage,
+                "juno_osmo".into(),
+                &AssetInfo::cw20(Addr::unchecked("juno_osmo_lp")),
+            )
+            .unwrap();
+        for pair in ["juno_osmo", "atom_osmo"] {
+            CONTRACT_ADDRESSES
+                .save(
+                    &mut memory_storage,
+                    UncheckedContractEntry::new(DEX_NAME, pair).check(),
+                    &Addr::unchecked(pair),
+                )
+                .unwrap();
+        }
+        let state: BTreeMap<Vec<u8>, Vec<u8>> =
+            memory_storage.range(None, None, Order::Ascending).collect();
+
+        let mut deps = mock_dependencies();
+        deps.querier = MockQuerier::new(&[(PROXY, &[coin(150, "uosmo")])]);
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Raw { contract_addr, key } if contract_addr == MEMORY => {
+                let value = state.get(key.as_slice()).cloned().unwrap_or_default();
+                SystemResult::Ok(ContractResult::Ok(Binary(value)))
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "juno_osmo" => {
+                SystemResult::Ok(ContractResult::Ok(handler(msg)))
+            }
+            _ => panic!("unexpected query {:?}", query),
+        });
+        let mut api = DexApi::new(&[]);
+        api.base_state
+            .save(
+                &mut deps.storage,
+                &ApiState {
+                    version_control: Addr::unchecked("version_control"),
+                    memory: Memory {
+                        address: Addr::unchecked(MEMORY),
+                    },
+                },
+            )
+            .unwrap();
+        api.target_os = Some(Core {
+            manager: Addr::unchecked("manager"),
+            proxy: Addr::unchecked(PROXY),
+        });
+        (deps, api)
+    }
+
+    /// Hop from osmo to atom after the proxy held `balance_before` uosmo
+    fn hop(balance_before: u128) -> RequestMsg {
+        RequestMsg::RouteSwapHop {
+            offer_asset: AssetEntry::new("osmo"),
+            balance_before: balance_before.into(),
+            path: vec![AssetEntry::new("atom")],
+            dex: DEX_NAME.into(),
+            max_spread: None,
+        }
+    }
+
+    #[test]
+    fn only_the_api_continues_a_route() {
+        let (mut deps, api) = api_deps();
+        let err = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(PROXY, &[]),
+            api,
+            hop(100),
+        )
+        .unwrap_err();
+        assert_eq!(err, DexError::UnauthorizedRouteSwapHop {});
+    }
+
+    #[test]
+    fn hop_requires_a_return() {
+        let (mut deps, api) = api_deps();
+        let err = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            api,
+            hop(150),
+        )
+        .unwrap_err();
+        assert_eq!(err, DexError::NoSwapReturn("osmo".into()));
+    }
+
+    #[cfg(feature = "terra")]
+    #[test]
+    fn requests_the_next_hop_after_swapping() {
+        use crate::exchanges::terraswap::Terraswap;
+        use abstract_os::api::{ApiRequestMsg, ExecuteMsg};
+        use cosmwasm_std::{wasm_execute, SubMsg};
+
+        let (mut deps, api) = api_deps();
+        let res = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            api.clone(),
+            RequestMsg::RouteSwap {
+                offer_asset: (AssetEntry::new("juno"), 10u128.into()),
+                path: vec![AssetEntry::new("osmo"), AssetEntry::new("atom")],
+                dex: Some(DEX_NAME.into()),
+                max_spread: None,
+            },
+        )
+        .unwrap();
+        let swap = Terraswap {}
+            .swap(
+                deps.as_ref(),
+                api,
+                Addr::unchecked("juno_osmo"),
+                Asset::native("ujuno", 10u128),
+                AssetInfo::native("uosmo"),
+                None,
+                None,
+            )
+            .unwrap();
+        // the next hop swaps whatever the proxy holds above its current 150 uosmo
+        let next_hop = wasm_execute(
+            MOCK_CONTRACT_ADDR,
+            &ExecuteMsg::from(ApiRequestMsg::new(Some(PROXY.into()), hop(150))),
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [swap.messages, vec![SubMsg::new(next_hop)]].concat()
+        );
+    }
+
+    #[cfg(feature = "terra")]
+    #[test]
+    fn hop_swaps_the_returned_amount() {
+        use crate::exchanges::terraswap::Terraswap;
+
+        let (mut deps, api) = api_deps();
+        let res = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            api.clone(),
+            hop(100),
+        )
+        .unwrap();
+        // the previous hop returned 50 uosmo and atom ends the path
+        let swap = Terraswap {}
+            .swap(
+                deps.as_ref(),
+                api,
+                Addr::unchecked("atom_osmo"),
+                Asset::native("uosmo", 50u128),
+                AssetInfo::native("uatom"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(res, swap);
+    }
+}
+
+#[cfg(feature = "terra")]
+mod liquidity {
+    use super::*;
+    use crate::contract::handle_api_request;
+    use abstract_os::{dex::RequestMsg, objects::AssetEntry};
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_env, mock_info},
+        to_binary,
+    };
+    use terraswap::{
+        asset::{Asset as TerraswapAsset, AssetInfo as TerraswapAssetInfo},
+        pair::{PoolResponse, QueryMsg},
+    };
+
+    /// Dex API whose juno_osmo pair holds 100 ujuno and 200 uosmo for 1500 LP tokens
+    fn pool_deps() -> (MockDeps, crate::contract::DexApi<'static>) {
+        route_swap::api_deps_with_pair(|msg| match from_binary(msg).unwrap() {
+            QueryMsg::Pool {} => to_binary(&PoolResponse {
+                assets: [("ujuno", 100u128), ("uosmo", 200u128)].map(|(denom, amount)| {
+                    TerraswapAsset {
+                        info: TerraswapAssetInfo::NativeToken {
+                            denom: denom.into(),
+                        },
+                        amount: amount.into(),
+                    }
+                }),
+                total_share: LP_SUPPLY.into(),
+            })
+            .unwrap(),
+            _ => panic!("unexpected pair query"),
+        })
+    }
+
+    fn provide_symmetric(belief_price: Decimal) -> RequestMsg {
+        RequestMsg::ProvideLiquiditySymmetric {
+            offer_asset: (AssetEntry::new("juno"), 10u128.into()),
+            paired_assets: vec![AssetEntry::new("osmo")],
+            dex: Some("terraswap".into()),
+            max_spread: Some(Decimal::percent(10)),
+            belief_price: Some(belief_price),
+        }
+    }
+
+    fn withdraw(min_osmo: u128) -> RequestMsg {
+        RequestMsg::WithdrawLiquidity {
+            lp_token: AssetEntry::new("juno_osmo"),
+            amount: 150u128.into(),
+            dex: Some("terraswap".into()),
+            min_assets: Some(vec![(AssetEntry::new("osmo"), min_osmo.into())]),
+        }
+    }
+
+    #[test]
+    fn symmetric_provide_checks_the_pool_price() {
+        let (mut deps, api) = pool_deps();
+        // juno trades at 2 osmo
+        handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            api.clone(),
+            provide_symmetric(Decimal::percent(190)),
+        )
+        .unwrap();
+        let err = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            api,
+            provide_symmetric(Decimal::percent(150)),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            DexError::MaxSpreadAssertion {
+                price: Decimal::percent(200),
+                belief_price: Decimal::percent(150),
+                max_spread: Decimal::percent(10),
+            }
+        );
+    }
+
+    #[test]
+    fn withdraw_checks_the_minimum_return() {
+        let (mut deps, api) = pool_deps();
+        // a tenth of the LP supply is redeemed for 20 uosmo
+        handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            api.clone(),
+            withdraw(20),
+        )
+        .unwrap();
+        let err = handle_api_request(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("manager", &[]),
+            api,
+            withdraw(21),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            DexError::MinimumNotReturned {
+                asset: "osmo".into(),
+                min: 21u128.into(),
+                returned: 20u128.into(),
+            }
+        );
+    }
+}
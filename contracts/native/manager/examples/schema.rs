@@ -0,0 +1,29 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use abstract_os::manager::{
+    ConfigResponse, DependentsResponse, ExecuteMsg, InfoResponse, InstantiateMsg, MigrateMsg,
+    ModuleAddressesResponse, ModuleInfosResponse, ModuleVersionsResponse, QueryMsg,
+    QueuedActionsResponse, TimelockResponse,
+};
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ModuleVersionsResponse), &out_dir);
+    export_schema(&schema_for!(ModuleAddressesResponse), &out_dir);
+    export_schema(&schema_for!(ModuleInfosResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(InfoResponse), &out_dir);
+    export_schema(&schema_for!(DependentsResponse), &out_dir);
+    export_schema(&schema_for!(TimelockResponse), &out_dir);
+    export_schema(&schema_for!(QueuedActionsResponse), &out_dir);
+}
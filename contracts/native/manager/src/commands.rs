@@ -0,0 +1,659 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use abstract_os::{
+    manager::{
+        state::{
+            QueuedAction, Timelock, CONFIG, DEPENDENTS, INFO, NEXT_ACTION_ID, OS_FACTORY,
+            OS_MODULES, QUEUED_ACTIONS, ROOT, STATUS, TIMELOCK,
+        },
+        ExecuteMsg, EXEC_ON_MODULE_PREFIX, TIMELOCK_NAMES,
+    },
+    module_factory::ExecuteMsg as ModuleFactoryMsg,
+    objects::{
+        dependency::{install_order, Dependency},
+        module::{assert_version_upgrade, Module, ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    proxy::{state::STATE as PROXY_STATE, ExecuteMsg as ProxyMsg},
+    MANAGER, PROXY,
+};
+use abstract_sdk::{get_module, get_module_dependencies, query_module_version};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response,
+    StdResult, Storage, WasmMsg,
+};
+use cw2::get_contract_version;
+
+use crate::{contract::ManagerResult, error::ManagerError};
+
+/// Forwards a message to an installed module
+pub fn exec_on_module(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    module_id: String,
+    exec_msg: Binary,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let module_addr = load_module_addr(deps.as_ref(), &module_id)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: module_addr.into_string(),
+            msg: exec_msg,
+            funds: msg_info.funds,
+        })
+        .add_attribute("action", "exec_on_module")
+        .add_attribute("module", module_id))
+}
+
+/// Adds or removes module addresses, used by the os factory to register the core modules.
+/// The os factory loses this right once the proxy is registered.
+pub fn update_module_addresses(
+    mut deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Option<Vec<(String, String)>>,
+    to_remove: Option<Vec<String>>,
+) -> ManagerResult {
+    let is_factory = OS_FACTORY.is_admin(deps.as_ref(), &msg_info.sender)?;
+    if !is_factory {
+        ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    }
+
+    for (module_id, address) in to_add.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&address)?;
+        OS_MODULES.save(deps.storage, &module_id, &addr)?;
+    }
+    for module_id in to_remove.unwrap_or_default() {
+        OS_MODULES.remove(deps.storage, &module_id);
+    }
+    if is_factory && OS_MODULES.has(deps.storage, PROXY) {
+        OS_FACTORY.set(deps.branch(), None)?;
+    }
+
+    Ok(Response::new().add_attribute("action", "update_module_addresses"))
+}
+
+/// Limits how often the install order is resolved again when the dependency versions change
+const MAX_DEPENDENCY_WALKS: usize = 5;
+
+/// Asks the module factory to create the module.
+/// The missing dependencies of the module are created first when `install_dependencies` is set,
+/// their versions are resolved from the requirements of the modules that depend on them.
+pub fn create_module(
+    deps: Deps,
+    msg_info: MessageInfo,
+    module: ModuleInfo,
+    init_msg: Option<Binary>,
+    install_dependencies: bool,
+) -> ManagerResult {
+    ROOT.assert_admin(deps, &msg_info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let module_id = module.id();
+    if OS_MODULES.has(deps.storage, &module_id) {
+        return Err(ManagerError::ModuleAlreadyInstalled(module_id));
+    }
+
+    let installed = OS_MODULES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    // Version requirements on each dependency. The dependencies of a module are those of the version
+    // that satisfies all the requirements on it, so the install order is resolved again with the
+    // requirements collected by the previous walk until they no longer change.
+    let mut requirements: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut walks = 0;
+    let order = loop {
+        let collected: RefCell<BTreeMap<String, Vec<String>>> = RefCell::default();
+        let order = install_order(&module_id, &installed, |id| {
+            let version = if id == module_id {
+                module.version.clone()
+            } else {
+                required_version(&requirements, id)
+            };
+            let dependencies = get_module_dependencies(
+                &deps.querier,
+                ModuleInfo::from_id(id, version)?,
+                &config.version_control_address,
+            )?;
+            let mut collected = collected.borrow_mut();
+            for dependency in &dependencies {
+                collected
+                    .entry(dependency.id.clone())
+                    .or_default()
+                    .extend(dependency.version_req.iter().cloned());
+            }
+            Ok(dependencies)
+        })?;
+        let collected = collected.into_inner();
+        if collected == requirements {
+            break order;
+        }
+        walks += 1;
+        if walks == MAX_DEPENDENCY_WALKS {
+            return Err(ManagerError::UnresolvedDependencies(module_id));
+        }
+        requirements = collected;
+    };
+
+    if !install_dependencies && order.len() > 1 {
+        return Err(ManagerError::MissingDependency {
+            module: module_id,
+            dependency: order[0].clone(),
+        });
+    }
+    // Installed dependencies must match the requirements
+    for (dependency, version_req) in &requirements {
+        if !order.contains(dependency) {
+            assert_dependency(
+                deps,
+                &module_id,
+                &Dependency::new(dependency, version_req.clone()),
+            )?;
+        }
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for id in order.iter() {
+        let (module, init_msg, funds) = if id == &module_id {
+            (module.clone(), init_msg.clone(), msg_info.funds.clone())
+        } else {
+            (
+                ModuleInfo::from_id(id, required_version(&requirements, id))?,
+                None,
+                vec![],
+            )
+        };
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: config.module_factory_address.to_string(),
+                msg: to_binary(&ModuleFactoryMsg::CreateModule { module, init_msg })?,
+                funds,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "create_module")
+        .add_attribute("modules", order.join(",")))
+}
+
+/// Registers a module that was created by the module factory and whitelists it on the proxy.
+pub fn register_module(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    module_addr: String,
+    module: Module,
+) -> ManagerResult {
+    let config = CONFIG.load(deps.storage)?;
+    if msg_info.sender != config.module_factory_address {
+        return Err(ManagerError::CallerNotFactory {});
+    }
+    let module_addr = deps.api.addr_validate(&module_addr)?;
+    let module_id = module.info.id();
+    OS_MODULES.save(deps.storage, &module_id, &module_addr)?;
+
+    let dependencies = get_module_dependencies(
+        &deps.querier,
+        module.info.clone(),
+        &config.version_control_address,
+    )?;
+    add_dependent(deps.storage, &module_id, &dependencies)?;
+
+    let response = Response::new()
+        .add_attribute("action", "register_module")
+        .add_attribute("module", &module_id);
+    match module.reference {
+        // Apis and apps act through the proxy
+        ModuleReference::Extension(_) | ModuleReference::App(_) => {
+            let proxy = load_module_addr(deps.as_ref(), PROXY)?;
+            Ok(response.add_message(proxy_msg(
+                &proxy,
+                &ProxyMsg::AddModule {
+                    module: module_addr.into_string(),
+                },
+            )?))
+        }
+        ModuleReference::Core(_) | ModuleReference::Perk(_) | ModuleReference::Service(_) => {
+            Ok(response)
+        }
+    }
+}
+
+/// Removes a module that no other installed module depends on.
+pub fn remove_module(deps: DepsMut, msg_info: MessageInfo, module_id: String) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    if module_id == PROXY || module_id == MANAGER {
+        return Err(ManagerError::CannotRemoveCore(module_id));
+    }
+    let module_addr = load_module_addr(deps.as_ref(), &module_id)?;
+    let dependents = DEPENDENTS
+        .may_load(deps.storage, &module_id)?
+        .unwrap_or_default();
+    if !dependents.is_empty() {
+        return Err(ManagerError::ModuleHasDependents {
+            module: module_id,
+            dependents: dependents.join(", "),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let installed = installed_module_info(deps.as_ref(), &module_id)?;
+    let dependencies =
+        get_module_dependencies(&deps.querier, installed, &config.version_control_address)?;
+    remove_dependent(deps.storage, &module_id, &dependencies)?;
+    OS_MODULES.remove(deps.storage, &module_id);
+
+    let mut response = Response::new()
+        .add_attribute("action", "remove_module")
+        .add_attribute("module", &module_id);
+    let proxy = load_module_addr(deps.as_ref(), PROXY)?;
+    if PROXY_STATE
+        .query(&deps.querier, proxy.clone())?
+        .modules
+        .contains(&module_addr)
+    {
+        response = response.add_message(proxy_msg(
+            &proxy,
+            &ProxyMsg::RemoveModule {
+                module: module_addr.into_string(),
+            },
+        )?);
+    }
+    Ok(response)
+}
+
+/// Upgrades an installed module to a higher version, resolved by version control.
+/// The dependencies of the new version must be installed and the modules that depend on it must accept the new version.
+/// Contracts are migrated to the code-id of the new version, apis are replaced by the new api instance.
+pub fn upgrade_module(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    module: ModuleInfo,
+    migrate_msg: Option<Binary>,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let module_id = module.id();
+    let (module_addr, current) = if module_id == MANAGER {
+        (
+            env.contract.address,
+            ModuleInfo::try_from(get_contract_version(deps.storage)?)?,
+        )
+    } else {
+        (
+            load_module_addr(deps.as_ref(), &module_id)?,
+            installed_module_info(deps.as_ref(), &module_id)?,
+        )
+    };
+    let new = get_module(&deps.querier, module, &config.version_control_address)?;
+    assert_version_upgrade(&current.version.to_string(), &new.info.version.to_string())?;
+
+    let new_version = new.info.version.to_string();
+    for dependent in DEPENDENTS
+        .may_load(deps.storage, &module_id)?
+        .unwrap_or_default()
+    {
+        let dependent_info = installed_module_info(deps.as_ref(), &dependent)?;
+        let dependencies = get_module_dependencies(
+            &deps.querier,
+            dependent_info,
+            &config.version_control_address,
+        )?;
+        if let Some(dependency) = dependencies.iter().find(|d| d.id == module_id) {
+            if !dependency.matches(&new_version)? {
+                return Err(ManagerError::DependencyNotSatisfied {
+                    module: dependent,
+                    dependency: module_id,
+                    version: new_version,
+                });
+            }
+        }
+    }
+    let old_dependencies =
+        get_module_dependencies(&deps.querier, current, &config.version_control_address)?;
+    let new_dependencies = get_module_dependencies(
+        &deps.querier,
+        new.info.clone(),
+        &config.version_control_address,
+    )?;
+    for dependency in &new_dependencies {
+        assert_dependency(deps.as_ref(), &module_id, dependency)?;
+    }
+    remove_dependent(deps.storage, &module_id, &old_dependencies)?;
+    add_dependent(deps.storage, &module_id, &new_dependencies)?;
+
+    let msgs: Vec<CosmosMsg> = match new.reference {
+        // Apis are shared, the OS switches to the instance of the new version
+        ModuleReference::Extension(new_addr) => {
+            OS_MODULES.save(deps.storage, &module_id, &new_addr)?;
+            let proxy = load_module_addr(deps.as_ref(), PROXY)?;
+            vec![
+                proxy_msg(
+                    &proxy,
+                    &ProxyMsg::RemoveModule {
+                        module: module_addr.into_string(),
+                    },
+                )?,
+                proxy_msg(
+                    &proxy,
+                    &ProxyMsg::AddModule {
+                        module: new_addr.into_string(),
+                    },
+                )?,
+            ]
+        }
+        ModuleReference::Core(code_id)
+        | ModuleReference::App(code_id)
+        | ModuleReference::Perk(code_id)
+        | ModuleReference::Service(code_id) => vec![WasmMsg::Migrate {
+            contract_addr: module_addr.into_string(),
+            new_code_id: code_id,
+            msg: migrate_msg.map_or_else(|| to_binary(&Empty {}), Ok)?,
+        }
+        .into()],
+    };
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "upgrade_module")
+        .add_attribute("module", module_id)
+        .add_attribute("version", new_version))
+}
+
+/// Updates the OS info
+pub fn update_info(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    name: Option<String>,
+    description: Option<String>,
+    link: Option<String>,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut info = INFO.load(deps.storage)?;
+    if let Some(name) = name {
+        info.name = name;
+    }
+    if let Some(description) = description {
+        info.description = Some(description);
+    }
+    if let Some(link) = link {
+        info.link = Some(link);
+    }
+    INFO.save(deps.storage, &info)?;
+
+    Ok(Response::new().add_attribute("action", "update_info"))
+}
+
+/// Sets a new root and optionally updates the governance type
+pub fn set_root(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    root: String,
+    governance_type: Option<String>,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let root = deps.api.addr_validate(&root)?;
+    if let Some(governance_type) = governance_type {
+        let mut info = INFO.load(deps.storage)?;
+        info.governance_type = governance_type;
+        INFO.save(deps.storage, &info)?;
+    }
+    ROOT.set(deps, Some(root.clone()))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_root")
+        .add_attribute("root", root))
+}
+
+/// Suspends or re-activates the OS.
+/// Only the subscription contract can change the status, the root can when the OS has no subscription.
+pub fn update_status(deps: DepsMut, msg_info: MessageInfo, new_status: bool) -> ManagerResult {
+    let config = CONFIG.load(deps.storage)?;
+    match config.subscription_address {
+        Some(subscription) if subscription != msg_info.sender => {
+            return Err(ManagerError::CallerNotSubscriptionContract {})
+        }
+        Some(_) => (),
+        None => ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?,
+    }
+    STATUS.save(deps.storage, &new_status)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_status")
+        .add_attribute("status", new_status.to_string()))
+}
+
+/// Queues a timelocked action that can be executed once the delay has passed
+pub fn propose_action(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    action: ExecuteMsg,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let timelock = TIMELOCK.may_load(deps.storage)?.unwrap_or_default();
+    if timelocked_name(&timelock, &action).is_none() {
+        return Err(ManagerError::ActionNotTimelocked {});
+    }
+    let action = pin_module_version(deps.as_ref(), action)?;
+    let action_id = NEXT_ACTION_ID.may_load(deps.storage)?.unwrap_or_default();
+    let eta = env.block.time.plus_seconds(timelock.delay);
+    QUEUED_ACTIONS.save(deps.storage, action_id, &QueuedAction { action, eta })?;
+    NEXT_ACTION_ID.save(deps.storage, &(action_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_action")
+        .add_attribute("action_id", action_id.to_string())
+        .add_attribute("eta", eta.to_string()))
+}
+
+/// Resolves the module version of an upgrade or module creation through version control,
+/// so a queued action installs the version that was proposed, not one registered during the delay.
+fn pin_module_version(deps: Deps, action: ExecuteMsg) -> Result<ExecuteMsg, ManagerError> {
+    let resolve = |module: ModuleInfo| -> Result<ModuleInfo, ManagerError> {
+        let config = CONFIG.load(deps.storage)?;
+        Ok(get_module(&deps.querier, module, &config.version_control_address)?.info)
+    };
+    Ok(match action {
+        ExecuteMsg::Upgrade {
+            module,
+            migrate_msg,
+        } => ExecuteMsg::Upgrade {
+            module: resolve(module)?,
+            migrate_msg,
+        },
+        ExecuteMsg::CreateModule {
+            module,
+            init_msg,
+            install_dependencies,
+        } => ExecuteMsg::CreateModule {
+            module: resolve(module)?,
+            init_msg,
+            install_dependencies,
+        },
+        action => action,
+    })
+}
+
+/// Removes a queued action whose delay has passed and returns it for execution
+pub fn take_ready_action(
+    deps: DepsMut,
+    env: &Env,
+    msg_info: &MessageInfo,
+    action_id: u64,
+) -> Result<ExecuteMsg, ManagerError> {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let queued = QUEUED_ACTIONS
+        .may_load(deps.storage, action_id)?
+        .ok_or(ManagerError::ActionNotQueued(action_id))?;
+    if env.block.time < queued.eta {
+        return Err(ManagerError::ActionNotReady {
+            action_id,
+            eta: queued.eta,
+        });
+    }
+    QUEUED_ACTIONS.remove(deps.storage, action_id);
+    Ok(queued.action)
+}
+
+/// Removes a queued action
+pub fn cancel_action(deps: DepsMut, msg_info: MessageInfo, action_id: u64) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    if !QUEUED_ACTIONS.has(deps.storage, action_id) {
+        return Err(ManagerError::ActionNotQueued(action_id));
+    }
+    QUEUED_ACTIONS.remove(deps.storage, action_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_action")
+        .add_attribute("action_id", action_id.to_string()))
+}
+
+/// Updates the delay and the messages that are timelocked.
+/// Queued actions keep their eta.
+pub fn update_timelock(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    delay: Option<u64>,
+    locked_actions: Option<Vec<String>>,
+) -> ManagerResult {
+    ROOT.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut timelock = TIMELOCK.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(delay) = delay {
+        timelock.delay = delay;
+    }
+    if let Some(locked_actions) = locked_actions {
+        if let Some(invalid) = locked_actions.iter().find(|name| {
+            !TIMELOCK_NAMES.contains(&name.as_str()) && !name.starts_with(EXEC_ON_MODULE_PREFIX)
+        }) {
+            return Err(ManagerError::InvalidTimelockAction(invalid.clone()));
+        }
+        timelock.locked_actions = locked_actions;
+    }
+    TIMELOCK.save(deps.storage, &timelock)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_timelock")
+        .add_attribute("delay", timelock.delay.to_string()))
+}
+
+/// Errors when the message has to be proposed before it can be executed
+pub fn assert_not_timelocked(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ManagerError> {
+    let timelock = match TIMELOCK.may_load(storage)? {
+        Some(timelock) => timelock,
+        None => return Ok(()),
+    };
+    match timelocked_name(&timelock, msg) {
+        Some(name) => Err(ManagerError::ActionTimelocked(name)),
+        None => Ok(()),
+    }
+}
+
+/// Name under which the message is timelocked, if it is.
+/// The timelock can't be changed directly while a delay is set.
+fn timelocked_name(timelock: &Timelock, msg: &ExecuteMsg) -> Option<String> {
+    msg.timelock_names().into_iter().find(|name| {
+        timelock.locked_actions.contains(name)
+            || (timelock.delay > 0 && matches!(msg, ExecuteMsg::UpdateTimelock { .. }))
+    })
+}
+
+fn load_module_addr(deps: Deps, module_id: &str) -> Result<Addr, ManagerError> {
+    OS_MODULES
+        .may_load(deps.storage, module_id)?
+        .ok_or_else(|| ManagerError::ModuleNotInstalled(module_id.to_string()))
+}
+
+/// Info of an installed module, its version is the one stored by the module itself
+fn installed_module_info(deps: Deps, module_id: &str) -> Result<ModuleInfo, ManagerError> {
+    let module_addr = load_module_addr(deps, module_id)?;
+    Ok(ModuleInfo::try_from(query_module_version(
+        &deps,
+        module_addr,
+    )?)?)
+}
+
+/// Errors unless the dependency is installed with a matching version
+fn assert_dependency(
+    deps: Deps,
+    module_id: &str,
+    dependency: &Dependency,
+) -> Result<(), ManagerError> {
+    if !OS_MODULES.has(deps.storage, &dependency.id) {
+        return Err(ManagerError::MissingDependency {
+            module: module_id.to_string(),
+            dependency: dependency.id.clone(),
+        });
+    }
+    let version = installed_module_info(deps, &dependency.id)?
+        .version
+        .to_string();
+    if !dependency.matches(&version)? {
+        return Err(ManagerError::DependencyNotSatisfied {
+            module: module_id.to_string(),
+            dependency: dependency.id.clone(),
+            version,
+        });
+    }
+    Ok(())
+}
+
+/// Version that satisfies all the requirements on a module
+fn required_version(
+    requirements: &BTreeMap<String, Vec<String>>,
+    module_id: &str,
+) -> ModuleVersion {
+    match requirements.get(module_id) {
+        Some(version_req) if !version_req.is_empty() => {
+            ModuleVersion::Requirement(version_req.join(", "))
+        }
+        _ => ModuleVersion::Latest {},
+    }
+}
+
+fn add_dependent(
+    storage: &mut dyn Storage,
+    module_id: &str,
+    dependencies: &[Dependency],
+) -> StdResult<()> {
+    for dependency in dependencies {
+        let mut dependents = DEPENDENTS
+            .may_load(storage, &dependency.id)?
+            .unwrap_or_default();
+        if !dependents.iter().any(|d| d == module_id) {
+            dependents.push(module_id.to_string());
+        }
+        DEPENDENTS.save(storage, &dependency.id, &dependents)?;
+    }
+    Ok(())
+}
+
+fn remove_dependent(
+    storage: &mut dyn Storage,
+    module_id: &str,
+    dependencies: &[Dependency],
+) -> StdResult<()> {
+    for dependency in dependencies {
+        let mut dependents = DEPENDENTS
+            .may_load(storage, &dependency.id)?
+            .unwrap_or_default();
+        dependents.retain(|d| d != module_id);
+        if dependents.is_empty() {
+            DEPENDENTS.remove(storage, &dependency.id);
+        } else {
+            DEPENDENTS.save(storage, &dependency.id, &dependents)?;
+        }
+    }
+    Ok(())
+}
+
+fn proxy_msg(proxy: &Addr, msg: &ProxyMsg) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: proxy.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }
+    .into())
+}
@@ -0,0 +1,150 @@
+use abstract_os::{
+    manager::{
+        state::{Config, OsInfo, CONFIG, INFO, OS_FACTORY, OS_ID, ROOT, STATUS},
+        ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    },
+    MANAGER,
+};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::{commands::*, error::ManagerError, queries};
+
+pub type ManagerResult = Result<Response, ManagerError>;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> ManagerResult {
+    set_contract_version(deps.storage, MANAGER, CONTRACT_VERSION)?;
+
+    let subscription_address = msg
+        .subscription_address
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    OS_ID.save(deps.storage, &msg.os_id)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            version_control_address: deps.api.addr_validate(&msg.version_control_address)?,
+            module_factory_address: deps.api.addr_validate(&msg.module_factory_address)?,
+            subscription_address,
+        },
+    )?;
+    INFO.save(
+        deps.storage,
+        &OsInfo {
+            name: msg.name,
+            governance_type: msg.governance_type,
+            chain_id: env.block.chain_id,
+            description: msg.description,
+            link: msg.link,
+        },
+    )?;
+    STATUS.save(deps.storage, &true)?;
+
+    let root = deps.api.addr_validate(&msg.root_user)?;
+    ROOT.set(deps.branch(), Some(root))?;
+    // The creator (os factory) can register the core modules
+    OS_FACTORY.set(deps, Some(info.sender))?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ManagerResult {
+    // A suspended OS can only be re-activated
+    if !matches!(msg, ExecuteMsg::SuspendOs { .. }) && !STATUS.load(deps.storage)? {
+        return Err(ManagerError::OsSuspended {});
+    }
+    assert_not_timelocked(deps.storage, &msg)?;
+    dispatch(deps, env, info, msg)
+}
+
+/// Handles a message that passed the status and timelock checks
+fn dispatch(mut deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ManagerResult {
+    match msg {
+        ExecuteMsg::ExecOnModule {
+            module_id,
+            exec_msg,
+        } => exec_on_module(deps, info, module_id, exec_msg),
+        ExecuteMsg::UpdateModuleAddresses { to_add, to_remove } => {
+            update_module_addresses(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::CreateModule {
+            module,
+            init_msg,
+            install_dependencies,
+        } => create_module(deps.as_ref(), info, module, init_msg, install_dependencies),
+        ExecuteMsg::RegisterModule {
+            module_addr,
+            module,
+        } => register_module(deps, info, module_addr, module),
+        ExecuteMsg::RemoveModule { module_id } => remove_module(deps, info, module_id),
+        ExecuteMsg::Upgrade {
+            module,
+            migrate_msg,
+        } => upgrade_module(deps, env, info, module, migrate_msg),
+        ExecuteMsg::UpdateInfo {
+            name,
+            description,
+            link,
+        } => update_info(deps, info, name, description, link),
+        ExecuteMsg::SetRoot {
+            root,
+            governance_type,
+        } => set_root(deps, info, root, governance_type),
+        ExecuteMsg::SuspendOs { new_status } => update_status(deps, info, new_status),
+        ExecuteMsg::ProposeAction { action } => propose_action(deps, env, info, *action),
+        ExecuteMsg::ExecuteAction { action_id } => {
+            let action = take_ready_action(deps.branch(), &env, &info, action_id)?;
+            Ok(dispatch(deps, env, info, action)?
+                .add_attribute("executed_action_id", action_id.to_string()))
+        }
+        ExecuteMsg::CancelAction { action_id } => cancel_action(deps, info, action_id),
+        ExecuteMsg::UpdateTimelock {
+            delay,
+            locked_actions,
+        } => update_timelock(deps, info, delay, locked_actions),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ModuleVersions { names } => {
+            to_binary(&queries::query_module_versions(deps, names)?)
+        }
+        QueryMsg::ModuleAddresses { names } => {
+            to_binary(&queries::query_module_addresses(deps, names)?)
+        }
+        QueryMsg::ModuleInfos {
+            page_token,
+            page_size,
+        } => to_binary(&queries::query_module_infos(deps, page_token, page_size)?),
+        QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
+        QueryMsg::Info {} => to_binary(&queries::query_info(deps)?),
+        QueryMsg::Dependents { module_id } => {
+            to_binary(&queries::query_dependents(deps, module_id)?)
+        }
+        QueryMsg::Timelock {} => to_binary(&queries::query_timelock(deps)?),
+        QueryMsg::QueuedActions {
+            page_token,
+            page_size,
+        } => to_binary(&queries::query_queued_actions(deps, page_token, page_size)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ManagerResult {
+    set_contract_version(deps.storage, MANAGER, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,61 @@
+use cosmwasm_std::{StdError, Timestamp};
+use cw_controllers::AdminError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ManagerError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Module {0} is already installed")]
+    ModuleAlreadyInstalled(String),
+
+    #[error("Module {0} is not installed")]
+    ModuleNotInstalled(String),
+
+    #[error("Module {module} requires {dependency} to be installed")]
+    MissingDependency { module: String, dependency: String },
+
+    #[error("Module {module} does not accept version {version} of {dependency}")]
+    DependencyNotSatisfied {
+        module: String,
+        dependency: String,
+        version: String,
+    },
+
+    #[error("The dependency versions of {0} could not be resolved")]
+    UnresolvedDependencies(String),
+
+    #[error("Module {module} is required by {dependents}")]
+    ModuleHasDependents { module: String, dependents: String },
+
+    #[error("Core module {0} can't be removed")]
+    CannotRemoveCore(String),
+
+    #[error("Modules can only be registered by the module factory")]
+    CallerNotFactory {},
+
+    #[error("The OS status can only be changed by the subscription contract")]
+    CallerNotSubscriptionContract {},
+
+    #[error("OS is suspended")]
+    OsSuspended {},
+
+    #[error("{0} is timelocked and has to be proposed first")]
+    ActionTimelocked(String),
+
+    #[error("The action is not timelocked and can be executed directly")]
+    ActionNotTimelocked {},
+
+    #[error("Action {0} is not queued")]
+    ActionNotQueued(u64),
+
+    #[error("Action {action_id} can't be executed before {eta}")]
+    ActionNotReady { action_id: u64, eta: Timestamp },
+
+    #[error("{0} is not a message that can be timelocked")]
+    InvalidTimelockAction(String),
+}
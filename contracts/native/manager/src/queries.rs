@@ -0,0 +1,115 @@
+use abstract_os::manager::{
+    state::{CONFIG, DEPENDENTS, INFO, OS_ID, OS_MODULES, QUEUED_ACTIONS, ROOT, TIMELOCK},
+    ConfigResponse, DependentsResponse, InfoResponse, ManagerModuleInfo, ModuleAddressesResponse,
+    ModuleInfosResponse, ModuleVersionsResponse, QueuedActionsResponse, TimelockResponse,
+};
+use abstract_sdk::query_module_version;
+use cosmwasm_std::{Addr, Deps, Order, StdError, StdResult, Uint64};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u8 = 5;
+const MAX_LIMIT: u8 = 10;
+
+pub fn query_module_versions(deps: Deps, names: Vec<String>) -> StdResult<ModuleVersionsResponse> {
+    let versions = names
+        .iter()
+        .map(|name| {
+            let addr = load_module_addr(deps, name)?;
+            query_module_version(&deps, addr)
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ModuleVersionsResponse { versions })
+}
+
+/// Addresses of the installed modules, modules that aren't installed are skipped.
+pub fn query_module_addresses(
+    deps: Deps,
+    names: Vec<String>,
+) -> StdResult<ModuleAddressesResponse> {
+    let mut modules = vec![];
+    for name in names {
+        if let Some(addr) = OS_MODULES.may_load(deps.storage, &name)? {
+            modules.push((name, addr.into_string()));
+        }
+    }
+    Ok(ModuleAddressesResponse { modules })
+}
+
+pub fn query_module_infos(
+    deps: Deps,
+    page_token: Option<String>,
+    page_size: Option<u8>,
+) -> StdResult<ModuleInfosResponse> {
+    let limit = page_size.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = page_token.as_deref().map(Bound::exclusive);
+
+    let module_infos = OS_MODULES
+        .range(deps.storage, start_bound, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (name, addr) = item?;
+            Ok(ManagerModuleInfo {
+                name,
+                version: query_module_version(&deps, addr.clone())?,
+                address: addr.into_string(),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ModuleInfosResponse { module_infos })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let root = ROOT
+        .get(deps)?
+        .ok_or_else(|| StdError::generic_err("OS has no root"))?;
+    Ok(ConfigResponse {
+        root: root.into_string(),
+        version_control_address: config.version_control_address.into_string(),
+        module_factory_address: config.module_factory_address.into_string(),
+        os_id: Uint64::from(OS_ID.load(deps.storage)?),
+    })
+}
+
+pub fn query_info(deps: Deps) -> StdResult<InfoResponse> {
+    Ok(InfoResponse {
+        info: INFO.load(deps.storage)?,
+    })
+}
+
+pub fn query_dependents(deps: Deps, module_id: String) -> StdResult<DependentsResponse> {
+    Ok(DependentsResponse {
+        dependents: DEPENDENTS
+            .may_load(deps.storage, &module_id)?
+            .unwrap_or_default(),
+    })
+}
+
+pub fn query_timelock(deps: Deps) -> StdResult<TimelockResponse> {
+    let timelock = TIMELOCK.may_load(deps.storage)?.unwrap_or_default();
+    Ok(TimelockResponse {
+        delay: timelock.delay,
+        locked_actions: timelock.locked_actions,
+    })
+}
+
+pub fn query_queued_actions(
+    deps: Deps,
+    page_token: Option<u64>,
+    page_size: Option<u8>,
+) -> StdResult<QueuedActionsResponse> {
+    let limit = page_size.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = page_token.map(Bound::exclusive);
+
+    let actions = QUEUED_ACTIONS
+        .range(deps.storage, start_bound, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(QueuedActionsResponse { actions })
+}
+
+fn load_module_addr(deps: Deps, name: &str) -> StdResult<Addr> {
+    OS_MODULES
+        .may_load(deps.storage, name)?
+        .ok_or_else(|| StdError::generic_err(format!("module {} is not installed", name)))
+}
@@ -0,0 +1,661 @@
+use abstract_os::{
+    manager::{
+        state::QueuedAction, ConfigResponse, DependentsResponse, ExecuteMsg, InstantiateMsg,
+        ModuleAddressesResponse, ModuleInfosResponse, QueryMsg, QueuedActionsResponse,
+    },
+    module_factory::ExecuteMsg as ModuleFactoryMsg,
+    objects::{
+        dependency::Dependency,
+        module::{resolve_version, Module, ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    proxy::{
+        state::{State, STATE as PROXY_STATE},
+        ExecuteMsg as ProxyMsg,
+    },
+    version_control::{ModuleDependenciesResponse, ModuleResponse, QueryMsg as VcQueryMsg},
+    ETF, EXCHANGE, PROXY,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    to_binary, Addr, Binary, ContractResult, CosmosMsg, Env, OwnedDeps, StdError, StdResult,
+    SystemResult, WasmMsg, WasmQuery,
+};
+use cw2::{ContractVersion, CONTRACT};
+use cw_controllers::AdminError;
+
+use crate::{
+    contract::{execute, instantiate, query, ManagerResult},
+    error::ManagerError,
+};
+
+const OS_FACTORY: &str = "os_factory";
+const ROOT: &str = "root";
+const VERSION_CONTROL: &str = "version_control";
+const MODULE_FACTORY: &str = "module_factory";
+const SUBSCRIPTION: &str = "subscription";
+const PROXY_ADDR: &str = "proxy";
+const DEX_V1: &str = "dex_api_v1";
+const DEX_V2: &str = "dex_api_v2";
+const ETF_ADDR: &str = "etf";
+const VAULT: &str = "abstract:vault";
+const LENDER: &str = "abstract:lender";
+const STAKER: &str = "abstract:staker";
+const ORACLE: &str = "abstract:oracle";
+
+/// Modules registered in the mocked version control: id, version, reference and dependencies.
+fn registry() -> Vec<(&'static str, &'static str, ModuleReference, Vec<Dependency>)> {
+    let etf_dependencies = vec![Dependency::new(EXCHANGE, vec!["^0.1".into()])];
+    vec![
+        (
+            EXCHANGE,
+            "0.1.0",
+            ModuleReference::Extension(Addr::unchecked(DEX_V1)),
+            vec![],
+        ),
+        (
+            EXCHANGE,
+            "0.2.0",
+            ModuleReference::Extension(Addr::unchecked(DEX_V2)),
+            vec![],
+        ),
+        (
+            ETF,
+            "0.1.0",
+            ModuleReference::App(1),
+            etf_dependencies.clone(),
+        ),
+        (ETF, "0.2.0", ModuleReference::App(2), etf_dependencies),
+        (LENDER, "0.1.0", ModuleReference::App(3), vec![]),
+        (
+            LENDER,
+            "0.2.0",
+            ModuleReference::App(4),
+            vec![Dependency::new(ORACLE, vec![])],
+        ),
+        (ORACLE, "0.1.0", ModuleReference::App(5), vec![]),
+        (
+            STAKER,
+            "0.1.0",
+            ModuleReference::App(6),
+            vec![Dependency::new(LENDER, vec!["^0.1".into()])],
+        ),
+        (
+            VAULT,
+            "0.1.0",
+            ModuleReference::App(7),
+            vec![
+                Dependency::new(LENDER, vec![]),
+                Dependency::new(STAKER, vec![]),
+            ],
+        ),
+    ]
+}
+
+fn resolve(module: &ModuleInfo) -> StdResult<(Module, Vec<Dependency>)> {
+    let candidates: Vec<_> = registry()
+        .into_iter()
+        .filter(|(id, ..)| *id == module.id())
+        .collect();
+    let version = resolve_version(
+        &module.version,
+        candidates.iter().map(|(_, ver, ..)| ver.to_string()),
+    )?
+    .ok_or_else(|| StdError::generic_err(format!("{} not found", module)))?;
+    let (_, _, reference, dependencies) = candidates
+        .into_iter()
+        .find(|(_, ver, ..)| *ver == version)
+        .unwrap();
+    let info = ModuleInfo::from_id(&module.id(), ModuleVersion::Version(version))?;
+    Ok((Module { info, reference }, dependencies))
+}
+
+/// Version that each deployed module stores
+fn contract_version(addr: &str) -> Option<ContractVersion> {
+    let (contract, version) = match addr {
+        DEX_V1 => (EXCHANGE, "0.1.0"),
+        DEX_V2 => (EXCHANGE, "0.2.0"),
+        ETF_ADDR => (ETF, "0.1.0"),
+        PROXY_ADDR => (PROXY, "0.1.0"),
+        _ => return None,
+    };
+    Some(ContractVersion {
+        contract: contract.into(),
+        version: version.into(),
+    })
+}
+
+/// Manager of an OS with a proxy that whitelisted the v0.1.0 dex api and the etf.
+fn manager_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| {
+        let res = match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == VERSION_CONTROL => {
+                match from_binary(msg).unwrap() {
+                    VcQueryMsg::Module { module } => resolve(&module)
+                        .and_then(|(module, _)| to_binary(&ModuleResponse { module })),
+                    VcQueryMsg::ModuleDependencies { module } => {
+                        resolve(&module).and_then(|(_, dependencies)| {
+                            to_binary(&ModuleDependenciesResponse { dependencies })
+                        })
+                    }
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Raw { contract_addr, key } if key.as_slice() == CONTRACT.as_slice() => {
+                to_binary(&contract_version(contract_addr).unwrap())
+            }
+            WasmQuery::Raw { contract_addr, key }
+                if contract_addr == PROXY_ADDR && key.as_slice() == PROXY_STATE.as_slice() =>
+            {
+                to_binary(&State {
+                    modules: vec![Addr::unchecked(DEX_V1), Addr::unchecked(ETF_ADDR)],
+                })
+            }
+            _ => Ok(Binary::default()),
+        };
+        match res {
+            Ok(bin) => SystemResult::Ok(ContractResult::Ok(bin)),
+            Err(e) => SystemResult::Ok(ContractResult::Err(e.to_string())),
+        }
+    });
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OS_FACTORY, &[]),
+        InstantiateMsg {
+            os_id: 1,
+            root_user: ROOT.into(),
+            version_control_address: VERSION_CONTROL.into(),
+            module_factory_address: MODULE_FACTORY.into(),
+            subscription_address: Some(SUBSCRIPTION.into()),
+            governance_type: "monarchy".into(),
+            name: "test".into(),
+            description: None,
+            link: None,
+        },
+    )
+    .unwrap();
+    execute_as(
+        &mut deps,
+        OS_FACTORY,
+        ExecuteMsg::UpdateModuleAddresses {
+            to_add: Some(vec![(PROXY.into(), PROXY_ADDR.into())]),
+            to_remove: None,
+        },
+    )
+    .unwrap();
+    deps
+}
+
+#[test]
+fn factory_only_registers_the_proxy() {
+    let mut deps = manager_deps();
+    // the proxy was registered by the factory in the setup
+    let err = execute_as(
+        &mut deps,
+        OS_FACTORY,
+        ExecuteMsg::UpdateModuleAddresses {
+            to_add: Some(vec![(PROXY.into(), "other_proxy".into())]),
+            to_remove: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ManagerError::Admin(AdminError::NotAdmin {}));
+}
+
+fn execute_as(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> ManagerResult {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+}
+
+fn register(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, addr: &str) -> ManagerResult {
+    let installed = contract_version(addr).unwrap();
+    let (module, _) = resolve(&ModuleInfo::try_from(installed).unwrap()).unwrap();
+    execute_as(
+        deps,
+        MODULE_FACTORY,
+        ExecuteMsg::RegisterModule {
+            module_addr: addr.into(),
+            module,
+        },
+    )
+}
+
+fn proxy_msg(msg: ProxyMsg) -> CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: PROXY_ADDR.into(),
+        msg: to_binary(&msg).unwrap(),
+        funds: vec![],
+    }
+    .into()
+}
+
+fn dependents(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, module_id: &str) -> Vec<String> {
+    let res: DependentsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Dependents {
+                module_id: module_id.into(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    res.dependents
+}
+
+#[test]
+fn creates_modules_with_dependencies() {
+    let mut deps = manager_deps();
+    let etf = ModuleInfo::from_id(ETF, ModuleVersion::Latest {}).unwrap();
+    let create = |install_dependencies| ExecuteMsg::CreateModule {
+        module: etf.clone(),
+        init_msg: Some(Binary::from(b"{}".to_vec())),
+        install_dependencies,
+    };
+
+    let err = execute_as(&mut deps, "someone", create(true)).unwrap_err();
+    assert_eq!(err, ManagerError::Admin(AdminError::NotAdmin {}));
+
+    let err = execute_as(&mut deps, ROOT, create(false)).unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::MissingDependency {
+            module: ETF.into(),
+            dependency: EXCHANGE.into(),
+        }
+    );
+
+    // the dex is created first, with a version that the etf accepts
+    let res = execute_as(&mut deps, ROOT, create(true)).unwrap();
+    let factory_msg = |module, init_msg| -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: MODULE_FACTORY.into(),
+            msg: to_binary(&ModuleFactoryMsg::CreateModule { module, init_msg }).unwrap(),
+            funds: vec![],
+        }
+        .into()
+    };
+    let dex = ModuleInfo::from_id(EXCHANGE, ModuleVersion::Requirement("^0.1".into())).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<_>>(),
+        vec![
+            factory_msg(dex, None),
+            factory_msg(etf.clone(), Some(Binary::from(b"{}".to_vec()))),
+        ]
+    );
+
+    // installed dependencies are not created again
+    register(&mut deps, DEX_V1).unwrap();
+    let res = execute_as(&mut deps, ROOT, create(false)).unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn resolves_dependency_versions_first() {
+    let mut deps = manager_deps();
+    let res = execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::CreateModule {
+            module: ModuleInfo::from_id(VAULT, ModuleVersion::Latest {}).unwrap(),
+            init_msg: None,
+            install_dependencies: true,
+        },
+    )
+    .unwrap();
+    // the lender is visited before the staker restricts it to ^0.1, which doesn't need the oracle
+    let modules = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "modules")
+        .unwrap();
+    assert_eq!(modules.value, [LENDER, STAKER, VAULT].join(","));
+}
+
+#[test]
+fn registers_and_removes_modules() {
+    let mut deps = manager_deps();
+    let err = execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::RegisterModule {
+            module_addr: DEX_V1.into(),
+            module: resolve(&ModuleInfo::from_id(EXCHANGE, ModuleVersion::Latest {}).unwrap())
+                .unwrap()
+                .0,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ManagerError::CallerNotFactory {});
+
+    // registered modules are whitelisted on the proxy
+    let res = register(&mut deps, DEX_V1).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        proxy_msg(ProxyMsg::AddModule {
+            module: DEX_V1.into()
+        })
+    );
+    register(&mut deps, ETF_ADDR).unwrap();
+    assert_eq!(dependents(&deps, EXCHANGE), vec![ETF.to_string()]);
+
+    let res: ModuleAddressesResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ModuleAddresses {
+                names: vec![ETF.into(), "abstract:unknown".into()],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.modules, vec![(ETF.into(), ETF_ADDR.into())]);
+
+    let remove = |module_id: &str| ExecuteMsg::RemoveModule {
+        module_id: module_id.into(),
+    };
+    let err = execute_as(&mut deps, ROOT, remove(EXCHANGE)).unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::ModuleHasDependents {
+            module: EXCHANGE.into(),
+            dependents: ETF.into(),
+        }
+    );
+    let err = execute_as(&mut deps, ROOT, remove(PROXY)).unwrap_err();
+    assert_eq!(err, ManagerError::CannotRemoveCore(PROXY.into()));
+
+    let res = execute_as(&mut deps, ROOT, remove(ETF)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        proxy_msg(ProxyMsg::RemoveModule {
+            module: ETF_ADDR.into()
+        })
+    );
+    assert!(dependents(&deps, EXCHANGE).is_empty());
+    execute_as(&mut deps, ROOT, remove(EXCHANGE)).unwrap();
+
+    let res: ModuleInfosResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ModuleInfos {
+                page_token: None,
+                page_size: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let installed: Vec<String> = res.module_infos.into_iter().map(|m| m.name).collect();
+    assert_eq!(installed, vec![PROXY.to_string()]);
+}
+
+#[test]
+fn upgrades_modules() {
+    let mut deps = manager_deps();
+    register(&mut deps, DEX_V1).unwrap();
+    register(&mut deps, ETF_ADDR).unwrap();
+    let upgrade = |id: &str, version| ExecuteMsg::Upgrade {
+        module: ModuleInfo::from_id(id, version).unwrap(),
+        migrate_msg: None,
+    };
+
+    let err = execute_as(
+        &mut deps,
+        ROOT,
+        upgrade(ETF, ModuleVersion::Version("0.1.0".into())),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ManagerError::Std(_)));
+
+    // apps are migrated to the code-id of the resolved version
+    let res = execute_as(&mut deps, ROOT, upgrade(ETF, ModuleVersion::Latest {})).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Migrate {
+            contract_addr: ETF_ADDR.into(),
+            new_code_id: 2,
+            msg: Binary::from(b"{}".to_vec()),
+        }
+        .into()
+    );
+
+    // the etf doesn't accept the new dex version
+    let err = execute_as(&mut deps, ROOT, upgrade(EXCHANGE, ModuleVersion::Latest {})).unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::DependencyNotSatisfied {
+            module: ETF.into(),
+            dependency: EXCHANGE.into(),
+            version: "0.2.0".into(),
+        }
+    );
+
+    // apis are replaced by the instance of the new version
+    execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::RemoveModule {
+            module_id: ETF.into(),
+        },
+    )
+    .unwrap();
+    let res = execute_as(&mut deps, ROOT, upgrade(EXCHANGE, ModuleVersion::Latest {})).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<_>>(),
+        vec![
+            proxy_msg(ProxyMsg::RemoveModule {
+                module: DEX_V1.into()
+            }),
+            proxy_msg(ProxyMsg::AddModule {
+                module: DEX_V2.into()
+            }),
+        ]
+    );
+}
+
+#[test]
+fn suspended_os_is_locked() {
+    let mut deps = manager_deps();
+    register(&mut deps, DEX_V1).unwrap();
+    let suspend = |new_status| ExecuteMsg::SuspendOs { new_status };
+    let exec = ExecuteMsg::ExecOnModule {
+        module_id: EXCHANGE.into(),
+        exec_msg: Binary::from(b"{}".to_vec()),
+    };
+
+    let err = execute_as(&mut deps, ROOT, suspend(false)).unwrap_err();
+    assert_eq!(err, ManagerError::CallerNotSubscriptionContract {});
+
+    execute_as(&mut deps, SUBSCRIPTION, suspend(false)).unwrap();
+    let err = execute_as(&mut deps, ROOT, exec.clone()).unwrap_err();
+    assert_eq!(err, ManagerError::OsSuspended {});
+
+    execute_as(&mut deps, SUBSCRIPTION, suspend(true)).unwrap();
+    let res = execute_as(&mut deps, ROOT, exec).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: DEX_V1.into(),
+            msg: Binary::from(b"{}".to_vec()),
+            funds: vec![],
+        }
+        .into()
+    );
+}
+
+fn env_after(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn queued_actions(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<(u64, QueuedAction)> {
+    let res: QueuedActionsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::QueuedActions {
+                page_token: None,
+                page_size: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    res.actions
+}
+
+#[test]
+fn timelocks_root_actions() {
+    let mut deps = manager_deps();
+    let set_root = ExecuteMsg::SetRoot {
+        root: "new_root".into(),
+        governance_type: None,
+    };
+    let propose = |action: &ExecuteMsg| ExecuteMsg::ProposeAction {
+        action: Box::new(action.clone()),
+    };
+    let update_timelock = |locked_actions: Vec<&str>| ExecuteMsg::UpdateTimelock {
+        delay: Some(100),
+        locked_actions: Some(locked_actions.into_iter().map(Into::into).collect()),
+    };
+
+    let err = execute_as(&mut deps, ROOT, propose(&set_root)).unwrap_err();
+    assert_eq!(err, ManagerError::ActionNotTimelocked {});
+    let err = execute_as(&mut deps, ROOT, update_timelock(vec!["suspend_os"])).unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::InvalidTimelockAction("suspend_os".into())
+    );
+    execute_as(
+        &mut deps,
+        ROOT,
+        update_timelock(vec!["set_root", "exec_on_module:abstract:proxy"]),
+    )
+    .unwrap();
+
+    let err = execute_as(&mut deps, ROOT, set_root.clone()).unwrap_err();
+    assert_eq!(err, ManagerError::ActionTimelocked("set_root".into()));
+    // proxy asset changes go through the timelock
+    let err = execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::ExecOnModule {
+            module_id: PROXY.into(),
+            exec_msg: Binary::from(b"{}".to_vec()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::ActionTimelocked("exec_on_module:abstract:proxy".into())
+    );
+    // the timelock can't be lifted directly
+    let err = execute_as(&mut deps, ROOT, update_timelock(vec![])).unwrap_err();
+    assert_eq!(
+        err,
+        ManagerError::ActionTimelocked("update_timelock".into())
+    );
+
+    execute_as(&mut deps, ROOT, propose(&set_root)).unwrap();
+    let eta = mock_env().block.time.plus_seconds(100);
+    assert_eq!(
+        queued_actions(&deps),
+        vec![(
+            0,
+            QueuedAction {
+                action: set_root,
+                eta
+            }
+        )]
+    );
+
+    let execute_action = ExecuteMsg::ExecuteAction { action_id: 0 };
+    let err = execute(
+        deps.as_mut(),
+        env_after(99),
+        mock_info(ROOT, &[]),
+        execute_action.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ManagerError::ActionNotReady { action_id: 0, eta });
+    execute(
+        deps.as_mut(),
+        env_after(100),
+        mock_info(ROOT, &[]),
+        execute_action,
+    )
+    .unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.root, "new_root");
+    assert_eq!(queued_actions(&deps), vec![]);
+}
+
+#[test]
+fn cancels_queued_actions() {
+    let mut deps = manager_deps();
+    execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::UpdateTimelock {
+            delay: Some(100),
+            locked_actions: Some(vec!["upgrade".into()]),
+        },
+    )
+    .unwrap();
+    execute_as(
+        &mut deps,
+        ROOT,
+        ExecuteMsg::ProposeAction {
+            action: Box::new(ExecuteMsg::Upgrade {
+                module: ModuleInfo::from_id(EXCHANGE, ModuleVersion::Latest {}).unwrap(),
+                migrate_msg: None,
+            }),
+        },
+    )
+    .unwrap();
+    // the latest version at the time of the proposal is queued
+    let (_, queued) = &queued_actions(&deps)[0];
+    assert_eq!(
+        queued.action,
+        ExecuteMsg::Upgrade {
+            module: ModuleInfo::from_id(EXCHANGE, ModuleVersion::Version("0.2.0".into())).unwrap(),
+            migrate_msg: None,
+        }
+    );
+
+    let cancel = ExecuteMsg::CancelAction { action_id: 0 };
+    let err = execute_as(&mut deps, "someone", cancel.clone()).unwrap_err();
+    assert_eq!(err, ManagerError::Admin(AdminError::NotAdmin {}));
+    execute_as(&mut deps, ROOT, cancel.clone()).unwrap();
+    assert_eq!(queued_actions(&deps), vec![]);
+
+    let err = execute(
+        deps.as_mut(),
+        env_after(100),
+        mock_info(ROOT, &[]),
+        ExecuteMsg::ExecuteAction { action_id: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ManagerError::ActionNotQueued(0));
+    let err = execute_as(&mut deps, ROOT, cancel).unwrap_err();
+    assert_eq!(err, ManagerError::ActionNotQueued(0));
+}
@@ -0,0 +1,117 @@
+use abstract_os::{
+    memory::state::{ADMIN, ASSET_ADDRESSES, CHANNELS, CONTRACT_ADDRESSES, CROSS_CHAIN_ASSETS},
+    objects::{
+        cross_chain::{ChannelRecord, CrossChainAsset},
+        AssetEntry, UncheckedContractEntry,
+    },
+};
+use cosmwasm_std::{DepsMut, Empty, MessageInfo, Response};
+use cw_asset::AssetInfoUnchecked;
+
+use crate::contract::MemoryResult;
+
+/// Adds, updates or removes provided addresses.
+pub fn update_contract_addresses(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<(UncheckedContractEntry, String)>,
+    to_remove: Vec<UncheckedContractEntry>,
+) -> MemoryResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (key, new_address) in to_add.into_iter() {
+        let key = key.check();
+        // validate addr
+        let addr = deps.api.addr_validate(&new_address)?;
+        CONTRACT_ADDRESSES.save(deps.storage, key, &addr)?;
+    }
+
+    for key in to_remove {
+        CONTRACT_ADDRESSES.remove(deps.storage, key.check());
+    }
+
+    Ok(Response::new().add_attribute("action", "updated contract addresses"))
+}
+
+/// Adds, updates or removes provided assets.
+pub fn update_asset_addresses(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<(String, AssetInfoUnchecked)>,
+    to_remove: Vec<String>,
+) -> MemoryResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (name, new_asset) in to_add.into_iter() {
+        // Update function for new or existing keys
+        let asset = new_asset.check(deps.api, None)?;
+        ASSET_ADDRESSES.save(deps.storage, name.into(), &asset)?;
+    }
+
+    for name in to_remove {
+        ASSET_ADDRESSES.remove(deps.storage, AssetEntry::from(name));
+    }
+
+    Ok(Response::new().add_attribute("action", "updated asset addresses"))
+}
+
+/// Adds, updates or removes provided cross-chain assets.
+pub fn update_cross_chain_assets(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<(String, CrossChainAsset)>,
+    to_remove: Vec<String>,
+) -> MemoryResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (name, asset) in to_add.into_iter() {
+        CROSS_CHAIN_ASSETS.save(deps.storage, name.into(), &asset.check())?;
+    }
+
+    for name in to_remove {
+        CROSS_CHAIN_ASSETS.remove(deps.storage, AssetEntry::from(name));
+    }
+
+    Ok(Response::new().add_attribute("action", "updated cross-chain assets"))
+}
+
+/// Adds, updates or removes provided channels.
+pub fn update_channels(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<((String, String), ChannelRecord)>,
+    to_remove: Vec<(String, String)>,
+) -> MemoryResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for ((src_chain, dst_chain), channel) in to_add.into_iter() {
+        let key = (
+            src_chain.to_ascii_lowercase(),
+            dst_chain.to_ascii_lowercase(),
+        );
+        CHANNELS.save(deps.storage, (&key.0, &key.1), &channel)?;
+    }
+
+    for (src_chain, dst_chain) in to_remove {
+        let key = (
+            src_chain.to_ascii_lowercase(),
+            dst_chain.to_ascii_lowercase(),
+        );
+        CHANNELS.remove(deps.storage, (&key.0, &key.1));
+    }
+
+    Ok(Response::new().add_attribute("action", "updated channels"))
+}
+
+pub fn set_admin(deps: DepsMut, info: MessageInfo, admin: String) -> MemoryResult {
+    let admin_addr = deps.api.addr_validate(&admin)?;
+    let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
+    ADMIN.execute_update_admin::<Empty, Empty>(deps, info, Some(admin_addr))?;
+    Ok(Response::default()
+        .add_attribute("previous admin", previous_admin)
+        .add_attribute("admin", admin))
+}
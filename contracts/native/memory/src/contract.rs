@@ -0,0 +1,81 @@
+use abstract_os::{
+    memory::{state::ADMIN, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    MEMORY,
+};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::{commands::*, error::MemoryError, queries};
+
+pub type MemoryResult = Result<Response, MemoryError>;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> MemoryResult {
+    set_contract_version(deps.storage, MEMORY, CONTRACT_VERSION)?;
+
+    // Setup the admin as the creator of the contract
+    ADMIN.set(deps, Some(info.sender))?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> MemoryResult {
+    match msg {
+        ExecuteMsg::UpdateContractAddresses { to_add, to_remove } => {
+            update_contract_addresses(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::UpdateAssetAddresses { to_add, to_remove } => {
+            update_asset_addresses(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::UpdateCrossChainAssets { to_add, to_remove } => {
+            update_cross_chain_assets(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::UpdateChannels { to_add, to_remove } => {
+            update_channels(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::SetAdmin { admin } => set_admin(deps, info, admin),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Assets { names } => to_binary(&queries::query_assets(deps, names)?),
+        QueryMsg::Contracts { names } => to_binary(&queries::query_contracts(deps, names)?),
+        QueryMsg::AssetList {
+            page_token,
+            page_size,
+        } => to_binary(&queries::query_asset_list(deps, page_token, page_size)?),
+        QueryMsg::ContractList {
+            page_token,
+            page_size,
+        } => to_binary(&queries::query_contract_list(deps, page_token, page_size)?),
+        QueryMsg::CrossChainAssets { names } => {
+            to_binary(&queries::query_cross_chain_assets(deps, names)?)
+        }
+        QueryMsg::AssetDenom { name, chain } => {
+            to_binary(&queries::query_asset_denom(deps, name, chain)?)
+        }
+        QueryMsg::Channel {
+            src_chain,
+            dst_chain,
+        } => to_binary(&queries::query_channel(deps, src_chain, dst_chain)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> MemoryResult {
+    set_contract_version(deps.storage, MEMORY, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,115 @@
+use abstract_os::{
+    memory::{
+        state::{ASSET_ADDRESSES, CHANNELS, CONTRACT_ADDRESSES, CROSS_CHAIN_ASSETS},
+        AssetDenomResponse, AssetListResponse, AssetsResponse, ChannelResponse,
+        ContractListResponse, ContractsResponse, CrossChainAssetsResponse,
+    },
+    objects::{cross_chain::CrossChainAsset, AssetEntry, ContractEntry},
+};
+use cosmwasm_std::{Deps, Order, StdError, StdResult};
+use cw_asset::AssetInfo;
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u8 = 15;
+const MAX_LIMIT: u8 = 25;
+
+pub fn query_assets(deps: Deps, names: Vec<String>) -> StdResult<AssetsResponse> {
+    let assets = names
+        .into_iter()
+        .map(|name| {
+            let entry = AssetEntry::from(name);
+            let info = ASSET_ADDRESSES
+                .may_load(deps.storage, entry.clone())?
+                .ok_or_else(|| StdError::generic_err(format!("asset {} not found", entry)))?;
+            Ok((entry, info))
+        })
+        .collect::<StdResult<Vec<(AssetEntry, AssetInfo)>>>()?;
+    Ok(AssetsResponse { assets })
+}
+
+pub fn query_contracts(deps: Deps, names: Vec<ContractEntry>) -> StdResult<ContractsResponse> {
+    let contracts = names
+        .into_iter()
+        .map(|entry| {
+            let addr = CONTRACT_ADDRESSES
+                .may_load(deps.storage, entry.clone())?
+                .ok_or_else(|| StdError::generic_err(format!("contract {} not found", entry)))?;
+            Ok((entry, addr.to_string()))
+        })
+        .collect::<StdResult<Vec<(ContractEntry, String)>>>()?;
+    Ok(ContractsResponse { contracts })
+}
+
+pub fn query_cross_chain_assets(
+    deps: Deps,
+    names: Vec<String>,
+) -> StdResult<CrossChainAssetsResponse> {
+    let assets = names
+        .into_iter()
+        .map(|name| {
+            let entry = AssetEntry::from(name);
+            let asset = load_cross_chain_asset(deps, &entry)?;
+            Ok((entry, asset))
+        })
+        .collect::<StdResult<Vec<(AssetEntry, CrossChainAsset)>>>()?;
+    Ok(CrossChainAssetsResponse { assets })
+}
+
+pub fn query_asset_denom(deps: Deps, name: String, chain: String) -> StdResult<AssetDenomResponse> {
+    let denom = load_cross_chain_asset(deps, &AssetEntry::from(name))?.denom_on(&chain)?;
+    Ok(AssetDenomResponse { denom })
+}
+
+pub fn query_channel(
+    deps: Deps,
+    src_chain: String,
+    dst_chain: String,
+) -> StdResult<ChannelResponse> {
+    let (src_chain, dst_chain) = (
+        src_chain.to_ascii_lowercase(),
+        dst_chain.to_ascii_lowercase(),
+    );
+    let channel = CHANNELS
+        .may_load(deps.storage, (&src_chain, &dst_chain))?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("no channel from {} to {}", src_chain, dst_chain))
+        })?;
+    Ok(ChannelResponse { channel })
+}
+
+fn load_cross_chain_asset(deps: Deps, entry: &AssetEntry) -> StdResult<CrossChainAsset> {
+    CROSS_CHAIN_ASSETS
+        .may_load(deps.storage, entry.clone())?
+        .ok_or_else(|| StdError::generic_err(format!("cross-chain asset {} not found", entry)))
+}
+
+pub fn query_asset_list(
+    deps: Deps,
+    page_token: Option<String>,
+    page_size: Option<u8>,
+) -> StdResult<AssetListResponse> {
+    let limit = page_size.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = page_token.map(|token| Bound::exclusive(AssetEntry::from(token)));
+
+    let assets = ASSET_ADDRESSES
+        .range(deps.storage, start_bound, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(AssetEntry, AssetInfo)>>>()?;
+    Ok(AssetListResponse { assets })
+}
+
+pub fn query_contract_list(
+    deps: Deps,
+    page_token: Option<ContractEntry>,
+    page_size: Option<u8>,
+) -> StdResult<ContractListResponse> {
+    let limit = page_size.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = page_token.map(Bound::exclusive);
+
+    let contracts = CONTRACT_ADDRESSES
+        .range(deps.storage, start_bound, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(entry, addr)| (entry, addr.to_string())))
+        .collect::<StdResult<Vec<(ContractEntry, String)>>>()?;
+    Ok(ContractListResponse { contracts })
+}
@@ -0,0 +1,445 @@
+use std::{cell::RefCell, collections::BTreeMap, marker::PhantomData, rc::Rc};
+
+use abstract_os::{
+    memory::{
+        AssetDenomResponse, AssetListResponse, AssetsResponse, ContractListResponse, ExecuteMsg,
+        InstantiateMsg, QueryMsg,
+    },
+    objects::{
+        cross_chain::{ibc_denom, ChannelRecord, CrossChainAsset},
+        memory::Memory,
+        AssetEntry, ContractEntry, UncheckedContractEntry,
+    },
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    Addr, Binary, ContractResult, DepsMut, Order, OwnedDeps, Storage, SystemError, SystemResult,
+    WasmQuery,
+};
+use cw_asset::{AssetInfo, AssetInfoUnchecked};
+use cw_controllers::AdminError;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::MemoryError,
+};
+
+const ADMIN: &str = "admin";
+const MEMORY_ADDR: &str = "memory";
+
+fn init(deps: DepsMut) {
+    instantiate(deps, mock_env(), mock_info(ADMIN, &[]), InstantiateMsg {}).unwrap();
+}
+
+fn add_assets(deps: DepsMut) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateAssetAddresses {
+            to_add: vec![
+                ("JUNO".into(), AssetInfoUnchecked::native("ujuno")),
+                ("crab".into(), AssetInfoUnchecked::cw20("crab_token")),
+            ],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+}
+
+fn add_contracts(deps: DepsMut) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateContractAddresses {
+            to_add: vec![(
+                UncheckedContractEntry::new("JunoSwap", "crab_juno"),
+                "pair".into(),
+            )],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+}
+
+/// Deps of a contract that queries the memory contract with the provided storage.
+fn deps_querying(memory_storage: &MockStorage) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    deps_logging_queries(memory_storage).0
+}
+
+/// Same as [`deps_querying`], also returns a log of the kind ("raw" or "smart") of each query to memory.
+fn deps_logging_queries(
+    memory_storage: &MockStorage,
+) -> (
+    OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    Rc<RefCell<Vec<&'static str>>>,
+) {
+    let state: BTreeMap<Vec<u8>, Vec<u8>> =
+        memory_storage.range(None, None, Order::Ascending).collect();
+    let log = Rc::new(RefCell::new(vec![]));
+    let query_log = log.clone();
+    let mut querier = MockQuerier::new(&[]);
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Raw { contract_addr, key } if contract_addr == MEMORY_ADDR => {
+            query_log.borrow_mut().push("raw");
+            let value = state.get(key.as_slice()).cloned().unwrap_or_default();
+            SystemResult::Ok(ContractResult::Ok(Binary(value)))
+        }
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == MEMORY_ADDR => {
+            query_log.borrow_mut().push("smart");
+            let mut deps = mock_dependencies();
+            for (key, value) in state.iter() {
+                deps.storage.set(key, value);
+            }
+            let res = from_binary(msg).and_then(|msg| query(deps.as_ref(), mock_env(), msg));
+            SystemResult::Ok(res.into())
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "only queries to memory".into(),
+        }),
+    });
+    let deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+        custom_query_type: PhantomData,
+    };
+    (deps, log)
+}
+
+#[test]
+fn only_admin_updates() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        ExecuteMsg::UpdateAssetAddresses {
+            to_add: vec![("juno".into(), AssetInfoUnchecked::native("ujuno"))],
+            to_remove: vec![],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, MemoryError::Admin(AdminError::NotAdmin {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetAdmin {
+            admin: "new_admin".into(),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetAdmin {
+            admin: ADMIN.into(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, MemoryError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn memory_helper_resolves_entries() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    add_assets(deps.as_mut());
+    add_contracts(deps.as_mut());
+
+    let module_deps = deps_querying(&deps.storage);
+    let memory = Memory {
+        address: Addr::unchecked(MEMORY_ADDR),
+    };
+
+    // names are stored lowercase
+    assert_eq!(
+        memory
+            .query_asset(module_deps.as_ref(), &AssetEntry::new("juno"))
+            .unwrap(),
+        AssetInfo::native("ujuno")
+    );
+    let pair = UncheckedContractEntry::new("junoswap", "crab_juno").check();
+    assert_eq!(
+        memory.query_contract(module_deps.as_ref(), &pair).unwrap(),
+        Addr::unchecked("pair")
+    );
+    let assets = memory
+        .query_assets(
+            module_deps.as_ref(),
+            vec![AssetEntry::new("crab"), AssetEntry::new("juno")],
+        )
+        .unwrap();
+    assert_eq!(
+        assets[&AssetEntry::new("crab")],
+        AssetInfo::cw20(Addr::unchecked("crab_token"))
+    );
+    memory
+        .query_asset(module_deps.as_ref(), &AssetEntry::new("atom"))
+        .unwrap_err();
+}
+
+#[test]
+fn removes_entries() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    add_assets(deps.as_mut());
+    add_contracts(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateAssetAddresses {
+            to_add: vec![],
+            to_remove: vec!["juno".into()],
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateContractAddresses {
+            to_add: vec![],
+            to_remove: vec![UncheckedContractEntry::new("junoswap", "crab_juno")],
+        },
+    )
+    .unwrap();
+
+    let module_deps = deps_querying(&deps.storage);
+    let memory = Memory {
+        address: Addr::unchecked(MEMORY_ADDR),
+    };
+    memory
+        .query_asset(module_deps.as_ref(), &AssetEntry::new("juno"))
+        .unwrap_err();
+    memory
+        .query_contract(
+            module_deps.as_ref(),
+            &ContractEntry {
+                protocol: "junoswap".into(),
+                contract: "crab_juno".into(),
+            },
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn queries() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    add_assets(deps.as_mut());
+    add_contracts(deps.as_mut());
+
+    let res: AssetsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Assets {
+                names: vec!["juno".into()],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.assets,
+        vec![(AssetEntry::new("juno"), AssetInfo::native("ujuno"))]
+    );
+
+    let res: AssetListResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AssetList {
+                page_token: Some("crab".into()),
+                page_size: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.assets.len(), 1);
+    assert_eq!(res.assets[0].0, AssetEntry::new("juno"));
+
+    let res: ContractListResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ContractList {
+                page_token: None,
+                page_size: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.contracts,
+        vec![(
+            ContractEntry {
+                protocol: "junoswap".into(),
+                contract: "crab_juno".into(),
+            },
+            "pair".to_string()
+        )]
+    );
+}
+
+#[test]
+fn cross_chain_registry() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateCrossChainAssets {
+            to_add: vec![(
+                "Atom".into(),
+                CrossChainAsset {
+                    home_chain: "CosmosHub".into(),
+                    denom: "uatom".into(),
+                    ibc_paths: vec![("Osmosis".into(), "transfer/channel-0".into())],
+                },
+            )],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+    let channel = ChannelRecord {
+        port: "transfer".into(),
+        channel_id: "channel-141".into(),
+        counterparty_channel_id: "channel-0".into(),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateChannels {
+            to_add: vec![(("CosmosHub".into(), "osmosis".into()), channel.clone())],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let res: AssetDenomResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AssetDenom {
+                name: "atom".into(),
+                chain: "osmosis".into(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.denom, ibc_denom("transfer/channel-0", "uatom"));
+
+    let module_deps = deps_querying(&deps.storage);
+    let memory = Memory {
+        address: Addr::unchecked(MEMORY_ADDR),
+    };
+    let atom = AssetEntry::new("atom");
+    assert_eq!(
+        memory
+            .query_asset_denom(module_deps.as_ref(), &atom, "cosmoshub")
+            .unwrap(),
+        "uatom"
+    );
+    assert_eq!(
+        memory
+            .query_asset_denom(module_deps.as_ref(), &atom, "Osmosis")
+            .unwrap(),
+        ibc_denom("transfer/channel-0", "uatom")
+    );
+    memory
+        .query_asset_denom(module_deps.as_ref(), &atom, "juno")
+        .unwrap_err();
+    assert_eq!(
+        memory
+            .query_channel(module_deps.as_ref(), "cosmoshub", "Osmosis")
+            .unwrap(),
+        channel
+    );
+    memory
+        .query_channel(module_deps.as_ref(), "osmosis", "cosmoshub")
+        .unwrap_err();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateChannels {
+            to_add: vec![],
+            to_remove: vec![("cosmoshub".into(), "osmosis".into())],
+        },
+    )
+    .unwrap();
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Channel {
+            src_chain: "cosmoshub".into(),
+            dst_chain: "osmosis".into(),
+        },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn memory_helper_batches_queries() {
+    let mut deps = mock_dependencies();
+    init(deps.as_mut());
+    add_assets(deps.as_mut());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::UpdateAssetAddresses {
+            to_add: vec![
+                ("atom".into(), AssetInfoUnchecked::native("uatom")),
+                ("osmo".into(), AssetInfoUnchecked::native("uosmo")),
+            ],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+    let (module_deps, log) = deps_logging_queries(&deps.storage);
+    let memory = Memory {
+        address: Addr::unchecked(MEMORY_ADDR),
+    };
+
+    // few keys are raw-queried
+    let names = vec![AssetEntry::new("atom"), AssetEntry::new("juno")];
+    memory.query_assets(module_deps.as_ref(), names).unwrap();
+    assert_eq!(*log.borrow(), vec!["raw", "raw"]);
+    log.borrow_mut().clear();
+
+    // many keys are resolved with a single query
+    let names: Vec<AssetEntry> = ["atom", "crab", "juno", "osmo"]
+        .into_iter()
+        .map(AssetEntry::new)
+        .collect();
+    let assets = memory.query_assets(module_deps.as_ref(), names).unwrap();
+    assert_eq!(*log.borrow(), vec!["smart"]);
+    assert_eq!(assets.len(), 4);
+    assert_eq!(assets[&AssetEntry::new("osmo")], AssetInfo::native("uosmo"));
+
+    // a missing key still fails the batch
+    let names: Vec<AssetEntry> = ["atom", "crab", "juno", "btc"]
+        .into_iter()
+        .map(AssetEntry::new)
+        .collect();
+    memory
+        .query_assets(module_deps.as_ref(), names)
+        .unwrap_err();
+}
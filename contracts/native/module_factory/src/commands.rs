@@ -0,0 +1,202 @@
+use abstract_os::{
+    manager::ExecuteMsg as ManagerMsg,
+    module_factory::{
+        state::{Context, ADMIN, CONFIG, CONTEXT, MODULE_INIT_BINARIES, OS_TEMPLATES},
+        OsTemplate,
+    },
+    objects::{
+        module::{Module, ModuleInfo, ModuleInitMsg},
+        module_reference::ModuleReference,
+    },
+};
+use abstract_sdk::{get_module, verify_os_manager};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CosmosMsg, DepsMut, MessageInfo, Reply, Response, StdResult, SubMsg,
+    WasmMsg,
+};
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::{contract::ModuleFactoryResult, error::ModuleFactoryError};
+
+pub const CREATE_MODULE_REPLY_ID: u64 = 1;
+
+/// Creates the module for the OS of the calling manager.
+/// Apis are shared, so they are registered on the manager directly.
+/// Other modules are instantiated with the manager as contract admin and registered once instantiated.
+pub fn create_module(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    module: ModuleInfo,
+    root_init_msg: Option<Binary>,
+) -> ModuleFactoryResult {
+    let config = CONFIG.load(deps.storage)?;
+    // Only managers of registered OSs can create modules
+    let core = verify_os_manager(
+        &deps.querier,
+        &msg_info.sender,
+        &config.version_control_address,
+    )?;
+    let module = get_module(&deps.querier, module, &config.version_control_address)?;
+
+    match module.reference.clone() {
+        ModuleReference::Extension(addr) => Ok(Response::new()
+            .add_message(register_msg(&core.manager, &addr, module.clone())?)
+            .add_attribute("action", "create_module")
+            .add_attribute("module", module.info.id())),
+        ModuleReference::App(code_id)
+        | ModuleReference::Perk(code_id)
+        | ModuleReference::Service(code_id) => {
+            let init_msg = ModuleInitMsg {
+                fixed_init: MODULE_INIT_BINARIES.may_load(deps.storage, module.info.clone())?,
+                root_init: root_init_msg,
+            }
+            .format()?;
+            let instantiate = WasmMsg::Instantiate {
+                admin: Some(core.manager.to_string()),
+                code_id,
+                msg: init_msg,
+                funds: msg_info.funds,
+                label: format!("Module: {}", module.info),
+            };
+            let module_id = module.info.id();
+            CONTEXT.save(
+                deps.storage,
+                &Context {
+                    core: Some(core),
+                    module: Some(module),
+                },
+            )?;
+            Ok(Response::new()
+                .add_submessage(SubMsg::reply_on_success(
+                    instantiate,
+                    CREATE_MODULE_REPLY_ID,
+                ))
+                .add_attribute("action", "create_module")
+                .add_attribute("module", module_id))
+        }
+        ModuleReference::Core(_) => Err(ModuleFactoryError::CoreModule(module.info.id())),
+    }
+}
+
+/// Registers the instantiated module on the manager that requested it.
+pub fn register_created_module(deps: DepsMut, reply: Reply) -> ModuleFactoryResult {
+    let context = CONTEXT.load(deps.storage)?;
+    let (core, module) = match context {
+        Context {
+            core: Some(core),
+            module: Some(module),
+        } => (core, module),
+        _ => return Err(ModuleFactoryError::UnexpectedReply(reply.id)),
+    };
+    CONTEXT.save(
+        deps.storage,
+        &Context {
+            core: None,
+            module: None,
+        },
+    )?;
+
+    let module_addr = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(reply)?.contract_address)?;
+    Ok(Response::new()
+        .add_message(register_msg(&core.manager, &module_addr, module)?)
+        .add_attribute("module_address", module_addr))
+}
+
+/// Updates the fixed init messages of modules, versions must be exact.
+pub fn update_factory_binaries(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<(ModuleInfo, Binary)>,
+    to_remove: Vec<ModuleInfo>,
+) -> ModuleFactoryResult {
+    // Only Admin can update binaries
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (module, binary) in to_add {
+        module.assert_version_variant()?;
+        MODULE_INIT_BINARIES.save(deps.storage, module, &binary)?;
+    }
+    for module in to_remove {
+        module.assert_version_variant()?;
+        MODULE_INIT_BINARIES.remove(deps.storage, module);
+    }
+
+    Ok(Response::new().add_attribute("action", "update_factory_binaries"))
+}
+
+/// Adds, replaces or removes OS templates.
+/// Trader grants can only reference modules that are installed by the template.
+pub fn update_os_templates(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<(String, OsTemplate)>,
+    to_remove: Vec<String>,
+) -> ModuleFactoryResult {
+    // Only Admin can update templates
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (name, template) in to_add {
+        let installed: Vec<String> = template
+            .modules
+            .iter()
+            .map(|module| module.module.id())
+            .collect();
+        let not_installed = template
+            .traders
+            .iter()
+            .flat_map(|(api, traders)| std::iter::once(api).chain(traders))
+            .find(|id| !installed.contains(id));
+        if let Some(id) = not_installed {
+            return Err(ModuleFactoryError::InvalidTemplate {
+                template: name,
+                reason: format!("module {} is not installed by the template", id),
+            });
+        }
+        OS_TEMPLATES.save(deps.storage, &name, &template)?;
+    }
+    for name in to_remove {
+        OS_TEMPLATES.remove(deps.storage, &name);
+    }
+
+    Ok(Response::new().add_attribute("action", "update_os_templates"))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    admin: Option<String>,
+    memory_address: Option<String>,
+    version_control_address: Option<String>,
+) -> ModuleFactoryResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(memory_address) = memory_address {
+        config.memory_address = deps.api.addr_validate(&memory_address)?;
+    }
+    if let Some(version_control_address) = version_control_address {
+        config.version_control_address = deps.api.addr_validate(&version_control_address)?;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    if let Some(admin) = admin {
+        let admin = deps.api.addr_validate(&admin)?;
+        ADMIN.set(deps, Some(admin))?;
+    }
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn register_msg(manager: &Addr, module_addr: &Addr, module: Module) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: manager.to_string(),
+        msg: to_binary(&ManagerMsg::RegisterModule {
+            module_addr: module_addr.to_string(),
+            module,
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
@@ -0,0 +1,114 @@
+use abstract_os::{
+    module_factory::{
+        state::{Config, Context, ADMIN, CONFIG, CONTEXT, OS_TEMPLATES},
+        ConfigResponse, ContextResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+        OsTemplateResponse, QueryMsg,
+    },
+    MODULE_FACTORY,
+};
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+use cw2::set_contract_version;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::{commands::*, error::ModuleFactoryError};
+
+pub type ModuleFactoryResult = Result<Response, ModuleFactoryError>;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> ModuleFactoryResult {
+    set_contract_version(deps.storage, MODULE_FACTORY, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            version_control_address: deps.api.addr_validate(&msg.version_control_address)?,
+            memory_address: deps.api.addr_validate(&msg.memory_address)?,
+        },
+    )?;
+    CONTEXT.save(
+        deps.storage,
+        &Context {
+            core: None,
+            module: None,
+        },
+    )?;
+    // Setup the admin as the creator of the contract
+    ADMIN.set(deps, Some(info.sender))?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> ModuleFactoryResult {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            admin,
+            memory_address,
+            version_control_address,
+        } => update_config(deps, info, admin, memory_address, version_control_address),
+        ExecuteMsg::CreateModule { module, init_msg } => {
+            create_module(deps, info, module, init_msg)
+        }
+        ExecuteMsg::UpdateFactoryBinaryMsgs { to_add, to_remove } => {
+            update_factory_binaries(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::UpdateOsTemplates { to_add, to_remove } => {
+            update_os_templates(deps, info, to_add, to_remove)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> ModuleFactoryResult {
+    match msg.id {
+        CREATE_MODULE_REPLY_ID => register_created_module(deps, msg),
+        id => Err(ModuleFactoryError::UnexpectedReply(id)),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let admin = ADMIN.get(deps)?.unwrap();
+            to_binary(&ConfigResponse {
+                owner: admin.into_string(),
+                memory_address: config.memory_address.into_string(),
+                version_control_address: config.version_control_address.into_string(),
+            })
+        }
+        QueryMsg::Context {} => {
+            let context = CONTEXT.load(deps.storage)?;
+            to_binary(&ContextResponse {
+                core: context.core,
+                module: context.module,
+            })
+        }
+        QueryMsg::OsTemplate { name } => to_binary(&OsTemplateResponse {
+            template: OS_TEMPLATES.load(deps.storage, &name)?,
+        }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ModuleFactoryResult {
+    set_contract_version(deps.storage, MODULE_FACTORY, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
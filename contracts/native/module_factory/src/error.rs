@@ -0,0 +1,25 @@
+use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
+use cw_utils::ParseReplyError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ModuleFactoryError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("Core module {0} can only be created by the os factory")]
+    CoreModule(String),
+
+    #[error("Template {template} is invalid: {reason}")]
+    InvalidTemplate { template: String, reason: String },
+
+    #[error("Reply id {0} is not handled")]
+    UnexpectedReply(u64),
+}
@@ -0,0 +1,7 @@
+pub(crate) mod commands;
+pub mod contract;
+pub mod error;
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests;
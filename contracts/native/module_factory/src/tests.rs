@@ -0,0 +1,74 @@
+use abstract_os::{
+    module_factory::{state::MODULE_INIT_BINARIES, ExecuteMsg, InstantiateMsg},
+    objects::module::{ModuleInfo, ModuleVersion},
+    EXCHANGE,
+};
+use cosmwasm_std::{
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    Binary, OwnedDeps,
+};
+use cw_controllers::AdminError;
+
+use crate::{
+    contract::{execute, instantiate},
+    error::ModuleFactoryError,
+};
+
+const ADMIN: &str = "admin";
+
+fn factory_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        InstantiateMsg {
+            version_control_address: "version_control".into(),
+            memory_address: "memory".into(),
+        },
+    )
+    .unwrap();
+    deps
+}
+
+#[test]
+fn updates_init_binaries() {
+    let mut deps = factory_deps();
+    let module = |version| ModuleInfo::from_id(EXCHANGE, version).unwrap();
+    let update = |module| ExecuteMsg::UpdateFactoryBinaryMsgs {
+        to_add: vec![(module, Binary::from(b"{}".to_vec()))],
+        to_remove: vec![],
+    };
+
+    let msg = update(module(ModuleVersion::Version("0.1.0".into())));
+    let err = execute(deps.as_mut(), mock_env(), mock_info("someone", &[]), msg).unwrap_err();
+    assert_eq!(err, ModuleFactoryError::Admin(AdminError::NotAdmin {}));
+
+    // binaries are stored per exact version
+    let msg = update(module(ModuleVersion::Latest {}));
+    let err = execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap_err();
+    assert!(matches!(err, ModuleFactoryError::Std(_)));
+
+    let msg = update(module(ModuleVersion::Version("0.1.0".into())));
+    execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    assert!(MODULE_INIT_BINARIES.has(
+        &deps.storage,
+        module(ModuleVersion::Version("0.1.0".into()))
+    ));
+}
+
+#[test]
+fn only_managers_create_modules() {
+    let mut deps = factory_deps();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        ExecuteMsg::CreateModule {
+            module: ModuleInfo::from_id(EXCHANGE, ModuleVersion::Latest {}).unwrap(),
+            init_msg: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ModuleFactoryError::Std(_)));
+}
@@ -0,0 +1,490 @@
+use abstract_os::{
+    api::{BaseExecuteMsg, ExecuteMsg as ApiMsg},
+    manager::{ExecuteMsg as ManagerMsg, InstantiateMsg as ManagerInstantiateMsg},
+    module_factory::{OsTemplate, OsTemplateResponse, QueryMsg as ModuleFactoryQuery},
+    objects::{
+        gov_type::GovernanceDetails,
+        module::{ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    os_factory::state::{Config, Context, SetupContext, ADMIN, CONFIG, CONTEXT, SETUP_CONTEXT},
+    proxy::{ExecuteMsg as ProxyMsg, InstantiateMsg as ProxyInstantiateMsg},
+    subscription::{DepositHookMsg, ExecuteMsg as SubscriptionMsg},
+    version_control::{Core, ExecuteMsg as VcMsg},
+    CW3_FIXED_MULTISIG, CW3_FLEX_MULTISIG, CW4_GROUP, MANAGER, PROXY,
+};
+use abstract_sdk::{
+    get_module,
+    manager::{query_module_addresses, query_os_id},
+};
+use cosmwasm_std::{
+    coin, to_binary, Addr, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response,
+    StdResult, SubMsg, WasmMsg,
+};
+use cw3_fixed_multisig::msg::{InstantiateMsg as FixedMultisigInstantiateMsg, Voter};
+use cw3_flex_multisig::msg::InstantiateMsg as FlexMultisigInstantiateMsg;
+use cw4_group::msg::{ExecuteMsg as GroupMsg, InstantiateMsg as GroupInstantiateMsg};
+use cw_asset::{Asset, AssetInfo};
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::{contract::OsFactoryResult, error::OsFactoryError};
+
+pub const CREATE_OS_MANAGER_MSG_ID: u64 = 1;
+pub const CREATE_OS_PROXY_MSG_ID: u64 = 2;
+pub const APPLY_TEMPLATE_MSG_ID: u64 = 3;
+pub const CREATE_GOVERNANCE_MSG_ID: u64 = 4;
+pub const CREATE_GROUP_MSG_ID: u64 = 5;
+
+/// Instantiates the manager of a new OS, the proxy is created once the manager exists.
+/// The payment is forwarded to the subscription contract.
+/// The factory is root until the template is applied and the governance contracts are created.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_os(
+    deps: DepsMut,
+    env: Env,
+    governance: GovernanceDetails,
+    payment: Option<Asset>,
+    name: String,
+    description: Option<String>,
+    link: Option<String>,
+    template: Option<String>,
+) -> OsFactoryResult {
+    let mut config = CONFIG.load(deps.storage)?;
+    let os_id = config.next_os_id;
+    let mut response = Response::new();
+    let mut setup = SetupContext::default();
+    match &governance {
+        GovernanceDetails::Monarchy { monarch } => {
+            setup.root = Some(deps.api.addr_validate(monarch)?)
+        }
+        GovernanceDetails::External {
+            governance_address, ..
+        } => setup.root = Some(deps.api.addr_validate(governance_address)?),
+        GovernanceDetails::Multisig {
+            members,
+            threshold,
+            max_voting_period,
+        } => {
+            let create_multisig = WasmMsg::Instantiate {
+                // Handed over to the multisig once it's created
+                admin: Some(env.contract.address.to_string()),
+                code_id: core_code_id(deps.as_ref(), &config, CW3_FIXED_MULTISIG)?,
+                msg: to_binary(&FixedMultisigInstantiateMsg {
+                    voters: members
+                        .iter()
+                        .map(|member| Voter {
+                            addr: member.addr.clone(),
+                            weight: member.weight,
+                        })
+                        .collect(),
+                    threshold: threshold.clone(),
+                    max_voting_period: *max_voting_period,
+                })?,
+                funds: vec![],
+                label: format!("Multisig of Abstract OS: {}", os_id),
+            };
+            response = response.add_submessage(SubMsg::reply_on_success(
+                create_multisig,
+                CREATE_GOVERNANCE_MSG_ID,
+            ));
+        }
+        GovernanceDetails::Dao { members, .. } => {
+            let create_group = WasmMsg::Instantiate {
+                admin: Some(env.contract.address.to_string()),
+                code_id: core_code_id(deps.as_ref(), &config, CW4_GROUP)?,
+                msg: to_binary(&GroupInstantiateMsg {
+                    admin: Some(env.contract.address.to_string()),
+                    members: members.clone(),
+                })?,
+                funds: vec![],
+                label: format!("Group of Abstract OS: {}", os_id),
+            };
+            response = response
+                .add_submessage(SubMsg::reply_on_success(create_group, CREATE_GROUP_MSG_ID));
+            // The multisig is created once the group exists
+            setup.governance = Some(governance.clone());
+        }
+    }
+    if let Some(name) = template {
+        let resp: OsTemplateResponse = deps.querier.query_wasm_smart(
+            &config.module_factory_address,
+            &ModuleFactoryQuery::OsTemplate { name },
+        )?;
+        setup.template = Some(resp.template);
+    }
+    // The factory is root while the OS is set up
+    let root_user = match &setup.root {
+        Some(root) if setup.template.is_none() => root.clone(),
+        _ => {
+            SETUP_CONTEXT.save(deps.storage, &setup)?;
+            env.contract.address.clone()
+        }
+    };
+
+    let manager_code_id = core_code_id(deps.as_ref(), &config, MANAGER)?;
+    let create_manager = WasmMsg::Instantiate {
+        // Handed over to the manager once it's created
+        admin: Some(env.contract.address.to_string()),
+        code_id: manager_code_id,
+        msg: to_binary(&ManagerInstantiateMsg {
+            os_id,
+            root_user: root_user.to_string(),
+            version_control_address: config.version_control_contract.to_string(),
+            module_factory_address: config.module_factory_address.to_string(),
+            subscription_address: config.subscription_address.as_ref().map(Addr::to_string),
+            governance_type: governance.to_string(),
+            name,
+            description,
+            link,
+        })?,
+        funds: vec![],
+        label: format!("Abstract OS: {}", os_id),
+    };
+
+    response = response
+        .add_submessage(SubMsg::reply_on_success(
+            create_manager,
+            CREATE_OS_MANAGER_MSG_ID,
+        ))
+        .add_attribute("action", "create_os")
+        .add_attribute("os_id", os_id.to_string());
+    if let Some(payment) = payment {
+        response = response.add_message(forward_payment(&config, os_id, payment)?);
+    }
+
+    config.next_os_id += 1;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(response)
+}
+
+/// Instantiates the flex multisig on top of the group of a DAO.
+pub fn after_group_create_multisig(deps: DepsMut, env: Env, reply: Reply) -> OsFactoryResult {
+    let config = CONFIG.load(deps.storage)?;
+    let group = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(reply)?.contract_address)?;
+    let mut setup = SETUP_CONTEXT.load(deps.storage)?;
+    let (threshold, max_voting_period) = match setup.governance.take() {
+        Some(GovernanceDetails::Dao {
+            threshold,
+            max_voting_period,
+            ..
+        }) => (threshold, max_voting_period),
+        _ => return Err(OsFactoryError::NoPendingGovernance {}),
+    };
+    let os_id = config.next_os_id - 1;
+
+    let create_multisig = WasmMsg::Instantiate {
+        admin: Some(env.contract.address.to_string()),
+        code_id: core_code_id(deps.as_ref(), &config, CW3_FLEX_MULTISIG)?,
+        msg: to_binary(&FlexMultisigInstantiateMsg {
+            group_addr: group.to_string(),
+            threshold,
+            max_voting_period,
+            executor: None,
+        })?,
+        funds: vec![],
+        label: format!("Multisig of Abstract OS: {}", os_id),
+    };
+    setup.group = Some(group.clone());
+    SETUP_CONTEXT.save(deps.storage, &setup)?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            create_multisig,
+            CREATE_GOVERNANCE_MSG_ID,
+        ))
+        .add_attribute("group_address", group))
+}
+
+/// Makes the multisig its own admin and the root of the OS once it's set up.
+/// The multisig becomes admin of its group, if any.
+pub fn after_governance_set_root(deps: DepsMut, reply: Reply) -> OsFactoryResult {
+    let multisig = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(reply)?.contract_address)?;
+    let mut setup = SETUP_CONTEXT.load(deps.storage)?;
+    let mut msgs: Vec<CosmosMsg> = vec![WasmMsg::UpdateAdmin {
+        contract_addr: multisig.to_string(),
+        admin: multisig.to_string(),
+    }
+    .into()];
+    if let Some(group) = setup.group.take() {
+        msgs.push(
+            WasmMsg::UpdateAdmin {
+                contract_addr: group.to_string(),
+                admin: multisig.to_string(),
+            }
+            .into(),
+        );
+        msgs.push(execute_msg(
+            &group,
+            &GroupMsg::UpdateAdmin {
+                admin: Some(multisig.to_string()),
+            },
+        )?);
+    }
+    setup.root = Some(multisig.clone());
+    SETUP_CONTEXT.save(deps.storage, &setup)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("governance_address", multisig))
+}
+
+/// Makes the manager its own admin and instantiates the proxy, owned by the manager.
+pub fn after_manager_create_proxy(deps: DepsMut, reply: Reply) -> OsFactoryResult {
+    let config = CONFIG.load(deps.storage)?;
+    let manager = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(reply)?.contract_address)?;
+    CONTEXT.save(
+        deps.storage,
+        &Context {
+            os_manager_address: manager.clone(),
+        },
+    )?;
+    let os_id = query_os_id(&deps.querier, &manager)?;
+
+    let proxy_code_id = core_code_id(deps.as_ref(), &config, PROXY)?;
+    let create_proxy = WasmMsg::Instantiate {
+        admin: Some(manager.to_string()),
+        code_id: proxy_code_id,
+        msg: to_binary(&ProxyInstantiateMsg {
+            os_id,
+            memory_address: config.memory_contract.to_string(),
+        })?,
+        funds: vec![],
+        label: format!("Proxy of Abstract OS: {}", os_id),
+    };
+
+    Ok(Response::new()
+        // The manager migrates itself
+        .add_message(WasmMsg::UpdateAdmin {
+            contract_addr: manager.to_string(),
+            admin: manager.to_string(),
+        })
+        .add_submessage(SubMsg::reply_on_success(
+            create_proxy,
+            CREATE_OS_PROXY_MSG_ID,
+        ))
+        .add_attribute("manager_address", manager))
+}
+
+/// Registers the OS on version control, adds the proxy to the manager and gives the manager control over the proxy.
+/// Then installs the modules and proxy assets of the template, if any, or hands root over to the governance.
+pub fn after_proxy_register_os(deps: DepsMut, reply: Reply) -> OsFactoryResult {
+    let config = CONFIG.load(deps.storage)?;
+    let manager = CONTEXT.load(deps.storage)?.os_manager_address;
+    let proxy = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(reply)?.contract_address)?;
+    let os_id = query_os_id(&deps.querier, &manager)?;
+
+    let register_os = execute_msg(
+        &config.version_control_contract,
+        &VcMsg::AddOs {
+            os_id,
+            core: Core {
+                manager: manager.clone(),
+                proxy: proxy.clone(),
+            },
+        },
+    )?;
+    let add_proxy = execute_msg(
+        &manager,
+        &ManagerMsg::UpdateModuleAddresses {
+            to_add: Some(vec![(PROXY.to_string(), proxy.to_string())]),
+            to_remove: None,
+        },
+    )?;
+    let set_proxy_admin = execute_msg(
+        &proxy,
+        &ProxyMsg::SetAdmin {
+            admin: manager.to_string(),
+        },
+    )?;
+
+    let mut msgs = vec![register_os, add_proxy, set_proxy_admin];
+
+    let template = match SETUP_CONTEXT.may_load(deps.storage)? {
+        Some(SetupContext {
+            template: Some(template),
+            ..
+        }) => template,
+        setup => {
+            if let Some(setup) = setup {
+                msgs.push(set_root_msg(&manager, setup.root)?);
+                SETUP_CONTEXT.remove(deps.storage);
+            }
+            CONTEXT.remove(deps.storage);
+            return Ok(Response::new()
+                .add_messages(msgs)
+                .add_attribute("proxy_address", proxy));
+        }
+    };
+    let core = Core { manager, proxy };
+    for module in &template.modules {
+        msgs.push(execute_msg(
+            &core.manager,
+            &ManagerMsg::CreateModule {
+                module: module.module.clone(),
+                init_msg: module.format_init_msg(&core, &config.memory_contract),
+                install_dependencies: false,
+            },
+        )?);
+    }
+    if !template.proxy_assets.is_empty() {
+        msgs.push(execute_msg(
+            &core.manager,
+            &ManagerMsg::ExecOnModule {
+                module_id: PROXY.to_string(),
+                exec_msg: to_binary(&ProxyMsg::UpdateAssets {
+                    to_add: template.proxy_assets,
+                    to_remove: vec![],
+                })?,
+            },
+        )?);
+    }
+    // Traders are granted once the modules exist
+    let apply_template = msgs.pop().unwrap();
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(SubMsg::reply_on_success(
+            apply_template,
+            APPLY_TEMPLATE_MSG_ID,
+        ))
+        .add_attribute("proxy_address", core.proxy)
+        .add_attribute("template_modules", template.modules.len().to_string()))
+}
+
+/// Grants the template's traders on the installed apis and hands root over to the governance of the OS.
+pub fn after_template_grant_traders(deps: DepsMut) -> OsFactoryResult {
+    let manager = CONTEXT.load(deps.storage)?.os_manager_address;
+    let SetupContext { root, template, .. } = SETUP_CONTEXT.load(deps.storage)?;
+    CONTEXT.remove(deps.storage);
+    SETUP_CONTEXT.remove(deps.storage);
+
+    let mut msgs = match template {
+        Some(template) => grant_traders_msgs(deps.as_ref(), &manager, &template)?,
+        None => vec![],
+    };
+    msgs.push(set_root_msg(&manager, root)?);
+
+    Ok(Response::new().add_messages(msgs))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_config(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    admin: Option<String>,
+    memory_contract: Option<String>,
+    version_control_contract: Option<String>,
+    module_factory_address: Option<String>,
+    subscription_address: Option<String>,
+) -> OsFactoryResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(memory_contract) = memory_contract {
+        config.memory_contract = deps.api.addr_validate(&memory_contract)?;
+    }
+    if let Some(version_control_contract) = version_control_contract {
+        config.version_control_contract = deps.api.addr_validate(&version_control_contract)?;
+    }
+    if let Some(module_factory_address) = module_factory_address {
+        config.module_factory_address = deps.api.addr_validate(&module_factory_address)?;
+    }
+    if let Some(subscription_address) = subscription_address {
+        config.subscription_address = Some(deps.api.addr_validate(&subscription_address)?);
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    if let Some(admin) = admin {
+        let admin = deps.api.addr_validate(&admin)?;
+        ADMIN.set(deps, Some(admin))?;
+    }
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn grant_traders_msgs(
+    deps: Deps,
+    manager: &Addr,
+    template: &OsTemplate,
+) -> StdResult<Vec<CosmosMsg>> {
+    template
+        .traders
+        .iter()
+        .map(|(api, traders)| {
+            let addresses = query_module_addresses(deps, manager, traders)?;
+            let update_traders = ApiMsg::<Empty>::Configure(BaseExecuteMsg::UpdateTraders {
+                to_add: Some(addresses.into_values().map(Addr::into_string).collect()),
+                to_remove: None,
+            });
+            execute_msg(
+                manager,
+                &ManagerMsg::ExecOnModule {
+                    module_id: api.clone(),
+                    exec_msg: to_binary(&update_traders)?,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Code-id of the latest version of a core module
+fn core_code_id(deps: Deps, config: &Config, module_id: &str) -> Result<u64, OsFactoryError> {
+    let module = get_module(
+        &deps.querier,
+        ModuleInfo::from_id(module_id, ModuleVersion::Latest {})?,
+        &config.version_control_contract,
+    )?;
+    match module.reference {
+        ModuleReference::Core(code_id) => Ok(code_id),
+        _ => Err(OsFactoryError::NotCoreModule(module_id.to_string())),
+    }
+}
+
+/// Hands root of the OS over from the factory to its governance
+fn set_root_msg(manager: &Addr, root: Option<Addr>) -> Result<CosmosMsg, OsFactoryError> {
+    let root = root.ok_or(OsFactoryError::NoPendingGovernance {})?;
+    Ok(execute_msg(
+        manager,
+        &ManagerMsg::SetRoot {
+            root: root.into_string(),
+            governance_type: None,
+        },
+    )?)
+}
+
+fn forward_payment(
+    config: &Config,
+    os_id: u32,
+    payment: Asset,
+) -> Result<CosmosMsg, OsFactoryError> {
+    let subscription = config
+        .subscription_address
+        .as_ref()
+        .ok_or(OsFactoryError::NoSubscription {})?;
+    match &payment.info {
+        AssetInfo::Native(denom) => Ok(WasmMsg::Execute {
+            contract_addr: subscription.to_string(),
+            msg: to_binary(&SubscriptionMsg::Pay { os_id })?,
+            funds: vec![coin(payment.amount.u128(), denom)],
+        }
+        .into()),
+        _ => Ok(payment.send_msg(subscription, to_binary(&DepositHookMsg::Pay { os_id })?)?),
+    }
+}
+
+fn execute_msg<T: serde::Serialize>(contract: &Addr, msg: &T) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }
+    .into())
+}
@@ -0,0 +1,152 @@
+use abstract_os::{
+    os_factory::{
+        state::{Config, ADMIN, CONFIG},
+        ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    },
+    OS_FACTORY,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+use cw2::set_contract_version;
+use cw_asset::Asset;
+use cw_utils::one_coin;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::{commands::*, error::OsFactoryError};
+
+pub type OsFactoryResult = Result<Response, OsFactoryError>;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> OsFactoryResult {
+    set_contract_version(deps.storage, OS_FACTORY, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            version_control_contract: deps.api.addr_validate(&msg.version_control_address)?,
+            memory_contract: deps.api.addr_validate(&msg.memory_address)?,
+            module_factory_address: deps.api.addr_validate(&msg.module_factory_address)?,
+            subscription_address: None,
+            next_os_id: 0,
+        },
+    )?;
+    // Setup the admin as the creator of the contract
+    ADMIN.set(deps, Some(info.sender))?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> OsFactoryResult {
+    match msg {
+        ExecuteMsg::Receive(msg) => match from_binary(&msg.msg)? {
+            // Pay with cw20 tokens
+            ExecuteMsg::CreateOs {
+                governance,
+                name,
+                description,
+                link,
+                template,
+            } => {
+                let payment = Asset::cw20(info.sender, msg.amount);
+                execute_create_os(
+                    deps,
+                    env,
+                    governance,
+                    Some(payment),
+                    name,
+                    description,
+                    link,
+                    template,
+                )
+            }
+            _ => Err(OsFactoryError::UnexpectedReceive {}),
+        },
+        ExecuteMsg::UpdateConfig {
+            admin,
+            memory_contract,
+            version_control_contract,
+            module_factory_address,
+            subscription_address,
+        } => execute_update_config(
+            deps,
+            info,
+            admin,
+            memory_contract,
+            version_control_contract,
+            module_factory_address,
+            subscription_address,
+        ),
+        ExecuteMsg::CreateOs {
+            governance,
+            name,
+            description,
+            link,
+            template,
+        } => {
+            let payment = if info.funds.is_empty() {
+                None
+            } else {
+                Some(Asset::from(one_coin(&info)?))
+            };
+            execute_create_os(
+                deps,
+                env,
+                governance,
+                payment,
+                name,
+                description,
+                link,
+                template,
+            )
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> OsFactoryResult {
+    match msg.id {
+        CREATE_OS_MANAGER_MSG_ID => after_manager_create_proxy(deps, msg),
+        CREATE_OS_PROXY_MSG_ID => after_proxy_register_os(deps, msg),
+        APPLY_TEMPLATE_MSG_ID => after_template_grant_traders(deps),
+        CREATE_GOVERNANCE_MSG_ID => after_governance_set_root(deps, msg),
+        CREATE_GROUP_MSG_ID => after_group_create_multisig(deps, env, msg),
+        id => Err(OsFactoryError::UnexpectedReply(id)),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let admin = ADMIN.get(deps)?.unwrap();
+    Ok(ConfigResponse {
+        owner: admin.into_string(),
+        version_control_contract: config.version_control_contract.into_string(),
+        memory_contract: config.memory_contract.into_string(),
+        module_factory_address: config.module_factory_address.into_string(),
+        subscription_address: config.subscription_address.map(Into::into),
+        next_os_id: config.next_os_id,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> OsFactoryResult {
+    set_contract_version(deps.storage, OS_FACTORY, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,34 @@
+use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
+use cw_utils::{ParseReplyError, PaymentError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OsFactoryError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Module {0} is not a core module")]
+    NotCoreModule(String),
+
+    #[error("No subscription contract is set to receive the payment")]
+    NoSubscription {},
+
+    #[error("Only OS creation messages can be sent along with cw20 tokens")]
+    UnexpectedReceive {},
+
+    #[error("The governance of the OS that's being created is not set")]
+    NoPendingGovernance {},
+
+    #[error("Reply id {0} is not handled")]
+    UnexpectedReply(u64),
+}
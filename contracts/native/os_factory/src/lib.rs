@@ -0,0 +1,3 @@
+pub(crate) mod commands;
+pub mod contract;
+pub mod error;
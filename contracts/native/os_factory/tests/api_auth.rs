@@ -0,0 +1,166 @@
+mod common;
+
+use abstract_api::ApiError;
+use abstract_os::{
+    api::{self, ApiRequestMsg, BaseExecuteMsg, BaseQueryMsg, TradersResponse},
+    dex::{ApiQueryMsg, RequestMsg},
+    manager,
+    objects::{
+        module::{ModuleInfo, ModuleVersion},
+        AssetEntry,
+    },
+    version_control::Core,
+    EXCHANGE,
+};
+use common::{create_os, deploy, Abstract};
+use cosmwasm_std::{to_binary, Addr, Uint128};
+use cw_multi_test::{App, Executor};
+use dex::error::DexError;
+
+const ROOT: &str = "root";
+const TRADER: &str = "trader";
+
+type DexExecuteMsg = api::ExecuteMsg<RequestMsg>;
+
+/// Creates an OS with the dex api installed
+fn os_with_dex(app: &mut App) -> (Abstract, Core) {
+    let abstr = deploy(app);
+    let core = create_os(app, &abstr, ROOT);
+    app.execute_contract(
+        Addr::unchecked(ROOT),
+        core.manager.clone(),
+        &manager::ExecuteMsg::CreateModule {
+            module: ModuleInfo::from_id(EXCHANGE, ModuleVersion::Latest {}).unwrap(),
+            init_msg: None,
+            install_dependencies: false,
+        },
+        &[],
+    )
+    .unwrap();
+    (abstr, core)
+}
+
+fn update_traders(to_add: Vec<&str>) -> DexExecuteMsg {
+    DexExecuteMsg::Configure(BaseExecuteMsg::UpdateTraders {
+        to_add: Some(to_add.into_iter().map(Into::into).collect()),
+        to_remove: None,
+    })
+}
+
+/// Request that passes authorization but fails in the dex handler
+fn request(proxy_address: Option<String>) -> DexExecuteMsg {
+    DexExecuteMsg::Request(ApiRequestMsg::new(
+        proxy_address,
+        RequestMsg::ProvideLiquidity {
+            assets: vec![(AssetEntry::from("juno"), Uint128::new(100))],
+            dex: Some("junoswap".into()),
+            max_spread: None,
+        },
+    ))
+}
+
+#[test]
+fn manager_configures_traders() {
+    let mut app = App::default();
+    let (abstr, core) = os_with_dex(&mut app);
+
+    // The api verifies the sender against the OS registered on version control
+    app.execute_contract(
+        Addr::unchecked("someone"),
+        abstr.dex.clone(),
+        &update_traders(vec![TRADER]),
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        Addr::unchecked(ROOT),
+        core.manager.clone(),
+        &manager::ExecuteMsg::ExecOnModule {
+            module_id: EXCHANGE.into(),
+            exec_msg: to_binary(&update_traders(vec![TRADER])).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: TradersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &abstr.dex,
+            &api::QueryMsg::<ApiQueryMsg>::Base(BaseQueryMsg::Traders {
+                proxy_address: core.proxy.to_string(),
+            }),
+        )
+        .unwrap();
+    assert_eq!(res.traders, vec![Addr::unchecked(TRADER)]);
+}
+
+#[test]
+fn authorizes_requests() {
+    let mut app = App::default();
+    let (abstr, core) = os_with_dex(&mut app);
+    app.execute_contract(
+        Addr::unchecked(ROOT),
+        core.manager.clone(),
+        &manager::ExecuteMsg::ExecOnModule {
+            module_id: EXCHANGE.into(),
+            exec_msg: to_binary(&update_traders(vec![TRADER])).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let too_few_assets = DexError::TooFewAssets {}.to_string();
+
+    // Without a proxy address only the manager can call the api
+    let err = app
+        .execute_contract(
+            Addr::unchecked(TRADER),
+            abstr.dex.clone(),
+            &request(None),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        DexError::from(ApiError::UnauthorizedApiRequest {}).to_string()
+    );
+
+    // Traders act on the proxy they're registered for
+    let err = app
+        .execute_contract(
+            Addr::unchecked(TRADER),
+            abstr.dex.clone(),
+            &request(Some(core.proxy.to_string())),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), too_few_assets);
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("someone"),
+            abstr.dex.clone(),
+            &request(Some(core.proxy.to_string())),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        DexError::from(ApiError::UnauthorizedTraderApiRequest {}).to_string()
+    );
+
+    // The manager of the OS is verified through version control
+    let err = app
+        .execute_contract(
+            Addr::unchecked(ROOT),
+            core.manager,
+            &manager::ExecuteMsg::ExecOnModule {
+                module_id: EXCHANGE.into(),
+                exec_msg: to_binary(&request(None)).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), too_few_assets);
+}
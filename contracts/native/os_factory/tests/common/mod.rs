@@ -0,0 +1,350 @@
+//! Deploys the Abstract native contracts, the governance contracts, the dex api and a mock etf app on a multi-test [`App`].
+#![allow(dead_code)]
+
+use abstract_os::{
+    api::BaseInstantiateMsg,
+    objects::{
+        gov_type::GovernanceDetails,
+        module::{ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    os_factory, version_control as vc, CW3_FIXED_MULTISIG, CW3_FLEX_MULTISIG, CW4_GROUP, ETF,
+    EXCHANGE, MANAGER, PROXY,
+};
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+
+/// Version of the deployed modules
+pub const VERSION: &str = "0.1.0-rc.3";
+pub const OWNER: &str = "owner";
+
+pub struct Abstract {
+    pub memory: Addr,
+    pub version_control: Addr,
+    pub module_factory: Addr,
+    pub os_factory: Addr,
+    pub dex: Addr,
+}
+
+/// App module that stores the addresses it's instantiated with.
+pub mod mock_etf {
+    use abstract_os::ETF;
+    use cosmwasm_std::{
+        to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    };
+    use cw_storage_plus::Item;
+
+    use super::VERSION;
+
+    #[cosmwasm_schema::cw_serde]
+    pub struct InstantiateMsg {
+        pub proxy_address: String,
+        pub memory_address: String,
+    }
+
+    const INIT_MSG: Item<InstantiateMsg> = Item::new("init_msg");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        cw2::set_contract_version(deps.storage, ETF, VERSION)?;
+        INIT_MSG.save(deps.storage, &msg)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Err(StdError::generic_err("not implemented"))
+    }
+
+    /// Returns the [`InstantiateMsg`]
+    pub fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_binary(&INIT_MSG.load(deps.storage)?)
+    }
+}
+
+fn manager_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        manager::contract::execute,
+        manager::contract::instantiate,
+        manager::contract::query,
+    ))
+}
+
+fn proxy_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        proxy::contract::execute,
+        proxy::contract::instantiate,
+        proxy::contract::query,
+    ))
+}
+
+fn memory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        memory::contract::execute,
+        memory::contract::instantiate,
+        memory::contract::query,
+    ))
+}
+
+fn version_control_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        version_control::contract::execute,
+        version_control::contract::instantiate,
+        version_control::contract::query,
+    ))
+}
+
+fn module_factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            module_factory::contract::execute,
+            module_factory::contract::instantiate,
+            module_factory::contract::query,
+        )
+        .with_reply(module_factory::contract::reply),
+    )
+}
+
+fn os_factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            os_factory::contract::execute,
+            os_factory::contract::instantiate,
+            os_factory::contract::query,
+        )
+        .with_reply(os_factory::contract::reply),
+    )
+}
+
+fn etf_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        mock_etf::execute,
+        mock_etf::instantiate,
+        mock_etf::query,
+    ))
+}
+
+fn cw3_fixed_multisig_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw3_fixed_multisig::contract::execute,
+        cw3_fixed_multisig::contract::instantiate,
+        cw3_fixed_multisig::contract::query,
+    ))
+}
+
+fn cw3_flex_multisig_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw3_flex_multisig::contract::execute,
+        cw3_flex_multisig::contract::instantiate,
+        cw3_flex_multisig::contract::query,
+    ))
+}
+
+fn cw4_group_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw4_group::contract::execute,
+        cw4_group::contract::instantiate,
+        cw4_group::contract::query,
+    ))
+}
+
+fn dex_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        dex::contract::execute,
+        dex::contract::instantiate,
+        dex::contract::query,
+    ))
+}
+
+/// Instantiates the native contracts and the dex api, and registers the core modules, the governance contracts, the api and the etf app on version control.
+pub fn deploy(app: &mut App) -> Abstract {
+    let owner = Addr::unchecked(OWNER);
+    let manager_code_id = app.store_code(manager_contract());
+    let proxy_code_id = app.store_code(proxy_contract());
+    let memory_code_id = app.store_code(memory_contract());
+    let version_control_code_id = app.store_code(version_control_contract());
+    let module_factory_code_id = app.store_code(module_factory_contract());
+    let os_factory_code_id = app.store_code(os_factory_contract());
+    let dex_code_id = app.store_code(dex_contract());
+    let etf_code_id = app.store_code(etf_contract());
+    let fixed_multisig_code_id = app.store_code(cw3_fixed_multisig_contract());
+    let flex_multisig_code_id = app.store_code(cw3_flex_multisig_contract());
+    let group_code_id = app.store_code(cw4_group_contract());
+
+    let memory = app
+        .instantiate_contract(
+            memory_code_id,
+            owner.clone(),
+            &abstract_os::memory::InstantiateMsg {},
+            &[],
+            "memory",
+            None,
+        )
+        .unwrap();
+    let version_control = app
+        .instantiate_contract(
+            version_control_code_id,
+            owner.clone(),
+            &vc::InstantiateMsg {},
+            &[],
+            "version control",
+            None,
+        )
+        .unwrap();
+    let module_factory = app
+        .instantiate_contract(
+            module_factory_code_id,
+            owner.clone(),
+            &abstract_os::module_factory::InstantiateMsg {
+                version_control_address: version_control.to_string(),
+                memory_address: memory.to_string(),
+            },
+            &[],
+            "module factory",
+            None,
+        )
+        .unwrap();
+    let os_factory = app
+        .instantiate_contract(
+            os_factory_code_id,
+            owner.clone(),
+            &os_factory::InstantiateMsg {
+                version_control_address: version_control.to_string(),
+                memory_address: memory.to_string(),
+                module_factory_address: module_factory.to_string(),
+            },
+            &[],
+            "os factory",
+            None,
+        )
+        .unwrap();
+    let dex = app
+        .instantiate_contract(
+            dex_code_id,
+            owner.clone(),
+            &BaseInstantiateMsg {
+                memory_address: memory.to_string(),
+                version_control_address: version_control.to_string(),
+            },
+            &[],
+            "dex api",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        version_control.clone(),
+        &vc::ExecuteMsg::SetFactory {
+            new_factory: os_factory.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let module =
+        |id: &str| ModuleInfo::from_id(id, ModuleVersion::Version(VERSION.into())).unwrap();
+    app.execute_contract(
+        owner,
+        version_control.clone(),
+        &vc::ExecuteMsg::AddModules {
+            modules: vec![
+                (module(MANAGER), ModuleReference::Core(manager_code_id)),
+                (module(PROXY), ModuleReference::Core(proxy_code_id)),
+                (
+                    module(CW3_FIXED_MULTISIG),
+                    ModuleReference::Core(fixed_multisig_code_id),
+                ),
+                (
+                    module(CW3_FLEX_MULTISIG),
+                    ModuleReference::Core(flex_multisig_code_id),
+                ),
+                (module(CW4_GROUP), ModuleReference::Core(group_code_id)),
+                (module(EXCHANGE), ModuleReference::Extension(dex.clone())),
+                (module(ETF), ModuleReference::App(etf_code_id)),
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    Abstract {
+        memory,
+        version_control,
+        module_factory,
+        os_factory,
+        dex,
+    }
+}
+
+/// Creates an OS with `root` as monarch and returns its core contracts.
+pub fn create_os(app: &mut App, abstr: &Abstract, root: &str) -> vc::Core {
+    create_os_with_template(app, abstr, root, None)
+}
+
+/// Creates an OS with `root` as monarch that's set up with the named template.
+pub fn create_os_with_template(
+    app: &mut App,
+    abstr: &Abstract,
+    root: &str,
+    template: Option<&str>,
+) -> vc::Core {
+    let governance = GovernanceDetails::Monarchy {
+        monarch: root.into(),
+    };
+    execute_create_os(app, abstr, root, governance, template).0
+}
+
+/// Creates an OS with the given governance and returns its core contracts along with the factory's response.
+pub fn create_os_with_governance(
+    app: &mut App,
+    abstr: &Abstract,
+    governance: GovernanceDetails,
+) -> (vc::Core, AppResponse) {
+    execute_create_os(app, abstr, OWNER, governance, None)
+}
+
+fn execute_create_os(
+    app: &mut App,
+    abstr: &Abstract,
+    sender: &str,
+    governance: GovernanceDetails,
+    template: Option<&str>,
+) -> (vc::Core, AppResponse) {
+    let config: os_factory::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&abstr.os_factory, &os_factory::QueryMsg::Config {})
+        .unwrap();
+    let response = app
+        .execute_contract(
+            Addr::unchecked(sender),
+            abstr.os_factory.clone(),
+            &os_factory::ExecuteMsg::CreateOs {
+                governance,
+                name: "test os".into(),
+                description: None,
+                link: None,
+                template: template.map(Into::into),
+            },
+            &[],
+        )
+        .unwrap();
+    let res: vc::OsCoreResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &abstr.version_control,
+            &vc::QueryMsg::OsCore {
+                os_id: config.next_os_id,
+            },
+        )
+        .unwrap();
+    (res.os_core, response)
+}
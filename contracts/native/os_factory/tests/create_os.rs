@@ -0,0 +1,110 @@
+mod common;
+
+use abstract_os::{
+    manager,
+    objects::module::{ModuleInfo, ModuleVersion},
+    os_factory, proxy, EXCHANGE, PROXY,
+};
+use common::{create_os, deploy};
+use cosmwasm_std::{Addr, Uint64};
+use cw_multi_test::{App, Executor};
+use cw_storage_plus::Item;
+
+const ROOT: &str = "root";
+
+#[test]
+fn creates_os() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    let core = create_os(&mut app, &abstr, ROOT);
+
+    let config: manager::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&core.manager, &manager::QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.root, ROOT);
+    assert_eq!(config.os_id, Uint64::zero());
+    assert_eq!(
+        config.version_control_address,
+        abstr.version_control.to_string()
+    );
+
+    // the proxy is registered on the manager
+    let modules: manager::ModuleAddressesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &core.manager,
+            &manager::QueryMsg::ModuleAddresses {
+                names: vec![PROXY.into()],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        modules.modules,
+        vec![(PROXY.to_string(), core.proxy.to_string())]
+    );
+
+    // the manager controls the proxy and migrates itself
+    let proxy_admin: Option<Addr> = Item::new("admin")
+        .query(&app.wrap(), core.proxy.clone())
+        .unwrap();
+    assert_eq!(proxy_admin, Some(core.manager.clone()));
+    assert_eq!(
+        app.contract_data(&core.manager).unwrap().admin,
+        Some(core.manager.clone())
+    );
+    assert_eq!(
+        app.contract_data(&core.proxy).unwrap().admin,
+        Some(core.manager.clone())
+    );
+
+    let config: os_factory::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&abstr.os_factory, &os_factory::QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.next_os_id, 1);
+}
+
+#[test]
+fn installs_dex_api() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    let core = create_os(&mut app, &abstr, ROOT);
+
+    let install = manager::ExecuteMsg::CreateModule {
+        module: ModuleInfo::from_id(EXCHANGE, ModuleVersion::Latest {}).unwrap(),
+        init_msg: None,
+        install_dependencies: false,
+    };
+    // only root can install modules
+    app.execute_contract(
+        Addr::unchecked("someone"),
+        core.manager.clone(),
+        &install,
+        &[],
+    )
+    .unwrap_err();
+    app.execute_contract(Addr::unchecked(ROOT), core.manager.clone(), &install, &[])
+        .unwrap();
+
+    let modules: manager::ModuleAddressesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &core.manager,
+            &manager::QueryMsg::ModuleAddresses {
+                names: vec![EXCHANGE.into()],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        modules.modules,
+        vec![(EXCHANGE.to_string(), abstr.dex.to_string())]
+    );
+
+    // the api can act on behalf of the proxy
+    let config: proxy::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&core.proxy, &proxy::QueryMsg::Config {})
+        .unwrap();
+    assert!(config.modules.contains(&abstr.dex.to_string()));
+}
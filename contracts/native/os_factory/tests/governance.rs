@@ -0,0 +1,156 @@
+mod common;
+
+use abstract_os::{
+    manager,
+    objects::{
+        gov_type::GovernanceDetails,
+        module::{ModuleInfo, ModuleVersion},
+    },
+    os_factory, version_control as vc, CW4_GROUP,
+};
+use common::{create_os_with_governance, deploy, OWNER, VERSION};
+use cosmwasm_std::Addr;
+use cw3::{Cw3QueryMsg, VoterDetail, VoterListResponse};
+use cw4::Member;
+use cw_controllers::AdminResponse;
+use cw_multi_test::{App, AppResponse, Executor};
+use cw_utils::{Duration, Threshold};
+
+fn members() -> Vec<Member> {
+    vec![
+        Member {
+            addr: "alice".into(),
+            weight: 1,
+        },
+        Member {
+            addr: "bob".into(),
+            weight: 2,
+        },
+    ]
+}
+
+/// Value of the first attribute with `key` emitted while creating the OS
+fn attribute(res: &AppResponse, key: &str) -> Addr {
+    let attr = res
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == key)
+        .unwrap();
+    Addr::unchecked(&attr.value)
+}
+
+/// Asserts that `multisig` is root of the OS and that the members are its voters
+fn assert_governed_by(app: &App, manager: &Addr, multisig: &Addr, governance_type: &str) {
+    let config: manager::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(manager, &manager::QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.root, multisig.as_str());
+    let info: manager::InfoResponse = app
+        .wrap()
+        .query_wasm_smart(manager, &manager::QueryMsg::Info {})
+        .unwrap();
+    assert_eq!(info.info.governance_type, governance_type);
+
+    let voters: VoterListResponse = app
+        .wrap()
+        .query_wasm_smart(
+            multisig,
+            &Cw3QueryMsg::ListVoters {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let expected: Vec<VoterDetail> = members()
+        .into_iter()
+        .map(|member| VoterDetail {
+            addr: member.addr,
+            weight: member.weight,
+        })
+        .collect();
+    assert_eq!(voters.voters, expected);
+
+    // the multisig migrates itself
+    let contract = app.contract_data(multisig).unwrap();
+    assert_eq!(contract.admin, Some(multisig.clone()));
+}
+
+#[test]
+fn creates_multisig_os() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    let (core, res) = create_os_with_governance(
+        &mut app,
+        &abstr,
+        GovernanceDetails::Multisig {
+            members: members(),
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(3600),
+        },
+    );
+
+    let multisig = attribute(&res, "governance_address");
+    assert_governed_by(&app, &core.manager, &multisig, "multisig");
+}
+
+#[test]
+fn creates_dao_os() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    let (core, res) = create_os_with_governance(
+        &mut app,
+        &abstr,
+        GovernanceDetails::Dao {
+            members: members(),
+            threshold: Threshold::AbsoluteCount { weight: 2 },
+            max_voting_period: Duration::Time(3600),
+        },
+    );
+
+    let multisig = attribute(&res, "governance_address");
+    assert_governed_by(&app, &core.manager, &multisig, "dao");
+
+    // members of the group are changed through the multisig
+    let group = attribute(&res, "group_address");
+    let admin: AdminResponse = app
+        .wrap()
+        .query_wasm_smart(&group, &cw4_group::msg::QueryMsg::Admin {})
+        .unwrap();
+    assert_eq!(admin.admin, Some(multisig.to_string()));
+    assert_eq!(app.contract_data(&group).unwrap().admin, Some(multisig));
+}
+
+#[test]
+fn requires_registered_governance() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abstr.version_control.clone(),
+        &vc::ExecuteMsg::RemoveModule {
+            module: ModuleInfo::from_id(CW4_GROUP, ModuleVersion::Version(VERSION.into())).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abstr.os_factory.clone(),
+        &os_factory::ExecuteMsg::CreateOs {
+            governance: GovernanceDetails::Dao {
+                members: members(),
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                max_voting_period: Duration::Time(3600),
+            },
+            name: "test os".into(),
+            description: None,
+            link: None,
+            template: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+}
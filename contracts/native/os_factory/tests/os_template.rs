@@ -0,0 +1,165 @@
+mod common;
+
+use abstract_os::{
+    api::{self, BaseQueryMsg, TradersResponse},
+    dex::ApiQueryMsg,
+    manager,
+    memory::ExecuteMsg as MemoryMsg,
+    module_factory::{ExecuteMsg as ModuleFactoryMsg, OsTemplate, TemplateModule},
+    objects::{
+        gov_type::GovernanceDetails,
+        module::{ModuleInfo, ModuleVersion},
+        proxy_asset::UncheckedProxyAsset,
+        AssetEntry,
+    },
+    os_factory,
+    proxy::{self, AssetConfigResponse},
+    ETF, EXCHANGE,
+};
+use common::{create_os_with_template, deploy, mock_etf, Abstract, OWNER};
+use cosmwasm_std::{Addr, Empty};
+use cw_asset::AssetInfoUnchecked;
+use cw_multi_test::{App, Executor};
+
+const ROOT: &str = "root";
+const TEMPLATE: &str = "etf";
+
+fn etf_template() -> OsTemplate {
+    let module = |id| TemplateModule {
+        module: ModuleInfo::from_id(id, ModuleVersion::Latest {}).unwrap(),
+        init_msg: None,
+    };
+    OsTemplate {
+        modules: vec![
+            module(EXCHANGE),
+            TemplateModule {
+                init_msg: Some(r#"{"proxy_address":"{proxy}","memory_address":"{memory}"}"#.into()),
+                ..module(ETF)
+            },
+        ],
+        proxy_assets: vec![UncheckedProxyAsset {
+            asset: "juno".into(),
+            value_reference: None,
+        }],
+        traders: vec![(EXCHANGE.into(), vec![ETF.into()])],
+    }
+}
+
+fn add_template(app: &mut App, abstr: &Abstract) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abstr.memory.clone(),
+        &MemoryMsg::UpdateAssetAddresses {
+            to_add: vec![("juno".into(), AssetInfoUnchecked::native("ujuno"))],
+            to_remove: vec![],
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abstr.module_factory.clone(),
+        &ModuleFactoryMsg::UpdateOsTemplates {
+            to_add: vec![(TEMPLATE.into(), etf_template())],
+            to_remove: vec![],
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn creates_os_from_template() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+    add_template(&mut app, &abstr);
+    let core = create_os_with_template(&mut app, &abstr, ROOT, Some(TEMPLATE));
+
+    // root is handed over once the template is applied
+    let config: manager::ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&core.manager, &manager::QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.root, ROOT);
+
+    let modules: manager::ModuleAddressesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &core.manager,
+            &manager::QueryMsg::ModuleAddresses {
+                names: vec![EXCHANGE.into(), ETF.into()],
+            },
+        )
+        .unwrap();
+    let etf = Addr::unchecked(&modules.modules[1].1);
+    assert_eq!(modules.modules[0], (EXCHANGE.into(), abstr.dex.to_string()));
+
+    // placeholders are replaced by the addresses of the OS
+    let init_msg: mock_etf::InstantiateMsg = app.wrap().query_wasm_smart(&etf, &Empty {}).unwrap();
+    assert_eq!(
+        init_msg,
+        mock_etf::InstantiateMsg {
+            proxy_address: core.proxy.to_string(),
+            memory_address: abstr.memory.to_string(),
+        }
+    );
+
+    let asset: AssetConfigResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &core.proxy,
+            &proxy::QueryMsg::AssetConfig {
+                identifier: "juno".into(),
+            },
+        )
+        .unwrap();
+    assert_eq!(asset.proxy_asset.asset, AssetEntry::from("juno"));
+
+    let traders: TradersResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &abstr.dex,
+            &api::QueryMsg::<ApiQueryMsg>::Base(BaseQueryMsg::Traders {
+                proxy_address: core.proxy.to_string(),
+            }),
+        )
+        .unwrap();
+    assert_eq!(traders.traders, vec![etf]);
+}
+
+#[test]
+fn rejects_invalid_templates() {
+    let mut app = App::default();
+    let abstr = deploy(&mut app);
+
+    // traders must be installed by the template
+    let mut template = etf_template();
+    template.modules.pop();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        abstr.module_factory.clone(),
+        &ModuleFactoryMsg::UpdateOsTemplates {
+            to_add: vec![(TEMPLATE.into(), template)],
+            to_remove: vec![],
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // unknown templates fail the OS creation
+    app.execute_contract(
+        Addr::unchecked(ROOT),
+        abstr.os_factory.clone(),
+        &os_factory::ExecuteMsg::CreateOs {
+            governance: GovernanceDetails::Monarchy {
+                monarch: ROOT.into(),
+            },
+            name: "test os".into(),
+            description: None,
+            link: None,
+            template: Some(TEMPLATE.into()),
+        },
+        &[],
+    )
+    .unwrap_err();
+}
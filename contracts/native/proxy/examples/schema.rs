@@ -0,0 +1,31 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use abstract_os::proxy::{
+    AssetConfigResponse, AssetsResponse, BaseAssetResponse, ConfigResponse, ExecuteMsg,
+    HoldingAmountResponse, HoldingValueResponse, InstantiateMsg, LatestNavResponse, LimitsResponse,
+    MigrateMsg, QueryMsg, TotalValueResponse, ValidityResponse,
+};
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(TotalValueResponse), &out_dir);
+    export_schema(&schema_for!(HoldingValueResponse), &out_dir);
+    export_schema(&schema_for!(HoldingAmountResponse), &out_dir);
+    export_schema(&schema_for!(AssetConfigResponse), &out_dir);
+    export_schema(&schema_for!(AssetsResponse), &out_dir);
+    export_schema(&schema_for!(ValidityResponse), &out_dir);
+    export_schema(&schema_for!(BaseAssetResponse), &out_dir);
+    export_schema(&schema_for!(LimitsResponse), &out_dir);
+    export_schema(&schema_for!(LatestNavResponse), &out_dir);
+}
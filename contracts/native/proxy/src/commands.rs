@@ -0,0 +1,54 @@
+use std::collections::BTreeSet;
+
+use std::convert::TryInto;
+
+use abstract_os::{
+    objects::{proxy_asset::ValueRef, ContractEntry, UncheckedContractEntry},
+    proxy::state::{ADMIN, MEMORY, STATE, VAULT_ASSETS},
+};
+use abstract_proxy::{prices::observe_pool_price, ProxyError, ProxyResult};
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult};
+
+/// Accumulates the spot price of every pool that's used to value a proxy asset.
+/// Pools that can't be priced are skipped, so they don't block the observation of the others.
+/// Only the admin and whitelisted modules can observe the prices.
+pub fn update_pool_prices(mut deps: DepsMut, env: Env, msg_info: MessageInfo) -> ProxyResult {
+    let sender = &msg_info.sender;
+    if !ADMIN.is_admin(deps.as_ref(), sender)?
+        && !STATE.load(deps.storage)?.modules.contains(sender)
+    {
+        return Err(ProxyError::SenderNotWhitelisted {});
+    }
+    let memory = MEMORY.load(deps.storage)?;
+    let mut response = Response::new().add_attribute("action", "update_pool_prices");
+    let mut skipped = vec![];
+    for pair in valuation_pools(deps.as_ref())? {
+        match observe_pool_price(deps.branch(), &env, &memory, pair.clone()) {
+            Ok(price) => response = response.add_attribute(pair.to_string(), price.to_string()),
+            Err(_) => skipped.push(pair.to_string()),
+        }
+    }
+    if !skipped.is_empty() {
+        response = response.add_attribute("skipped", skipped.join(","));
+    }
+    Ok(response)
+}
+
+/// Pools that are used to value the proxy assets, including the pairs of LP tokens
+fn valuation_pools(deps: Deps) -> StdResult<BTreeSet<ContractEntry>> {
+    VAULT_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((_, asset)) => match asset.value_reference {
+                Some(ValueRef::Pool { pair }) => Some(Ok(pair)),
+                Some(ValueRef::LiquidityToken {}) => {
+                    let pair: StdResult<UncheckedContractEntry> =
+                        asset.asset.to_string().try_into();
+                    Some(pair.map(|pair| pair.check()))
+                }
+                _ => None,
+            },
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
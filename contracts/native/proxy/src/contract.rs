@@ -0,0 +1,104 @@
+use abstract_os::{
+    objects::memory::Memory,
+    proxy::{
+        state::{State, ADMIN, MEMORY, OS_ID, STATE, VAULT_ASSETS, VAULT_VALUE},
+        AssetConfigResponse, ExecuteMsg, HoldingValueResponse, InstantiateMsg, MigrateMsg,
+        QueryMsg, TotalValueResponse,
+    },
+    PROXY,
+};
+use abstract_proxy::{
+    commands as proxy_commands, queries as proxy_queries, LocalPrices, ProxyResult,
+};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::commands::*;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> ProxyResult {
+    set_contract_version(deps.storage, PROXY, CONTRACT_VERSION)?;
+    OS_ID.save(deps.storage, &msg.os_id)?;
+    STATE.save(deps.storage, &State { modules: vec![] })?;
+    VAULT_VALUE.instantiate(deps.storage)?;
+    MEMORY.save(
+        deps.storage,
+        &Memory {
+            address: deps.api.addr_validate(&msg.memory_address)?,
+        },
+    )?;
+    // The creator (manager) is the admin
+    ADMIN.set(deps, Some(info.sender))?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ProxyResult {
+    match msg {
+        ExecuteMsg::SetAdmin { admin } => proxy_commands::set_admin(deps, info, admin),
+        ExecuteMsg::ModuleAction { msgs } => proxy_commands::execute_action(deps, info, msgs),
+        ExecuteMsg::AddModule { module } => proxy_commands::add_module(deps, info, module),
+        ExecuteMsg::RemoveModule { module } => proxy_commands::remove_module(deps, info, module),
+        ExecuteMsg::UpdateAssets { to_add, to_remove } => {
+            proxy_commands::update_assets(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::UpdatePoolPrices {} => update_pool_prices(deps, env, info),
+        ExecuteMsg::UpdateLimits {
+            max_assets,
+            max_modules,
+        } => proxy_commands::update_limits(deps, info, max_assets, max_modules),
+        ExecuteMsg::ComputeNav { limit } => {
+            proxy_commands::compute_nav(deps, env, info, limit, &LocalPrices)
+        }
+        ExecuteMsg::CancelNav {} => proxy_commands::cancel_nav(deps, info),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&proxy_queries::query_config(deps)?),
+        QueryMsg::TotalValue {} => to_binary(&TotalValueResponse {
+            value: proxy_queries::compute_total_value(deps, env, &LocalPrices)?,
+        }),
+        QueryMsg::HoldingValue { identifier } => to_binary(&HoldingValueResponse {
+            value: proxy_queries::compute_holding_value(deps, &env, identifier, &LocalPrices)?,
+        }),
+        QueryMsg::HoldingAmount { identifier } => {
+            to_binary(&proxy_queries::query_holding_amount(deps, env, identifier)?)
+        }
+        QueryMsg::AssetConfig { identifier } => to_binary(&AssetConfigResponse {
+            proxy_asset: VAULT_ASSETS.load(deps.storage, identifier.into())?,
+        }),
+        QueryMsg::Assets {
+            page_token,
+            page_size,
+        } => to_binary(&proxy_queries::query_proxy_assets(
+            deps, page_token, page_size,
+        )?),
+        QueryMsg::CheckValidity {} => to_binary(&proxy_queries::query_proxy_asset_validity(deps)?),
+        QueryMsg::BaseAsset {} => to_binary(&proxy_queries::query_base_asset(deps)?),
+        QueryMsg::Limits {} => to_binary(&proxy_queries::query_limits(deps)?),
+        QueryMsg::LatestNav {} => to_binary(&proxy_queries::query_latest_nav(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ProxyResult {
+    set_contract_version(deps.storage, PROXY, CONTRACT_VERSION)?;
+    if VAULT_VALUE.status.may_load(deps.storage)?.is_none() {
+        VAULT_VALUE.instantiate(deps.storage)?;
+    }
+    Ok(Response::default())
+}
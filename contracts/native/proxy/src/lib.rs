@@ -0,0 +1,4 @@
+pub(crate) mod commands;
+pub mod contract;
+#[cfg(test)]
+mod tests;
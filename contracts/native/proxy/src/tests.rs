@@ -0,0 +1,605 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use abstract_os::{
+    manager::state::STATUS,
+    memory::state::{ASSET_ADDRESSES, CONTRACT_ADDRESSES},
+    objects::{
+        proxy_asset::{UncheckedProxyAsset, UncheckedValueRef},
+        UncheckedContractEntry,
+    },
+    proxy::{
+        state::{POOL_SPOT_PRICES, POOL_TWAS, POOL_TWA_PERIOD},
+        ExecuteMsg, InstantiateMsg, LatestNavResponse, LimitsResponse, QueryMsg,
+        TotalValueResponse,
+    },
+};
+use abstract_proxy::ProxyError;
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR},
+    to_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Decimal, DepsMut, Env, Order,
+    OwnedDeps, Storage, SystemResult, Uint128, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use cw_asset::AssetInfo;
+use cw_controllers::AdminError;
+
+use crate::contract::{execute, instantiate, query};
+
+const MANAGER: &str = "manager";
+const MEMORY_ADDR: &str = "memory";
+const MODULE: &str = "module";
+const POOL: &str = "pool";
+const LP_TOKEN: &str = "lp_token";
+
+/// Deps of a proxy that holds 100 ujuno, 50 ubjuno, 10 uosmo and 100 of the 1000 LP tokens.
+/// The juno_osmo pool holds 1000 ujuno and 500 uosmo.
+fn proxy_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut memory_storage = MockStorage::new();
+    for (name, denom) in [("juno", "ujuno"), ("bjuno", "ubjuno"), ("osmo", "uosmo")] {
+        ASSET_ADDRESSES
+            .save(&mut memory_storage, name.into(), &AssetInfo::native(denom))
+            .unwrap();
+    }
+    ASSET_ADDRESSES
+        .save(
+            &mut memory_storage,
+            "junoswap/juno_osmo".into(),
+            &AssetInfo::cw20(Addr::unchecked(LP_TOKEN)),
+        )
+        .unwrap();
+    CONTRACT_ADDRESSES
+        .save(
+            &mut memory_storage,
+            UncheckedContractEntry::new("junoswap", "juno_osmo").check(),
+            &Addr::unchecked(POOL),
+        )
+        .unwrap();
+    let state: BTreeMap<Vec<u8>, Vec<u8>> =
+        memory_storage.range(None, None, Order::Ascending).collect();
+
+    let mut querier = MockQuerier::new(&[
+        (
+            MOCK_CONTRACT_ADDR,
+            &[coin(100, "ujuno"), coin(50, "ubjuno"), coin(10, "uosmo")],
+        ),
+        (POOL, &[coin(1000, "ujuno"), coin(500, "uosmo")]),
+    ]);
+    // memory serves its entries, the manager has no modules installed
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Raw { contract_addr, key } if contract_addr == MEMORY_ADDR => {
+            let value = state.get(key.as_slice()).cloned().unwrap_or_default();
+            SystemResult::Ok(ContractResult::Ok(Binary(value)))
+        }
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == LP_TOKEN => {
+            let response = match from_binary(msg).unwrap() {
+                Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                    name: "juno_osmo".into(),
+                    symbol: "LP".into(),
+                    decimals: 6,
+                    total_supply: Uint128::new(1000),
+                }),
+                Cw20QueryMsg::Balance { .. } => to_binary(&BalanceResponse {
+                    balance: Uint128::new(100),
+                }),
+                msg => panic!("unexpected query {:?}", msg),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        }
+        _ => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+    });
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+        custom_query_type: PhantomData,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        InstantiateMsg {
+            os_id: 0,
+            memory_address: MEMORY_ADDR.into(),
+        },
+    )
+    .unwrap();
+    deps
+}
+
+fn add_assets(deps: DepsMut) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdateAssets {
+            to_add: vec![
+                UncheckedProxyAsset {
+                    asset: "juno".into(),
+                    value_reference: None,
+                },
+                UncheckedProxyAsset {
+                    asset: "bjuno".into(),
+                    value_reference: Some(UncheckedValueRef::ValueAs {
+                        asset: "juno".into(),
+                        multiplier: Decimal::percent(200),
+                    }),
+                },
+                UncheckedProxyAsset {
+                    asset: "osmo".into(),
+                    value_reference: Some(UncheckedValueRef::Pool {
+                        pair: "osmo_juno".into(),
+                        exchange: "junoswap".into(),
+                    }),
+                },
+            ],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+}
+
+fn env_after(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env.block.height += seconds / 5;
+    env
+}
+
+/// Observes the pools at the start and the end of a full averaging period
+fn observe_full_period(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    for env in [mock_env(), env_after(POOL_TWA_PERIOD)] {
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MANAGER, &[]),
+            ExecuteMsg::UpdatePoolPrices {},
+        )
+        .unwrap();
+    }
+}
+
+fn total_value(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Uint128 {
+    let res: TotalValueResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TotalValue {}).unwrap()).unwrap();
+    res.value
+}
+
+#[test]
+fn whitelists_modules() {
+    let mut deps = proxy_deps();
+    let action = ExecuteMsg::ModuleAction {
+        msgs: vec![CosmosMsg::Bank(BankMsg::Burn {
+            amount: vec![coin(1, "ujuno")],
+        })],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        action.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::SenderNotWhitelisted {}.to_string()
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::AddModule {
+            module: MODULE.into(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::Admin(AdminError::NotAdmin {}).to_string()
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::AddModule {
+            module: MODULE.into(),
+        },
+    )
+    .unwrap();
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MODULE, &[]), action).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    // the limit can't be set below the current amount
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdateLimits {
+            max_assets: None,
+            max_modules: Some(0),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::LimitBelowCurrent {
+            kind: "modules".into(),
+            current: 1
+        }
+        .to_string()
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdateLimits {
+            max_assets: None,
+            max_modules: Some(1),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::AddModule {
+            module: "other_module".into(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::ModuleLimitReached {}.to_string()
+    );
+
+    let res: LimitsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Limits {}).unwrap()).unwrap();
+    assert_eq!(res.modules, 1);
+    assert_eq!(res.limits.max_modules, 1);
+}
+
+#[test]
+fn suspended_os_blocks_actions() {
+    let mut deps = proxy_deps();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::AddModule {
+            module: MODULE.into(),
+        },
+    )
+    .unwrap();
+    // the manager reports the OS as suspended
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Raw { contract_addr, key }
+            if contract_addr == MANAGER && key.as_slice() == STATUS.as_slice() =>
+        {
+            SystemResult::Ok(ContractResult::Ok(to_binary(&false).unwrap()))
+        }
+        _ => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+    });
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::ModuleAction { msgs: vec![] },
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), ProxyError::OsSuspended {}.to_string());
+}
+
+#[test]
+fn values_assets() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    observe_full_period(&mut deps);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::AddModule {
+            module: MODULE.into(),
+        },
+    )
+    .unwrap();
+
+    // 100 juno + 50 bjuno * 2 + 10 osmo * (1000 / 500)
+    assert_eq!(total_value(&deps), Uint128::new(220));
+
+    // only the admin and modules can lock the assets
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::ComputeNav { limit: Some(2) },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::SenderNotWhitelisted {}.to_string()
+    );
+
+    // the NAV is computed over multiple transactions
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::ComputeNav { limit: Some(2) },
+    )
+    .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "nav"));
+    let res: LatestNavResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::LatestNav {}).unwrap()).unwrap();
+    assert!(res.in_progress);
+    assert_eq!(res.nav, None);
+    // the balances can't change until the last page is valued
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::ModuleAction { msgs: vec![] },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::ActionDuringValuation.to_string()
+    );
+
+    let mut next_block = mock_env();
+    next_block.block.height += 1;
+    execute(
+        deps.as_mut(),
+        next_block,
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::ComputeNav { limit: Some(2) },
+    )
+    .unwrap();
+    let res: LatestNavResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::LatestNav {}).unwrap()).unwrap();
+    assert!(!res.in_progress);
+    let nav = res.nav.unwrap();
+    assert_eq!(nav.value, Uint128::new(220));
+    assert_eq!(nav.per_asset.len(), 3);
+    // the pages were processed in different blocks
+    assert_eq!(nav.start_block, mock_env().block);
+    assert_eq!(nav.block.height, mock_env().block.height + 1);
+    // its age counts from the first page
+    nav.check_consistent(&mock_env().block, 60).unwrap();
+    nav.check_consistent(&env_after(60).block, 60).unwrap();
+    nav.check_consistent(&env_after(61).block, 60).unwrap_err();
+
+    // modules can act again
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::ModuleAction { msgs: vec![] },
+    )
+    .unwrap();
+}
+
+#[test]
+fn admin_cancels_nav_runs() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    observe_full_period(&mut deps);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::ComputeNav { limit: Some(2) },
+    )
+    .unwrap();
+
+    // assets can't change while the NAV is computed
+    let remove_bjuno = ExecuteMsg::UpdateAssets {
+        to_add: vec![],
+        to_remove: vec!["bjuno".into()],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        remove_bjuno.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), ProxyError::ValuationInProgress.to_string());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CancelNav {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::Admin(AdminError::NotAdmin {}).to_string()
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::CancelNav {},
+    )
+    .unwrap();
+    let res: LatestNavResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::LatestNav {}).unwrap()).unwrap();
+    assert!(!res.in_progress);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        remove_bjuno,
+    )
+    .unwrap();
+}
+
+#[test]
+fn observes_valuation_pools() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::AddModule {
+            module: MODULE.into(),
+        },
+    )
+    .unwrap();
+
+    // module actions don't depend on the price observation
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::ModuleAction { msgs: vec![] },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    // only the admin and whitelisted modules observe the prices
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::UpdatePoolPrices {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::SenderNotWhitelisted {}.to_string()
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::UpdatePoolPrices {},
+    )
+    .unwrap();
+    let pair = UncheckedContractEntry::new("junoswap", "juno_osmo").check();
+    assert!(POOL_TWAS.has(&deps.storage, pair));
+
+    // pools can't be used for valuation until a full period has been observed
+    query(deps.as_ref(), mock_env(), QueryMsg::TotalValue {}).unwrap_err();
+    execute(
+        deps.as_mut(),
+        env_after(POOL_TWA_PERIOD),
+        mock_info(MODULE, &[]),
+        ExecuteMsg::UpdatePoolPrices {},
+    )
+    .unwrap();
+    assert_eq!(total_value(&deps), Uint128::new(220));
+}
+
+#[test]
+fn caps_observed_price_moves() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    let pair = UncheckedContractEntry::new("junoswap", "juno_osmo").check();
+    let observe = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, env: Env| {
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(MANAGER, &[]),
+            ExecuteMsg::UpdatePoolPrices {},
+        )
+        .unwrap();
+    };
+    observe(&mut deps, mock_env());
+    assert_eq!(
+        POOL_SPOT_PRICES.load(&deps.storage, pair.clone()).unwrap(),
+        Decimal::percent(50)
+    );
+
+    // the pool is moved right before the next observation
+    deps.querier
+        .update_balance(POOL, vec![coin(1000, "ujuno"), coin(5000, "uosmo")]);
+    observe(&mut deps, env_after(60));
+    assert_eq!(
+        POOL_SPOT_PRICES.load(&deps.storage, pair.clone()).unwrap(),
+        Decimal::percent(55)
+    );
+    // and observed again in the same block
+    observe(&mut deps, env_after(60));
+    assert_eq!(
+        POOL_SPOT_PRICES.load(&deps.storage, pair).unwrap(),
+        Decimal::percent(55)
+    );
+}
+
+#[test]
+fn skips_pools_that_cant_be_priced() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    deps.querier.update_balance(POOL, vec![coin(500, "uosmo")]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdatePoolPrices {},
+    )
+    .unwrap();
+    let pair = UncheckedContractEntry::new("junoswap", "juno_osmo").check();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "skipped" && attr.value == pair.to_string()));
+    assert!(!POOL_TWAS.has(&deps.storage, pair));
+}
+
+#[test]
+fn rejects_unresolvable_assets() {
+    let mut deps = proxy_deps();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdateAssets {
+            to_add: vec![UncheckedProxyAsset {
+                asset: "bjuno".into(),
+                value_reference: Some(UncheckedValueRef::ValueAs {
+                    asset: "juno".into(),
+                    multiplier: Decimal::one(),
+                }),
+            }],
+            to_remove: vec![],
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ProxyError::BadUpdate(_)));
+}
+
+#[test]
+fn values_lp_tokens_at_the_pool_twa() {
+    let mut deps = proxy_deps();
+    add_assets(deps.as_mut());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MANAGER, &[]),
+        ExecuteMsg::UpdateAssets {
+            to_add: vec![UncheckedProxyAsset {
+                asset: "junoswap/juno_osmo".into(),
+                value_reference: Some(UncheckedValueRef::LiquidityToken {}),
+            }],
+            to_remove: vec![],
+        },
+    )
+    .unwrap();
+    observe_full_period(&mut deps);
+
+    // 220 + 100 juno + 50 osmo * (1000 / 500)
+    assert_eq!(total_value(&deps), Uint128::new(420));
+
+    // the pool is moved without changing the product of its reserves
+    deps.querier
+        .update_balance(POOL, vec![coin(2000, "ujuno"), coin(250, "uosmo")]);
+    // the LP tokens are still valued at the TWA price of the pool
+    assert_eq!(total_value(&deps), Uint128::new(420));
+}
@@ -0,0 +1,26 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use abstract_os::version_control::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, ModuleDependenciesResponse,
+    ModuleResponse, ModuleVersionsResponse, ModulesResponse, OsCoreResponse, QueryMsg,
+};
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(OsCoreResponse), &out_dir);
+    export_schema(&schema_for!(ModuleResponse), &out_dir);
+    export_schema(&schema_for!(ModuleVersionsResponse), &out_dir);
+    export_schema(&schema_for!(ModuleDependenciesResponse), &out_dir);
+    export_schema(&schema_for!(ModulesResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+}
@@ -0,0 +1,131 @@
+use abstract_os::{
+    objects::{
+        dependency::Dependency,
+        module::{parse_version, ModuleInfo},
+        module_reference::ModuleReference,
+    },
+    version_control::{
+        state::{ADMIN, FACTORY, MODULE_DEPENDENCIES, MODULE_LIBRARY, OS_ADDRESSES},
+        Core,
+    },
+};
+use cosmwasm_std::{DepsMut, Empty, MessageInfo, Response};
+
+use crate::{contract::VersionControlResult, error::VersionControlError};
+
+/// Registers new module versions. Registered versions are immutable, so they can be trusted by the factories.
+pub fn add_modules(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    modules: Vec<(ModuleInfo, ModuleReference)>,
+) -> VersionControlResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    for (module, reference) in modules {
+        module.assert_version_variant()?;
+        // Versions must be semver to be resolvable
+        parse_version(&module.version.to_string())?;
+        if MODULE_LIBRARY.has(deps.storage, module.clone()) {
+            return Err(VersionControlError::ModuleAlreadyRegistered(
+                module.to_string(),
+            ));
+        }
+        if let ModuleReference::Extension(addr) = &reference {
+            deps.api.addr_validate(addr.as_str())?;
+        }
+        MODULE_LIBRARY.save(deps.storage, module, &reference)?;
+    }
+
+    Ok(Response::new().add_attribute("action", "add_modules"))
+}
+
+/// Removes a module version and its declared dependencies.
+pub fn remove_module(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    module: ModuleInfo,
+) -> VersionControlResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    module.assert_version_variant()?;
+
+    if !MODULE_LIBRARY.has(deps.storage, module.clone()) {
+        return Err(VersionControlError::ModuleNotFound(module.to_string()));
+    }
+    MODULE_LIBRARY.remove(deps.storage, module.clone());
+    MODULE_DEPENDENCIES.remove(deps.storage, module.clone());
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_module")
+        .add_attribute("module", module.to_string()))
+}
+
+/// Sets the dependencies of a registered module version, an empty list clears them.
+pub fn set_module_dependencies(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    module: ModuleInfo,
+    dependencies: Vec<Dependency>,
+) -> VersionControlResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    module.assert_version_variant()?;
+
+    if !MODULE_LIBRARY.has(deps.storage, module.clone()) {
+        return Err(VersionControlError::ModuleNotFound(module.to_string()));
+    }
+    for dependency in &dependencies {
+        dependency.validate()?;
+    }
+    if dependencies.is_empty() {
+        MODULE_DEPENDENCIES.remove(deps.storage, module.clone());
+    } else {
+        MODULE_DEPENDENCIES.save(deps.storage, module.clone(), &dependencies)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_module_dependencies")
+        .add_attribute("module", module.to_string()))
+}
+
+/// Registers the core contracts of a new OS.
+pub fn add_os(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    os_id: u32,
+    core: Core,
+) -> VersionControlResult {
+    // Only Factory can add new OS
+    FACTORY.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    if OS_ADDRESSES.has(deps.storage, os_id) {
+        return Err(VersionControlError::OsAlreadyRegistered(os_id));
+    }
+    OS_ADDRESSES.save(deps.storage, os_id, &core)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_os")
+        .add_attribute("os_id", os_id.to_string())
+        .add_attribute("manager", core.manager)
+        .add_attribute("proxy", core.proxy))
+}
+
+pub fn set_admin(deps: DepsMut, info: MessageInfo, admin: String) -> VersionControlResult {
+    let admin_addr = deps.api.addr_validate(&admin)?;
+    let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
+    ADMIN.execute_update_admin::<Empty, Empty>(deps, info, Some(admin_addr))?;
+    Ok(Response::default()
+        .add_attribute("previous admin", previous_admin)
+        .add_attribute("admin", admin))
+}
+
+pub fn set_factory(deps: DepsMut, info: MessageInfo, new_factory: String) -> VersionControlResult {
+    // Only Admin can set the factory
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let factory_addr = deps.api.addr_validate(&new_factory)?;
+    FACTORY.set(deps, Some(factory_addr))?;
+    Ok(Response::default()
+        .add_attribute("action", "set_factory")
+        .add_attribute("factory", new_factory))
+}
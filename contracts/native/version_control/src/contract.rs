@@ -0,0 +1,79 @@
+use abstract_os::{
+    version_control::{
+        state::{ADMIN, FACTORY},
+        ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    },
+    VERSION_CONTROL,
+};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::{commands::*, error::VersionControlError, queries};
+
+pub type VersionControlResult = Result<Response, VersionControlError>;
+
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> VersionControlResult {
+    set_contract_version(deps.storage, VERSION_CONTROL, CONTRACT_VERSION)?;
+
+    // Setup the admin as the creator of the contract, the factory is set once deployed
+    ADMIN.set(deps.branch(), Some(info.sender.clone()))?;
+    FACTORY.set(deps, Some(info.sender))?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> VersionControlResult {
+    match msg {
+        ExecuteMsg::AddModules { modules } => add_modules(deps, info, modules),
+        ExecuteMsg::RemoveModule { module } => remove_module(deps, info, module),
+        ExecuteMsg::SetModuleDependencies {
+            module,
+            dependencies,
+        } => set_module_dependencies(deps, info, module, dependencies),
+        ExecuteMsg::AddOs { os_id, core } => add_os(deps, info, os_id, core),
+        ExecuteMsg::SetAdmin { new_admin } => set_admin(deps, info, new_admin),
+        ExecuteMsg::SetFactory { new_factory } => set_factory(deps, info, new_factory),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::OsCore { os_id } => to_binary(&queries::query_os_core(deps, os_id)?),
+        QueryMsg::Module { module } => to_binary(&queries::query_module(deps, module)?),
+        QueryMsg::ModuleVersions { provider, name } => {
+            to_binary(&queries::query_module_versions(deps, provider, name)?)
+        }
+        QueryMsg::ModuleDependencies { module } => {
+            to_binary(&queries::query_module_dependencies(deps, module)?)
+        }
+        QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
+        QueryMsg::Modules {
+            page_token,
+            page_size,
+        } => to_binary(&queries::query_modules(deps, page_token, page_size)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> VersionControlResult {
+    set_contract_version(deps.storage, VERSION_CONTROL, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,21 @@
+use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum VersionControlError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("module {0} is already registered, registered versions can't be changed")]
+    ModuleAlreadyRegistered(String),
+
+    #[error("module {0} is not registered")]
+    ModuleNotFound(String),
+
+    #[error("OS with id {0} is already registered")]
+    OsAlreadyRegistered(u32),
+}
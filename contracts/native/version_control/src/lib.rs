@@ -0,0 +1,8 @@
+pub(crate) mod commands;
+pub mod contract;
+pub mod error;
+pub(crate) mod queries;
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests;
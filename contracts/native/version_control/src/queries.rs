@@ -0,0 +1,93 @@
+use abstract_os::{
+    objects::{
+        module::{resolve_version, sort_versions, Module, ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    version_control::{
+        state::{ADMIN, FACTORY, MODULE_DEPENDENCIES, MODULE_LIBRARY, OS_ADDRESSES},
+        ConfigResponse, ModuleDependenciesResponse, ModuleResponse, ModuleVersionsResponse,
+        ModulesResponse, OsCoreResponse,
+    },
+};
+use cosmwasm_std::{Deps, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u8 = 10;
+const MAX_LIMIT: u8 = 20;
+
+pub fn query_os_core(deps: Deps, os_id: u32) -> StdResult<OsCoreResponse> {
+    let os_core = OS_ADDRESSES
+        .may_load(deps.storage, os_id)?
+        .ok_or_else(|| StdError::generic_err(format!("OS with id {} is not active.", os_id)))?;
+    Ok(OsCoreResponse { os_core })
+}
+
+pub fn query_module(deps: Deps, module: ModuleInfo) -> StdResult<ModuleResponse> {
+    let info = resolve_module(deps, module)?;
+    let reference = MODULE_LIBRARY.load(deps.storage, info.clone())?;
+    Ok(ModuleResponse {
+        module: Module { info, reference },
+    })
+}
+
+pub fn query_module_versions(
+    deps: Deps,
+    provider: String,
+    name: String,
+) -> StdResult<ModuleVersionsResponse> {
+    let versions = sort_versions(registered_versions(deps, provider, name)?);
+    Ok(ModuleVersionsResponse { versions })
+}
+
+pub fn query_module_dependencies(
+    deps: Deps,
+    module: ModuleInfo,
+) -> StdResult<ModuleDependenciesResponse> {
+    let info = resolve_module(deps, module)?;
+    let dependencies = MODULE_DEPENDENCIES
+        .may_load(deps.storage, info)?
+        .unwrap_or_default();
+    Ok(ModuleDependenciesResponse { dependencies })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let admin = ADMIN.get(deps)?.unwrap();
+    let factory = FACTORY.get(deps)?.unwrap();
+    Ok(ConfigResponse {
+        admin: admin.into_string(),
+        factory: factory.into_string(),
+    })
+}
+
+pub fn query_modules(
+    deps: Deps,
+    page_token: Option<ModuleInfo>,
+    page_size: Option<u8>,
+) -> StdResult<ModulesResponse> {
+    let limit = page_size.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = page_token.map(Bound::exclusive);
+
+    let modules = MODULE_LIBRARY
+        .range(deps.storage, start_bound, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(ModuleInfo, ModuleReference)>>>()?;
+    Ok(ModulesResponse { modules })
+}
+
+/// Resolves the version of the module to the highest registered version that matches.
+fn resolve_module(deps: Deps, module: ModuleInfo) -> StdResult<ModuleInfo> {
+    let versions = registered_versions(deps, module.provider.clone(), module.name.clone())?;
+    let version = resolve_version(&module.version, versions)?
+        .ok_or_else(|| StdError::generic_err(format!("module {} not found", module)))?;
+    Ok(ModuleInfo {
+        version: ModuleVersion::Version(version),
+        ..module
+    })
+}
+
+fn registered_versions(deps: Deps, provider: String, name: String) -> StdResult<Vec<String>> {
+    MODULE_LIBRARY
+        .prefix((provider, name))
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
@@ -0,0 +1,334 @@
+use abstract_os::{
+    objects::{
+        dependency::Dependency,
+        module::{ModuleInfo, ModuleVersion},
+        module_reference::ModuleReference,
+    },
+    version_control::{
+        state::MODULE_LIBRARY, Core, ExecuteMsg, InstantiateMsg, ModuleDependenciesResponse,
+        ModuleResponse, ModuleVersionsResponse, ModulesResponse, OsCoreResponse, QueryMsg,
+    },
+    ETF, EXCHANGE,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    Addr, DepsMut, OwnedDeps,
+};
+use cw_controllers::AdminError;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::VersionControlError,
+};
+
+const ADMIN: &str = "admin";
+const FACTORY: &str = "factory";
+
+fn module(id: &str, version: &str) -> ModuleInfo {
+    ModuleInfo::from_id(id, ModuleVersion::Version(version.into())).unwrap()
+}
+
+fn init() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        InstantiateMsg {},
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetFactory {
+            new_factory: FACTORY.into(),
+        },
+    )
+    .unwrap();
+    deps
+}
+
+fn add_modules(deps: DepsMut, modules: Vec<(ModuleInfo, ModuleReference)>) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::AddModules { modules },
+    )
+    .unwrap();
+}
+
+fn query_as<T: DeserializeOwned>(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    msg: QueryMsg,
+) -> T {
+    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn only_admin_adds_modules() {
+    let mut deps = init();
+    let msg = ExecuteMsg::AddModules {
+        modules: vec![(
+            module(EXCHANGE, "0.1.0"),
+            ModuleReference::Extension(Addr::unchecked("dex")),
+        )],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(FACTORY, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, VersionControlError::Admin(AdminError::NotAdmin {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    // registered versions can't be overwritten
+    let err = execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        VersionControlError::ModuleAlreadyRegistered(module(EXCHANGE, "0.1.0").to_string())
+    );
+
+    // only exact semver versions are registered
+    for version in [
+        ModuleVersion::Latest {},
+        ModuleVersion::Version("v1".into()),
+        ModuleVersion::Requirement("0.2.0".into()),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::AddModules {
+                modules: vec![(
+                    ModuleInfo::from_id(ETF, version).unwrap(),
+                    ModuleReference::App(1),
+                )],
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, VersionControlError::Std(_)));
+    }
+}
+
+#[test]
+fn resolves_module_versions() {
+    let mut deps = init();
+    add_modules(
+        deps.as_mut(),
+        vec![
+            (module(ETF, "0.1.0"), ModuleReference::App(1)),
+            (module(ETF, "0.10.0"), ModuleReference::App(3)),
+            (module(ETF, "0.2.0"), ModuleReference::App(2)),
+        ],
+    );
+    // deployments still hold legacy "latest" entries
+    MODULE_LIBRARY
+        .save(
+            deps.as_mut().storage,
+            ModuleInfo::from_id(ETF, ModuleVersion::Latest {}).unwrap(),
+            &ModuleReference::App(0),
+        )
+        .unwrap();
+
+    let res: ModuleVersionsResponse = query_as(
+        &deps,
+        QueryMsg::ModuleVersions {
+            provider: "abstract".into(),
+            name: "etf".into(),
+        },
+    );
+    assert_eq!(res.versions, vec!["0.1.0", "0.2.0", "0.10.0"]);
+
+    let resolve = |version| {
+        let res: ModuleResponse = query_as(
+            &deps,
+            QueryMsg::Module {
+                module: ModuleInfo::from_id(ETF, version).unwrap(),
+            },
+        );
+        res.module
+    };
+    let latest = resolve(ModuleVersion::Latest {});
+    assert_eq!(latest.info, module(ETF, "0.10.0"));
+    assert_eq!(latest.reference, ModuleReference::App(3));
+    assert_eq!(
+        resolve(ModuleVersion::Requirement("<0.10".into())).info,
+        module(ETF, "0.2.0")
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Module {
+            module: module(ETF, "0.3.0"),
+        },
+    );
+    assert!(err.is_err());
+}
+
+#[test]
+fn removes_modules_with_dependencies() {
+    let mut deps = init();
+    add_modules(
+        deps.as_mut(),
+        vec![
+            (
+                module(EXCHANGE, "0.1.0"),
+                ModuleReference::Extension(Addr::unchecked("dex")),
+            ),
+            (module(ETF, "0.1.0"), ModuleReference::App(1)),
+        ],
+    );
+    let dependencies = vec![Dependency::new(EXCHANGE, vec!["^0.1".into()])];
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetModuleDependencies {
+            module: module(ETF, "0.1.0"),
+            dependencies: dependencies.clone(),
+        },
+    )
+    .unwrap();
+    let res: ModuleDependenciesResponse = query_as(
+        &deps,
+        QueryMsg::ModuleDependencies {
+            module: ModuleInfo::from_id(ETF, ModuleVersion::Latest {}).unwrap(),
+        },
+    );
+    assert_eq!(res.dependencies, dependencies);
+
+    // malformed dependencies are rejected
+    for dependency in [
+        Dependency::new("dex", vec![]),
+        Dependency::new(EXCHANGE, vec!["not a req".into()]),
+    ] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::SetModuleDependencies {
+                module: module(ETF, "0.1.0"),
+                dependencies: vec![dependency],
+            },
+        )
+        .unwrap_err();
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::RemoveModule {
+            module: module(ETF, "0.1.0"),
+        },
+    )
+    .unwrap();
+    let res: ModulesResponse = query_as(
+        &deps,
+        QueryMsg::Modules {
+            page_token: None,
+            page_size: None,
+        },
+    );
+    assert_eq!(
+        res.modules,
+        vec![(
+            module(EXCHANGE, "0.1.0"),
+            ModuleReference::Extension(Addr::unchecked("dex"))
+        )]
+    );
+
+    // dependencies of removed modules can't be set
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetModuleDependencies {
+            module: module(ETF, "0.1.0"),
+            dependencies,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        VersionControlError::ModuleNotFound(module(ETF, "0.1.0").to_string())
+    );
+}
+
+#[test]
+fn pages_modules() {
+    let mut deps = init();
+    let modules: Vec<(ModuleInfo, ModuleReference)> = (0..3)
+        .map(|minor| {
+            (
+                module(ETF, &format!("0.{}.0", minor)),
+                ModuleReference::App(minor),
+            )
+        })
+        .collect();
+    add_modules(deps.as_mut(), modules.clone());
+
+    let first: ModulesResponse = query_as(
+        &deps,
+        QueryMsg::Modules {
+            page_token: None,
+            page_size: Some(2),
+        },
+    );
+    assert_eq!(first.modules, modules[..2]);
+    let second: ModulesResponse = query_as(
+        &deps,
+        QueryMsg::Modules {
+            page_token: first.modules.last().map(|(info, _)| info.clone()),
+            page_size: Some(2),
+        },
+    );
+    assert_eq!(second.modules, modules[2..]);
+}
+
+#[test]
+fn only_factory_adds_os() {
+    let mut deps = init();
+    let core = Core {
+        manager: Addr::unchecked("manager"),
+        proxy: Addr::unchecked("proxy"),
+    };
+    let msg = ExecuteMsg::AddOs {
+        os_id: 0,
+        core: core.clone(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, VersionControlError::Admin(AdminError::NotAdmin {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(FACTORY, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info(FACTORY, &[]), msg).unwrap_err();
+    assert_eq!(err, VersionControlError::OsAlreadyRegistered(0));
+
+    let res: OsCoreResponse = query_as(&deps, QueryMsg::OsCore { os_id: 0 });
+    assert_eq!(res.os_core, core);
+}
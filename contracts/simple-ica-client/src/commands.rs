@@ -1,179 +1,207 @@
-use abstract_os::objects::{AssetEntry, ContractEntry};
-use abstract_sdk::Resolve;
-use client_osmo_bindings::OsmosisQuery;
-use cosmwasm_std::{CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order, Response, StdError};
-
-use crate::contract::{execute_ibc_query, ProxyResult};
+use abstract_os::objects::{
+    memory::Memory,
+    proxy_asset::{UncheckedProxyAsset, UncheckedValueRef, ValueRef},
+    AssetEntry, ContractEntry,
+};
+use abstract_proxy::{commands as proxy_commands, PriceSource};
+use cosmwasm_std::{
+    Addr, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+};
+use cw_asset::AssetInfoBase;
+use simple_ica::TwapRequest;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::contract::{execute_twap_query, ProxyResult};
 use crate::error::ProxyError;
-use crate::proxy_asset::{UncheckedProxyAsset, ValueRef};
-use crate::queries::*;
-use crate::state::{OraclePrice, POOL_PRICES, TWAP_STATE};
-use crate::state::{ADMIN, MEMORY, STATE, VAULT_ASSETS};
-
-const LIST_SIZE_LIMIT: usize = 15;
-// 30 minute TWAP
-const TWAP_INTERVAL: u64 = 60 * 30;
+use crate::state::{OraclePrice, TwapWindow, POOL_PRICES, TWAP_REQUESTS, TWAP_STATE, TWAP_WINDOWS};
+use crate::state::{ADMIN, MEMORY, VAULT_ASSETS};
 
 pub const TWAP_QUERY: &str = "twap";
+/// Name of the host chain in the memory cross-chain registry
+pub const HOST_CHAIN: &str = "osmosis";
 
-pub fn update_prices(deps: DepsMut, info: MessageInfo, env: Env) -> ProxyResult {
+pub fn update_prices(deps: DepsMut, _info: MessageInfo, env: Env) -> ProxyResult {
     let keys_res: Result<Vec<ContractEntry>, _> = POOL_PRICES
         .keys(deps.storage, None, None, Order::Descending)
         .collect();
     let memory = MEMORY.load(deps.storage)?;
-    let current_time = env.block.time.seconds();
     let pools = memory.query_contracts(deps.as_ref(), keys_res?)?;
-    let mut queries = vec![];
+    let mut pairs = vec![];
     for (pool_name, pool_id) in pools {
-        let ContractEntry {
-            protocol: _,
-            contract,
-        } = pool_name;
-        let lowercase = contract.to_ascii_lowercase();
-        let mut composite: Vec<&str> = lowercase.split('_').collect();
+        let lowercase = pool_name.contract.to_ascii_lowercase();
+        let mut composite: Vec<String> = lowercase.split('_').map(String::from).collect();
         if composite.len() != 2 {
             return Err(ProxyError::Std(StdError::generic_err(
                 "trading pair should be formatted as \"asset1_asset2\".",
             )));
         }
         composite.sort();
-        let quote =
-            AssetEntry::new(&format! {"osmo>{}",composite[0]}).resolve(deps.as_ref(), &memory)?;
-        let quote = match quote {
-            cw_asset::AssetInfoBase::Native(denom) => denom,
-            _ => todo!(),
-        };
-        let base =
-            AssetEntry::new(&format! {"osmo>{}",composite[1]}).resolve(deps.as_ref(), &memory)?;
-        let base = match base {
-            cw_asset::AssetInfoBase::Native(denom) => denom,
-            _ => todo!(),
+        let base = composite.pop().unwrap();
+        let quote = composite.pop().unwrap();
+        pairs.push((pool_name, pool_id, base, quote));
+    }
+    // pools share assets, so each asset is only resolved once
+    let assets = pairs
+        .iter()
+        .flat_map(|(_, _, base, quote)| [base.clone(), quote.clone()])
+        .collect();
+    let denoms = host_denoms(deps.as_ref(), &memory, assets)?;
+
+    let mut requests = vec![];
+    for (pool_name, pool_id, base, quote) in pairs {
+        let window = TWAP_WINDOWS
+            .may_load(deps.storage, pool_name.clone())?
+            .unwrap_or_default();
+        let request = TwapRequest {
+            pool_id: parse_pool_id(&pool_id)?,
+            base_asset: denoms[&base].clone(),
+            quote_asset: denoms[&quote].clone(),
+            kind: window.kind,
+            start_time: env.block.time.minus_seconds(window.duration),
+            // the window ends when the host receives the packet
+            end_time: None,
         };
-        queries.push(cosmwasm_std::QueryRequest::Custom(
-            OsmosisQuery::ArithmeticTwapToNow {
-                id: pool_id.to_string().parse().unwrap(),
-                quote_asset_denom: quote,
-                base_asset_denom: base,
-                start_time: (current_time - TWAP_INTERVAL) as i64,
-            },
-        ))
+        requests.push((pool_name, request))
     }
+    // the results are matched with the pools by request index
+    TWAP_REQUESTS.save(deps.storage, &requests)?;
     let twap_channel = TWAP_STATE.load(deps.storage)?;
-    execute_ibc_query(
+    execute_twap_query(
         deps,
         env,
-        info,
         twap_channel.channel_id,
-        queries,
+        requests.into_iter().map(|(_, request)| request).collect(),
         Some(TWAP_QUERY.into()),
     )
     .map_err(Into::into)
 }
 
-/// Executes actions forwarded by whitelisted contracts
-/// This contracts acts as a proxy contract for the dApps
-pub fn execute_action(deps: DepsMut, msg_info: MessageInfo, msgs: Vec<CosmosMsg>) -> ProxyResult {
-    let state = STATE.load(deps.storage)?;
-    if !state
-        .modules
-        .contains(&deps.api.addr_validate(msg_info.sender.as_str())?)
-    {
-        return Err(ProxyError::SenderNotWhitelisted {});
+/// Denoms of assets on the host chain.
+/// Uses the cross-chain registry and falls back to the "osmo>{asset}" memory entries for assets that aren't registered,
+/// which are resolved in one batch. Registered assets that aren't available on the host chain are an error.
+pub fn host_denoms(
+    deps: Deps,
+    memory: &Memory,
+    assets: BTreeSet<String>,
+) -> StdResult<BTreeMap<String, String>> {
+    let host_entry = |asset: &str| AssetEntry::new(&format!("osmo>{}", asset));
+    let mut denoms = BTreeMap::new();
+    let mut unregistered = vec![];
+    for asset in assets {
+        match memory.may_query_cross_chain_asset(deps, &AssetEntry::new(&asset))? {
+            Some(registered) => {
+                let denom = registered.denom_on(HOST_CHAIN)?;
+                denoms.insert(asset, denom);
+            }
+            None => unregistered.push(asset),
+        }
     }
+    let resolved = memory.query_assets(
+        deps,
+        unregistered.iter().map(|asset| host_entry(asset)).collect(),
+    )?;
+    for asset in unregistered {
+        match resolved.get(&host_entry(&asset)) {
+            Some(AssetInfoBase::Native(denom)) => {
+                denoms.insert(asset, denom.clone());
+            }
+            _ => {
+                return Err(StdError::generic_err(format!(
+                    "asset {} is not a native asset on {}",
+                    asset, HOST_CHAIN
+                )))
+            }
+        }
+    }
+    Ok(denoms)
+}
 
-    Ok(Response::new().add_messages(msgs))
+/// Pool ids are stored in memory as contract addresses.
+pub fn parse_pool_id(pool_id: &Addr) -> StdResult<u64> {
+    pool_id
+        .as_str()
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("invalid osmosis pool id {}", pool_id)))
 }
 
-/// Update the stored vault asset information
-pub fn update_assets(
+/// Set or reset the TWAP window of a pool
+pub fn set_twap_window(
     deps: DepsMut,
     msg_info: MessageInfo,
-    to_add: Vec<UncheckedProxyAsset>,
-    to_remove: Vec<String>,
+    pool: ContractEntry,
+    window: Option<TwapWindow>,
 ) -> ProxyResult {
     // Only Admin can call this method
     ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
-    let memory = &MEMORY.load(deps.storage)?;
-    // Check the vault size to be within the size limit to prevent running out of gas when doing lookups
-    let current_vault_size = VAULT_ASSETS
-        .keys(deps.storage, None, None, Order::Ascending)
-        .count();
-    let delta: i128 = to_add.len() as i128 - to_remove.len() as i128;
-    if current_vault_size as i128 + delta > LIST_SIZE_LIMIT as i128 {
-        return Err(ProxyError::AssetsLimitReached {});
+    if !POOL_PRICES.has(deps.storage, pool.clone()) {
+        return Err(ProxyError::Std(StdError::generic_err(format!(
+            "pool {} is not used to value an asset",
+            pool
+        ))));
     }
-
-    for new_asset in to_add.into_iter() {
-        let checked_asset = new_asset.check(deps.as_ref(), memory)?;
-        match &checked_asset.value_reference {
-            Some(val_ref) => match val_ref {
-                ValueRef::Pool { pair } => {
-                    POOL_PRICES.save(
-                        deps.storage,
-                        pair.clone(),
-                        &OraclePrice {
-                            price: Decimal::zero(),
-                        },
-                    )?;
-                }
-                _ => panic!("not supported"),
-            },
-            _ => (),
+    match window {
+        Some(window) => {
+            if window.duration == 0 {
+                return Err(ProxyError::Std(StdError::generic_err(
+                    "TWAP window can not be empty",
+                )));
+            }
+            TWAP_WINDOWS.save(deps.storage, pool, &window)?
         }
-        VAULT_ASSETS.save(deps.storage, checked_asset.asset.clone(), &checked_asset)?;
+        None => TWAP_WINDOWS.remove(deps.storage, pool),
     }
-
-    for asset_id in to_remove {
-        VAULT_ASSETS.remove(deps.storage, asset_id.into());
-    }
-
-    // Check validity of new configuration
-    let validity_result = query_proxy_asset_validity(deps.as_ref())?;
-    if validity_result.missing_dependencies.is_some()
-        || validity_result.unresolvable_assets.is_some()
-    {
-        return Err(ProxyError::BadUpdate(format!("{:?}", validity_result)));
-    }
-
-    Ok(Response::new().add_attribute("action", "update_proxy_assets"))
+    Ok(Response::new().add_attribute("action", "set_twap_window"))
 }
 
-/// Add a contract to the whitelist
-pub fn add_module(deps: DepsMut, msg_info: MessageInfo, module: String) -> ProxyResult {
-    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
-
-    let mut state = STATE.load(deps.storage)?;
-    if state.modules.contains(&deps.api.addr_validate(&module)?) {
-        return Err(ProxyError::AlreadyInList {});
-    }
+/// Prices of the remote pools, as reported by the last TWAP query.
+pub struct OraclePrices;
 
-    // This is a limit to prevent potentially running out of gas when doing lookups on the modules list
-    if state.modules.len() >= LIST_SIZE_LIMIT {
-        return Err(ProxyError::ModuleLimitReached {});
+impl PriceSource for OraclePrices {
+    fn pair_price(&self, deps: Deps, _memory: &Memory, pair: &ContractEntry) -> StdResult<Decimal> {
+        // The oracle price is that of the second asset of the pair, denominated in the first
+        let price = POOL_PRICES.load(deps.storage, pair.clone())?.price;
+        Ok(price.inv().unwrap_or_default())
     }
-
-    // Add contract to whitelist.
-    state.modules.push(deps.api.addr_validate(&module)?);
-    STATE.save(deps.storage, &state)?;
-
-    // Respond and note the change
-    Ok(Response::new().add_attribute("Added contract to whitelist: ", module))
 }
 
-/// Remove a contract from the whitelist
-pub fn remove_module(deps: DepsMut, msg_info: MessageInfo, module: String) -> ProxyResult {
-    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
-
-    let mut state = STATE.load(deps.storage)?;
-    if !state.modules.contains(&deps.api.addr_validate(&module)?) {
-        return Err(ProxyError::NotInList {});
+/// Update the stored vault asset information.
+/// Assets on the remote chain can only be valued through a pool.
+pub fn update_assets(
+    mut deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<UncheckedProxyAsset>,
+    to_remove: Vec<String>,
+) -> ProxyResult {
+    for asset in to_add.iter() {
+        if !matches!(
+            asset.value_reference,
+            None | Some(UncheckedValueRef::Pool { .. })
+        ) {
+            return Err(ProxyError::BadUpdate(format!(
+                "asset {} can only be valued through a pool on the IBC proxy",
+                asset.asset
+            )));
+        }
     }
-
-    // Remove contract from whitelist.
-    let module_address = deps.api.addr_validate(&module)?;
-    state.modules.retain(|addr| *addr != module_address);
-    STATE.save(deps.storage, &state)?;
-
-    // Respond and note the change
-    Ok(Response::new().add_attribute("Removed contract from whitelist: ", module))
+    let response = proxy_commands::update_assets(deps.branch(), msg_info, to_add, to_remove)?;
+
+    // Pools that value an asset are priced on the next price update
+    let assets = VAULT_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (_, asset) in assets {
+        if let Some(ValueRef::Pool { pair }) = asset.value_reference {
+            if !POOL_PRICES.has(deps.storage, pair.clone()) {
+                POOL_PRICES.save(
+                    deps.storage,
+                    pair,
+                    &OraclePrice {
+                        price: Decimal::zero(),
+                        twap: None,
+                    },
+                )?;
+            }
+        }
+    }
+    Ok(response)
 }
@@ -1,37 +1,36 @@
-use std::str::FromStr;
-
-use crate::msg::{
-    AssetConfigResponse, BaseAssetResponse, HoldingAmountResponse, HoldingValueResponse,
+use crate::msg::{AssetConfigResponse, HoldingValueResponse};
+use crate::state::{
+    State, TWAPInfo, ADMIN, MEMORY, OS_ID, RETRIES, STATE, VAULT_ASSETS, VAULT_VALUE,
 };
-use crate::proxy_asset::ProxyAsset;
-use crate::state::{State, TWAPInfo, ADMIN, MEMORY, OS_ID, STATE, VAULT_ASSETS, RETRIES};
-use abstract_os::objects::{AssetEntry, ContractEntry};
+use abstract_os::objects::ContractEntry;
 use abstract_os::proxy::MigrateMsg;
 use abstract_os::IBC_PROXY;
+use abstract_proxy::{commands as proxy_commands, queries as proxy_queries};
 use abstract_sdk::memory::Memory;
-use abstract_sdk::{Resolve, CONTRACT_VERSION};
+use abstract_sdk::CONTRACT_VERSION;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, IbcMsg,
-    MessageInfo, Order, QueryRequest, QueryResponse, Response, StdError, StdResult,
+    from_binary, to_binary, Coin, CosmosMsg, Deps, DepsMut, Env, IbcMsg, MessageInfo, Order,
+    QueryRequest, QueryResponse, Response, StdError, StdResult,
 };
-use osmosis_std::types::osmosis::twap::v1beta1::ArithmeticTwapToNowResponse;
 
 use crate::commands::*;
 use crate::error::ProxyError;
 use crate::ibc::PACKET_LIFETIME;
-use crate::msg::TotalValueResponse;
 use crate::msg::{
     AccountInfo, AccountResponse, AdminResponse, ExecuteMsg, InstantiateMsg, LatestQueryResponse,
     ListAccountsResponse, QueryMsg,
 };
-use crate::queries::*;
-use crate::state::{OraclePrice, ACCOUNTS, LATEST_QUERIES, POOL_PRICES, TWAP_STATE};
+use crate::msg::{PoolPriceResponse, TotalValueResponse};
+use crate::state::{
+    load_open_account, OraclePrice, ACCOUNTS, LATEST_QUERIES, POOL_PRICES, TWAP_REQUESTS,
+    TWAP_STATE, TWAP_WINDOWS,
+};
 use client_osmo_bindings::{OsmosisMsg, OsmosisQuery};
 use cw2::set_contract_version;
 use simple_ica::client_ibc_msg::PacketMsg;
-use simple_ica::{IbcQueryResponse, ReceiveIcaResponseMsg, StdAck};
+use simple_ica::{ReceiveIcaResponseMsg, StdAck, TwapQueryResponse, TwapRequest};
 pub type ProxyResult = Result<Response, ProxyError>;
 
 const OSMO_SWAP: &str = "twap";
@@ -47,6 +46,7 @@ pub fn instantiate(
     set_contract_version(deps.storage, IBC_PROXY, CONTRACT_VERSION)?;
     OS_ID.save(deps.storage, &msg.os_id)?;
     STATE.save(deps.storage, &State { modules: vec![] })?;
+    VAULT_VALUE.instantiate(deps.storage)?;
     RETRIES.save(deps.storage, &0u8)?;
     TWAP_STATE.save(
         deps.storage,
@@ -69,6 +69,9 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ProxyResult {
     RETRIES.save(deps.storage, &0u8)?;
+    if VAULT_VALUE.status.may_load(deps.storage)?.is_none() {
+        VAULT_VALUE.instantiate(deps.storage)?;
+    }
     Ok(Response::default())
 }
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -86,23 +89,23 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> P
             )?;
             Ok(Response::new())
         }
+        ExecuteMsg::SetTwapWindow { pool, window } => set_twap_window(deps, info, pool, window),
         ExecuteMsg::UpdatePrices {} => update_prices(deps, info, env),
-        ExecuteMsg::ModuleAction { msgs } => execute_action(deps, info, msgs),
-        ExecuteMsg::AddModule { module } => add_module(deps, info, module),
-        ExecuteMsg::RemoveModule { module } => remove_module(deps, info, module),
+        ExecuteMsg::ModuleAction { msgs } => proxy_commands::execute_action(deps, info, msgs),
+        ExecuteMsg::AddModule { module } => proxy_commands::add_module(deps, info, module),
+        ExecuteMsg::RemoveModule { module } => proxy_commands::remove_module(deps, info, module),
         ExecuteMsg::UpdateAssets { to_add, to_remove } => {
-            Ok(update_assets(deps, info, to_add, to_remove).unwrap())
+            update_assets(deps, info, to_add, to_remove)
         }
-        ExecuteMsg::SetAdmin { admin } => {
-            let admin_addr = deps.api.addr_validate(&admin)?;
-            let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
-            ADMIN
-                .execute_update_admin::<Empty, Empty>(deps, info, Some(admin_addr))
-                .unwrap();
-            Ok(Response::default()
-                .add_attribute("previous admin", previous_admin)
-                .add_attribute("admin", admin))
+        ExecuteMsg::UpdateLimits {
+            max_assets,
+            max_modules,
+        } => proxy_commands::update_limits(deps, info, max_assets, max_modules),
+        ExecuteMsg::ComputeNav { limit } => {
+            proxy_commands::compute_nav(deps, env, info, limit, &OraclePrices)
         }
+        ExecuteMsg::CancelNav {} => proxy_commands::cancel_nav(deps, info),
+        ExecuteMsg::SetAdmin { admin } => proxy_commands::set_admin(deps, info, admin),
         ExecuteMsg::SendMsgs { msgs } => {
             execute_send_msgs(deps, env, info, msgs).map_err(Into::into)
         }
@@ -118,11 +121,20 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> P
             transfer_channel_id,
             coins,
         } => execute_send_funds(deps, env, info, coins, transfer_channel_id).map_err(Into::into),
-        ExecuteMsg::ReceiveIcaResponse(resp) => handle_ica_resp(deps, env, resp),
+        ExecuteMsg::ReceiveIcaResponse(resp) => handle_ica_resp(deps, env, info, resp),
     }
 }
 
-pub fn handle_ica_resp(deps: DepsMut, env: Env, resp: ReceiveIcaResponseMsg) -> ProxyResult {
+pub fn handle_ica_resp(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    resp: ReceiveIcaResponseMsg,
+) -> ProxyResult {
+    // the responses are sent by the proxy itself when the packet is acknowledged
+    if info.sender != env.contract.address {
+        return Err(ProxyError::UnauthorizedIcaResponse {});
+    }
     let ReceiveIcaResponseMsg { id, msg } = resp;
 
     let unwrap_res = match msg {
@@ -131,23 +143,20 @@ pub fn handle_ica_resp(deps: DepsMut, env: Env, resp: ReceiveIcaResponseMsg) ->
     }?;
 
     if id == TWAP_QUERY {
-        let keys_res: Result<Vec<ContractEntry>, _> = POOL_PRICES
-            .keys(deps.storage, None, None, Order::Descending)
-            .collect();
-        let twap_results: IbcQueryResponse = from_binary(&unwrap_res)?;
-        let decoded: StdResult<Vec<ArithmeticTwapToNowResponse>> = twap_results
-            .results
-            .iter()
-            .map(|binar| from_binary(binar))
-            .collect();
-        let values: Vec<(ContractEntry, ArithmeticTwapToNowResponse)> =
-            keys_res?.into_iter().zip(decoded?).collect();
-        for (pool, price) in values {
+        let TwapQueryResponse { results } = from_binary(&unwrap_res)?;
+        // the results are in the order of the requests of the last query
+        let requests = TWAP_REQUESTS.may_load(deps.storage)?.unwrap_or_default();
+        for ((pool, request), twap) in requests.into_iter().zip(results) {
+            // skips the results of an older query and pools that were removed since
+            if !twap.answers(&request) || !POOL_PRICES.has(deps.storage, pool.clone()) {
+                continue;
+            }
             POOL_PRICES.save(
                 deps.storage,
                 pool,
                 &OraclePrice {
-                    price: Decimal::from_str(&price.arithmetic_twap)?,
+                    price: twap.twap,
+                    twap: Some(twap),
                 },
             )?;
         }
@@ -155,17 +164,22 @@ pub fn handle_ica_resp(deps: DepsMut, env: Env, resp: ReceiveIcaResponseMsg) ->
         // Send everything back
         let twap_info = TWAP_STATE.load(deps.storage)?;
         let mem = MEMORY.load(deps.storage)?;
-        // get channel id for osmo-> juno transfers
-        let osmo_to_juno_channel = mem.query_contract(
-            deps.as_ref(),
-            &ContractEntry {
-                protocol: "hermes".into(),
-                contract: "osmo>juno".into(),
-            },
-        )?;
+        // get channel id for osmo-> juno transfers, registered channels take precedence
+        let osmo_to_juno_channel = match mem.query_channel(deps.as_ref(), HOST_CHAIN, "juno") {
+            Ok(channel) => channel.channel_id,
+            Err(_) => mem
+                .query_contract(
+                    deps.as_ref(),
+                    &ContractEntry {
+                        protocol: "hermes".into(),
+                        contract: "osmo>juno".into(),
+                    },
+                )?
+                .to_string(),
+        };
         let packet = PacketMsg::SendAllBack {
             sender: env.contract.address.to_string(),
-            transfer_channel: osmo_to_juno_channel.to_string(),
+            transfer_channel: osmo_to_juno_channel,
         };
         let msg = IbcMsg::SendPacket {
             channel_id: twap_info.channel_id,
@@ -190,8 +204,8 @@ pub fn execute_send_msgs(
     }
     let twap = TWAP_STATE.load(deps.storage)?;
     let callback_id = Some(OSMO_SWAP.to_string());
-    // ensure the channel exists (not found if not registered)
-    ACCOUNTS.load(deps.storage, &twap.channel_id)?;
+    // ensure the channel exists and is open
+    load_open_account(deps.storage, &twap.channel_id)?;
 
     // construct a packet to send
     let sender = env.contract.address.into();
@@ -213,13 +227,15 @@ pub fn execute_send_msgs(
 }
 
 pub fn execute_ibc_query(
-    _deps: DepsMut,
+    deps: DepsMut,
     env: Env,
     info: MessageInfo,
     channel_id: String,
     msgs: Vec<QueryRequest<OsmosisQuery>>,
     callback_id: Option<String>,
 ) -> StdResult<Response> {
+    // ensure the channel exists and is open
+    load_open_account(deps.storage, &channel_id)?;
     // construct a packet to send
     let sender = info.sender.into();
     let packet = PacketMsg::IbcQuery {
@@ -239,14 +255,42 @@ pub fn execute_ibc_query(
     Ok(res)
 }
 
+pub fn execute_twap_query(
+    deps: DepsMut,
+    env: Env,
+    channel_id: String,
+    requests: Vec<TwapRequest>,
+    callback_id: Option<String>,
+) -> StdResult<Response> {
+    // ensure the channel exists and is open
+    load_open_account(deps.storage, &channel_id)?;
+
+    // construct a packet to send
+    let packet = PacketMsg::TwapQuery {
+        sender: env.contract.address.to_string(),
+        requests,
+        callback_id,
+    };
+    let msg = IbcMsg::SendPacket {
+        channel_id,
+        data: to_binary(&packet)?,
+        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+    };
+
+    let res = Response::new()
+        .add_message(msg)
+        .add_attribute("action", "handle_twap_query");
+    Ok(res)
+}
+
 pub fn execute_check_remote_balance(
     deps: DepsMut,
     env: Env,
     _info: MessageInfo,
     channel_id: String,
 ) -> StdResult<Response> {
-    // ensure the channel exists (not found if not registered)
-    ACCOUNTS.load(deps.storage, &channel_id)?;
+    // ensure the channel exists and is open
+    load_open_account(deps.storage, &channel_id)?;
 
     // construct a packet to send
     let packet = PacketMsg::Balances {};
@@ -277,7 +321,7 @@ pub fn execute_send_funds(
 
     let ica_channel_id = TWAP_STATE.load(deps.storage)?.channel_id;
     // load remote account
-    let data = ACCOUNTS.load(deps.storage, &ica_channel_id)?;
+    let data = load_open_account(deps.storage, &ica_channel_id)?;
     let remote_addr = match data.remote_addr {
         Some(addr) => addr,
         None => {
@@ -307,20 +351,15 @@ pub fn execute_send_funds(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
     match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Config {} => to_binary(&proxy_queries::query_config(deps)?),
         QueryMsg::TotalValue {} => to_binary(&TotalValueResponse {
-            value: compute_total_value(deps, env)?,
+            value: proxy_queries::compute_total_value(deps, env, &OraclePrices)?,
         }),
         QueryMsg::HoldingAmount { identifier } => {
-            let vault_asset: AssetEntry = identifier.into();
-            let memory = MEMORY.load(deps.storage)?;
-            let asset_info = vault_asset.resolve(deps, &memory)?;
-            to_binary(&HoldingAmountResponse {
-                amount: asset_info.query_balance(&deps.querier, env.contract.address)?,
-            })
+            to_binary(&proxy_queries::query_holding_amount(deps, env, identifier)?)
         }
         QueryMsg::HoldingValue { identifier } => to_binary(&HoldingValueResponse {
-            value: compute_holding_value(deps, &env, identifier)?,
+            value: proxy_queries::compute_holding_value(deps, &env, identifier, &OraclePrices)?,
         }),
         QueryMsg::AssetConfig { identifier } => to_binary(&AssetConfigResponse {
             proxy_asset: VAULT_ASSETS.load(deps.storage, identifier.into())?,
@@ -328,33 +367,32 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
         QueryMsg::Assets {
             page_token,
             page_size,
-        } => to_binary(&query_proxy_assets(deps, page_token, page_size)?),
-        QueryMsg::CheckValidity {} => to_binary(&query_proxy_asset_validity(deps)?),
-        QueryMsg::BaseAsset {} => {
-            let res: Result<Vec<(AssetEntry, ProxyAsset)>, _> = VAULT_ASSETS
-                .range(deps.storage, None, None, Order::Ascending)
-                .collect();
-            let maybe_base_asset: Vec<(AssetEntry, ProxyAsset)> = res?
-                .into_iter()
-                .filter(|(_, p)| p.value_reference.is_none())
-                .collect();
-            if maybe_base_asset.len() != 1 {
-                Err(StdError::generic_err("No base asset configured."))
-            } else {
-                to_binary(&BaseAssetResponse {
-                    base_asset: maybe_base_asset[0].1.to_owned(),
-                })
-            }
-        }
+        } => to_binary(&proxy_queries::query_proxy_assets(
+            deps, page_token, page_size,
+        )?),
+        QueryMsg::CheckValidity {} => to_binary(&proxy_queries::query_proxy_asset_validity(deps)?),
+        QueryMsg::BaseAsset {} => to_binary(&proxy_queries::query_base_asset(deps)?),
         QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
         QueryMsg::Account { channel_id } => to_binary(&query_account(deps, channel_id)?),
         QueryMsg::ListAccounts {} => to_binary(&query_list_accounts(deps)?),
         QueryMsg::LatestQueryResult { channel_id } => {
             to_binary(&query_latest_ibc_query_result(deps, channel_id)?)
         }
+        QueryMsg::PoolPrice { pool } => to_binary(&query_pool_price(deps, pool)?),
+        QueryMsg::Limits {} => to_binary(&proxy_queries::query_limits(deps)?),
+        QueryMsg::LatestNav {} => to_binary(&proxy_queries::query_latest_nav(deps)?),
     }
 }
 
+fn query_pool_price(deps: Deps, pool: ContractEntry) -> StdResult<PoolPriceResponse> {
+    Ok(PoolPriceResponse {
+        price: POOL_PRICES.load(deps.storage, pool.clone())?,
+        window: TWAP_WINDOWS
+            .may_load(deps.storage, pool)?
+            .unwrap_or_default(),
+    })
+}
+
 fn query_account(deps: Deps, channel_id: String) -> StdResult<AccountResponse> {
     let account = ACCOUNTS.load(deps.storage, &channel_id)?;
     Ok(account.into())
@@ -65,17 +65,21 @@ pub fn ibc_channel_connect(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-/// On closed channel, simply delete the account from our local store
+/// On closed channel, keep the account as a tombstone.
+/// The remote account still exists on the host and its funds can be recovered from there.
 pub fn ibc_channel_close(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcChannelCloseMsg,
 ) -> StdResult<IbcBasicResponse> {
     let channel = msg.channel();
 
-    // remove the channel
+    // mark the channel as closed
     let channel_id = &channel.endpoint.channel_id;
-    ACCOUNTS.remove(deps.storage, channel_id);
+    if let Some(mut account) = ACCOUNTS.may_load(deps.storage, channel_id)? {
+        account.closed_at = Some(env.block.time);
+        ACCOUNTS.save(deps.storage, channel_id, &account)?;
+    }
 
     Ok(IbcBasicResponse::new()
         .add_attribute("action", "ibc_close")
@@ -117,6 +121,11 @@ pub fn ibc_packet_ack(
             callback_id,
             ..
         } => acknowledge_query(deps, env, caller, sender, callback_id, msg),
+        PacketMsg::TwapQuery {
+            sender,
+            callback_id,
+            ..
+        } => acknowledge_query(deps, env, caller, sender, callback_id, msg),
         PacketMsg::WhoAmI {} => acknowledge_who_am_i(deps, caller, res),
         PacketMsg::Balances {} => acknowledge_balances(deps, env, caller, res),
         PacketMsg::SendAllBack {
@@ -271,6 +280,7 @@ fn acknowledge_balances(
                 last_update_time: env.block.time,
                 remote_addr: Some(account),
                 remote_balance: balances,
+                closed_at: acct.closed_at,
             })
         }
         None => Err(ContractError::UnregisteredChannel(caller.clone())),
@@ -4,6 +4,6 @@ pub mod contract_error;
 pub mod error;
 pub mod ibc;
 pub mod msg;
-pub mod proxy_asset;
-pub mod queries;
 pub mod state;
+#[cfg(test)]
+mod tests;
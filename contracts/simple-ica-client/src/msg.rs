@@ -3,10 +3,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use simple_ica::{ReceiveIcaResponseMsg, StdAck};
 
-use crate::{
-    proxy_asset::{ProxyAsset, UncheckedProxyAsset},
-    state::AccountData,
-};
+use crate::state::{AccountData, OraclePrice, TwapWindow};
 use client_osmo_bindings::{OsmosisMsg, OsmosisQuery};
 /// This needs no info. Owner of the contract is whoever signed the InstantiateMsg.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,14 +13,24 @@ pub struct InstantiateMsg {
     pub memory_address: String,
 }
 
-use cosmwasm_std::{Empty, Uint128};
+use cosmwasm_std::Empty;
 
-use abstract_os::objects::AssetEntry;
+use abstract_os::objects::{proxy_asset::UncheckedProxyAsset, ContractEntry};
+pub use abstract_os::proxy::{
+    AssetConfigResponse, AssetsResponse, BaseAssetResponse, ConfigResponse, ExternalValueResponse,
+    HoldingAmountResponse, HoldingValueResponse, LatestNavResponse, LimitsResponse,
+    TotalValueResponse, ValidityResponse, ValueQueryMsg,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     SetTWAPChannel(String),
+    /// Sets the TWAP window used to price a pool, resets it to the default if not set.
+    SetTwapWindow {
+        pool: ContractEntry,
+        window: Option<TwapWindow>,
+    },
     UpdatePrices {},
     /// Sets the admin
     SetAdmin {
@@ -46,6 +53,17 @@ pub enum ExecuteMsg {
         to_add: Vec<UncheckedProxyAsset>,
         to_remove: Vec<String>,
     },
+    /// Updates the asset and module limits, can't be set below the current amount.
+    UpdateLimits {
+        max_assets: Option<u32>,
+        max_modules: Option<u32>,
+    },
+    /// Values the next `limit` proxy assets. A NAV snapshot is stored when the last asset is processed.
+    ComputeNav {
+        limit: Option<u32>,
+    },
+    /// Cancels the NAV computation that is in progress, unlocking the assets.
+    CancelNav {},
     SendMsgs {
         /// Note: we don't handle custom messages on remote chains
         msgs: Vec<CosmosMsg<OsmosisMsg>>,
@@ -107,9 +125,13 @@ pub enum QueryMsg {
     CheckValidity {},
     /// Returns [`BaseAssetResponse`]
     BaseAsset {},
+    /// Returns [`LimitsResponse`]
+    Limits {},
+    /// Returns [`LatestNavResponse`]
+    LatestNav {},
     // Returns current admin
     Admin {},
-    // Shows all open accounts (incl. remote info)
+    // Shows all accounts (incl. remote info), closed accounts have `closed_at` set
     ListAccounts {},
     // Get account for one channel
     Account {
@@ -119,6 +141,10 @@ pub enum QueryMsg {
     LatestQueryResult {
         channel_id: String,
     },
+    /// Returns [`PoolPriceResponse`]
+    PoolPrice {
+        pool: ContractEntry,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -147,6 +173,8 @@ pub struct AccountInfo {
     /// the channel and making a query and in that time it is empty
     pub remote_addr: Option<String>,
     pub remote_balance: Vec<Coin>,
+    /// Set if the channel is closed
+    pub closed_at: Option<Timestamp>,
 }
 
 impl AccountInfo {
@@ -156,6 +184,7 @@ impl AccountInfo {
             last_update_time: input.last_update_time,
             remote_addr: input.remote_addr,
             remote_balance: input.remote_balance,
+            closed_at: input.closed_at,
         }
     }
 }
@@ -168,6 +197,8 @@ pub struct AccountResponse {
     /// the channel and making a query and in that time it is empty
     pub remote_addr: Option<String>,
     pub remote_balance: Vec<Coin>,
+    /// Set if the channel is closed
+    pub closed_at: Option<Timestamp>,
 }
 
 impl From<AccountData> for AccountResponse {
@@ -176,62 +207,13 @@ impl From<AccountData> for AccountResponse {
             last_update_time: input.last_update_time,
             remote_addr: input.remote_addr,
             remote_balance: input.remote_balance,
+            closed_at: input.closed_at,
         }
     }
 }
 
 #[cosmwasm_schema::cw_serde]
-pub struct ConfigResponse {
-    pub modules: Vec<String>,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct TotalValueResponse {
-    pub value: Uint128,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct HoldingValueResponse {
-    pub value: Uint128,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct ValidityResponse {
-    /// Assets that have unresolvable dependencies in their value calculation
-    pub unresolvable_assets: Option<Vec<AssetEntry>>,
-    /// Assets that are missing in the VAULT_ASSET map which caused some assets to be unresolvable.
-    pub missing_dependencies: Option<Vec<AssetEntry>>,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct BaseAssetResponse {
-    pub base_asset: ProxyAsset,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct HoldingAmountResponse {
-    pub amount: Uint128,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct AssetConfigResponse {
-    pub proxy_asset: ProxyAsset,
-}
-
-#[cosmwasm_schema::cw_serde]
-pub struct AssetsResponse {
-    pub assets: Vec<(AssetEntry, ProxyAsset)>,
-}
-
-/// Query message to external contract to get asset value
-#[cosmwasm_schema::cw_serde]
-
-pub struct ValueQueryMsg {
-    pub asset: AssetEntry,
-    pub amount: Uint128,
-}
-/// External contract value response
-#[cosmwasm_schema::cw_serde]
-pub struct ExternalValueResponse {
-    pub value: Uint128,
+pub struct PoolPriceResponse {
+    pub price: OraclePrice,
+    pub window: TwapWindow,
 }
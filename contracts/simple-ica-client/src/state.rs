@@ -1,8 +1,9 @@
 use abstract_os::objects::ContractEntry;
 use serde::{Deserialize, Serialize};
+use simple_ica::{TwapKind, TwapRequest, TwapResult};
 
 use crate::msg::LatestQueryResponse;
-use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
+use cosmwasm_std::{Coin, Decimal, StdError, StdResult, Storage, Timestamp};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
@@ -16,11 +17,46 @@ pub struct AccountData {
     /// must not be of type `Addr`.
     pub remote_addr: Option<String>,
     pub remote_balance: Vec<Coin>,
+    /// Set when the channel closed. The data is kept as a tombstone
+    /// as the remote account can still hold funds.
+    pub closed_at: Option<Timestamp>,
+}
+
+/// Load the account of a channel, errors if the channel is unknown or closed.
+pub fn load_open_account(store: &dyn Storage, channel_id: &str) -> StdResult<AccountData> {
+    let account = ACCOUNTS.load(store, channel_id)?;
+    if account.closed_at.is_some() {
+        return Err(StdError::generic_err(format!(
+            "channel {} is closed",
+            channel_id
+        )));
+    }
+    Ok(account)
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct OraclePrice {
     pub price: Decimal,
+    /// The TWAP this price was set from, none until the first update.
+    pub twap: Option<TwapResult>,
+}
+
+/// TWAP window that is used to price a pool
+#[cosmwasm_schema::cw_serde]
+pub struct TwapWindow {
+    pub kind: TwapKind,
+    /// Length of the window in seconds, ending at the time of the update
+    pub duration: u64,
+}
+
+impl Default for TwapWindow {
+    /// 30 minute arithmetic TWAP
+    fn default() -> Self {
+        Self {
+            kind: TwapKind::Arithmetic,
+            duration: 60 * 30,
+        }
+    }
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -30,23 +66,17 @@ pub struct TWAPInfo {
 }
 
 pub const POOL_PRICES: Map<ContractEntry, OraclePrice> = Map::new("pools");
+/// TWAP window per pool, pools without a window use [`TwapWindow::default`]
+pub const TWAP_WINDOWS: Map<ContractEntry, TwapWindow> = Map::new("twap_windows");
+/// Pools of the last TWAP query, in the order of the requests
+pub const TWAP_REQUESTS: Item<Vec<(ContractEntry, TwapRequest)>> = Item::new("twap_requests");
 pub const ACCOUNTS: Map<&str, AccountData> = Map::new("accounts");
 pub const LATEST_QUERIES: Map<&str, LatestQueryResponse> = Map::new("querys");
 pub const TWAP_STATE: Item<TWAPInfo> = Item::new("twap_channel");
 pub const RETRIES: Item<u8> = Item::new("test");
 
-use crate::proxy_asset::ProxyAsset;
-
-pub use abstract_os::objects::core::OS_ID;
-use cw_controllers::Admin;
-
-use abstract_os::objects::{memory::Memory, AssetEntry};
-#[cosmwasm_schema::cw_serde]
-pub struct State {
-    pub modules: Vec<Addr>,
-}
-
-pub const MEMORY: Item<Memory> = Item::new("\u{0}{6}memory");
-pub const STATE: Item<State> = Item::new("\u{0}{5}state");
-pub const ADMIN: Admin = Admin::new("admin");
-pub const VAULT_ASSETS: Map<AssetEntry, ProxyAsset> = Map::new("proxy_assets");
+// The proxy state is shared with the local proxy
+pub use abstract_os::proxy::state::{
+    NavAccumulator, NavSnapshot, ProxyLimits, State, ADMIN, LATEST_NAV, LIMITS, MEMORY, OS_ID,
+    STATE, VAULT_ASSETS, VAULT_VALUE,
+};
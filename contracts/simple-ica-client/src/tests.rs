@@ -0,0 +1,351 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+use abstract_os::{
+    memory::state::{ASSET_ADDRESSES, CONTRACT_ADDRESSES, CROSS_CHAIN_ASSETS},
+    objects::{
+        cross_chain::CrossChainAsset, memory::Memory, AssetEntry, ContractEntry,
+        UncheckedContractEntry,
+    },
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR},
+    to_binary, Addr, Binary, ContractResult, CosmosMsg, Decimal, DepsMut, IbcMsg, Order, OwnedDeps,
+    Response, Storage, SystemResult, WasmQuery,
+};
+use cw_asset::AssetInfo;
+use simple_ica::{
+    client_ibc_msg::PacketMsg, ReceiveIcaResponseMsg, StdAck, TwapKind, TwapQueryResponse,
+    TwapRequest, TwapResult,
+};
+
+use crate::{
+    commands::{host_denoms, TWAP_QUERY},
+    contract::{execute, instantiate, query},
+    error::ProxyError,
+    msg::{ExecuteMsg, InstantiateMsg, PoolPriceResponse, QueryMsg},
+    state::{AccountData, OraclePrice, TwapWindow, ACCOUNTS, POOL_PRICES},
+};
+
+const ADMIN: &str = "admin";
+const MEMORY_ADDR: &str = "memory";
+const CHANNEL: &str = "channel-1";
+
+/// Both pools are osmosis pool 1, which trades atom for osmo
+fn pools() -> [ContractEntry; 2] {
+    [
+        UncheckedContractEntry::new("osmosis", "atom_osmo").check(),
+        UncheckedContractEntry::new("osmosis_stable", "atom_osmo").check(),
+    ]
+}
+
+/// Deps of an IBC proxy with an open TWAP channel that prices [`pools`].
+/// Juno is registered as a cross-chain asset that isn't available on osmosis.
+fn client_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut memory_storage = MockStorage::new();
+    for (name, denom) in [
+        ("osmo>atom", "ibc/atom"),
+        ("osmo>osmo", "uosmo"),
+        ("osmo>juno", "ibc/juno"),
+    ] {
+        ASSET_ADDRESSES
+            .save(&mut memory_storage, name.into(), &AssetInfo::native(denom))
+            .unwrap();
+    }
+    CROSS_CHAIN_ASSETS
+        .save(
+            &mut memory_storage,
+            AssetEntry::new("juno"),
+            &CrossChainAsset {
+                home_chain: "juno".into(),
+                denom: "ujuno".into(),
+                ibc_paths: vec![],
+            },
+        )
+        .unwrap();
+    for pool in pools() {
+        CONTRACT_ADDRESSES
+            .save(&mut memory_storage, pool, &Addr::unchecked("1"))
+            .unwrap();
+    }
+    let state: BTreeMap<Vec<u8>, Vec<u8>> =
+        memory_storage.range(None, None, Order::Ascending).collect();
+
+    let mut querier = MockQuerier::new(&[]);
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Raw { contract_addr, key } if contract_addr == MEMORY_ADDR => {
+            let value = state.get(key.as_slice()).cloned().unwrap_or_default();
+            SystemResult::Ok(ContractResult::Ok(Binary(value)))
+        }
+        _ => SystemResult::Ok(ContractResult::Ok(Binary::default())),
+    });
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+        custom_query_type: PhantomData,
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        InstantiateMsg {
+            os_id: 0,
+            memory_address: MEMORY_ADDR.into(),
+        },
+    )
+    .unwrap();
+    ACCOUNTS
+        .save(deps.as_mut().storage, CHANNEL, &AccountData::default())
+        .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetTWAPChannel(CHANNEL.into()),
+    )
+    .unwrap();
+    // as if the pools value proxy assets
+    for pool in pools() {
+        POOL_PRICES
+            .save(
+                deps.as_mut().storage,
+                pool,
+                &OraclePrice {
+                    price: Decimal::zero(),
+                    twap: None,
+                },
+            )
+            .unwrap();
+    }
+    deps
+}
+
+fn pool_price(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    pool: ContractEntry,
+) -> PoolPriceResponse {
+    from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PoolPrice { pool }).unwrap()).unwrap()
+}
+
+fn set_window(deps: DepsMut, pool: ContractEntry, window: Option<TwapWindow>) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetTwapWindow { pool, window },
+    )
+    .unwrap();
+}
+
+/// The requests of the TWAP query packet
+fn sent_requests(res: Response) -> Vec<TwapRequest> {
+    match &res.messages[0].msg {
+        CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id, data, ..
+        }) => {
+            assert_eq!(channel_id, CHANNEL);
+            match from_binary(data).unwrap() {
+                PacketMsg::TwapQuery { requests, .. } => requests,
+                packet => panic!("unexpected packet {:?}", packet),
+            }
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+/// The host's answer to `requests`, priced at `twap(request index)`
+fn answer(requests: &[TwapRequest], twap: impl Fn(u64) -> Decimal) -> ExecuteMsg {
+    let results = requests
+        .iter()
+        .zip(0..)
+        .map(|(request, i)| TwapResult {
+            pool_id: request.pool_id,
+            base_asset: request.base_asset.clone(),
+            quote_asset: request.quote_asset.clone(),
+            kind: request.kind,
+            start_time: request.start_time,
+            end_time: mock_env().block.time,
+            twap: twap(i),
+        })
+        .collect();
+    ExecuteMsg::ReceiveIcaResponse(ReceiveIcaResponseMsg {
+        id: TWAP_QUERY.into(),
+        msg: StdAck::Result(to_binary(&TwapQueryResponse { results }).unwrap()),
+    })
+}
+
+#[test]
+fn sets_twap_windows() {
+    let mut deps = client_deps();
+    let [pool, _] = pools();
+    assert_eq!(
+        pool_price(&deps, pool.clone()).window,
+        TwapWindow::default()
+    );
+
+    let window = TwapWindow {
+        kind: TwapKind::Geometric,
+        duration: 3600,
+    };
+    let set_window_msg = |window| ExecuteMsg::SetTwapWindow {
+        pool: pool.clone(),
+        window,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        set_window_msg(Some(window.clone())),
+    )
+    .unwrap_err();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        set_window_msg(Some(TwapWindow {
+            kind: TwapKind::Geometric,
+            duration: 0,
+        })),
+    )
+    .unwrap_err();
+    // only pools that value an asset
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN, &[]),
+        ExecuteMsg::SetTwapWindow {
+            pool: UncheckedContractEntry::new("osmosis", "juno_osmo").check(),
+            window: Some(window.clone()),
+        },
+    )
+    .unwrap_err();
+
+    set_window(deps.as_mut(), pool.clone(), Some(window.clone()));
+    assert_eq!(pool_price(&deps, pool.clone()).window, window);
+    set_window(deps.as_mut(), pool.clone(), None);
+    assert_eq!(pool_price(&deps, pool).window, TwapWindow::default());
+}
+
+#[test]
+fn prices_pools_by_request() {
+    let mut deps = client_deps();
+    let [arithmetic_pool, geometric_pool] = pools();
+    set_window(
+        deps.as_mut(),
+        geometric_pool.clone(),
+        Some(TwapWindow {
+            kind: TwapKind::Geometric,
+            duration: 3600,
+        }),
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::UpdatePrices {},
+    )
+    .unwrap();
+    let requests = sent_requests(res);
+    // only the acknowledgement of the proxy's own packet sets prices
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        answer(&requests, |_| Decimal::percent(1)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ProxyError::UnauthorizedIcaResponse {}.to_string()
+    );
+    assert!(pools()
+        .into_iter()
+        .all(|pool| pool_price(&deps, pool).price.twap.is_none()));
+    // one request per pool, for the same osmosis pool
+    assert_eq!(requests.len(), 2);
+    for request in requests.iter() {
+        assert_eq!(request.pool_id, 1);
+        assert_eq!(request.base_asset, "uosmo");
+        assert_eq!(request.quote_asset, "ibc/atom");
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        answer(&requests, |i| Decimal::percent(100 + i)),
+    )
+    .unwrap();
+    for pool in [arithmetic_pool, geometric_pool] {
+        let PoolPriceResponse { price, window } = pool_price(&deps, pool);
+        let twap = price.twap.unwrap();
+        assert_eq!(twap.kind, window.kind);
+        assert_eq!(
+            twap.start_time,
+            mock_env().block.time.minus_seconds(window.duration)
+        );
+        assert_eq!(price.price, twap.twap);
+    }
+    // the pools got distinct prices
+    let [first, second] = pools().map(|pool| pool_price(&deps, pool).price.price);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn skips_results_of_older_queries() {
+    let mut deps = client_deps();
+    let update = |deps: DepsMut| {
+        let res = execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::UpdatePrices {},
+        )
+        .unwrap();
+        sent_requests(res)
+    };
+    let old_requests = update(deps.as_mut());
+
+    // the window changes before the answer comes in
+    let [pool, _] = pools();
+    set_window(
+        deps.as_mut(),
+        pool,
+        Some(TwapWindow {
+            kind: TwapKind::Geometric,
+            duration: 3600,
+        }),
+    );
+    update(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        answer(&old_requests, |_| Decimal::one()),
+    )
+    .unwrap();
+    let prices = pools().map(|pool| pool_price(&deps, pool).price);
+    // only the result that answers the latest request of its pool is used
+    assert_eq!(
+        prices.iter().filter(|price| price.twap.is_some()).count(),
+        1
+    );
+}
+
+#[test]
+fn host_entries_only_stand_in_for_unregistered_assets() {
+    let deps = client_deps();
+    let memory = Memory {
+        address: Addr::unchecked(MEMORY_ADDR),
+    };
+    let denoms = host_denoms(deps.as_ref(), &memory, BTreeSet::from(["atom".into()])).unwrap();
+    assert_eq!(denoms["atom"], "ibc/atom");
+    // juno has an osmo>juno entry, but its registration says it's not on osmosis
+    host_denoms(deps.as_ref(), &memory, BTreeSet::from(["juno".into()])).unwrap_err();
+}
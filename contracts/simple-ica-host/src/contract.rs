@@ -1,19 +1,25 @@
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    entry_point, from_slice, to_binary, to_vec, wasm_execute, BankMsg, Binary, ContractResult,
-    CosmosMsg, Deps, DepsMut, Empty, Env, Event, Ibc3ChannelOpenResponse, IbcBasicResponse,
-    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg,
-    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo,
-    Order, QuerierWrapper, QueryRequest, QueryResponse, Reply, Response, StdError, StdResult,
-    SubMsg, SystemResult, WasmMsg,
+    entry_point, from_slice, to_binary, to_vec, wasm_execute, Addr, Binary, ChannelResponse,
+    ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Event, Ibc3ChannelOpenResponse,
+    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcMsg, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcQuery, IbcReceiveResponse, MessageInfo, Order, QuerierWrapper, QueryRequest, QueryResponse,
+    Reply, Response, StdError, StdResult, SubMsg, SystemResult, WasmMsg,
 };
+use cw_storage_plus::Bound;
 use cw_utils::parse_reply_instantiate_data;
-use osmo_bindings::{OsmosisMsg, OsmosisQuery};
+use osmo_bindings::{OsmosisMsg, OsmosisQuery, Step, SwapAmountWithLimit};
 use osmosis_std::shim::Timestamp;
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
-use osmosis_std::types::osmosis::gamm::v1beta1::{MsgSwapExactAmountIn, SwapAmountInRoute};
+use osmosis_std::types::osmosis::gamm::v1beta1::{
+    MsgSwapExactAmountIn, MsgSwapExactAmountOut, SwapAmountInRoute, SwapAmountOutRoute,
+};
 use simple_ica::{
     check_order, check_version, BalancesResponse, DispatchResponse, IbcQueryResponse,
-    SendAllBackResponse, StdAck, WhoAmIResponse, IBC_APP_VERSION,
+    SendAllBackResponse, StdAck, TwapKind, TwapQueryResponse, TwapRequest, TwapResult,
+    WhoAmIResponse, IBC_APP_VERSION,
 };
 
 use crate::contract::OsmosisQuery::ArithmeticTwapToNow;
@@ -25,41 +31,244 @@ use simple_ica::host_ibc_msg::PacketMsg;
 
 use crate::error::ContractError;
 use crate::msg::{
-    AccountInfo, AccountResponse, InstantiateMsg, ListAccountsResponse, QueryMsg, ReflectExecuteMsg, MigrateMsg,
+    AccountInfo, AccountResponse, ChannelPolicyResponse, ConfigResponse, ExecuteMsg,
+    InstantiateMsg, ListAccountsResponse, MigrateMsg, OrphanedAccountsResponse, QueryMsg,
+    RecoveryAddressResponse,
+};
+use crate::policy::{msg_kind, osmosis_msg_kind};
+use crate::state::{
+    ChannelInfo, Config, OrphanedAccount, PacketInfo, ACCOUNTS, CHANNEL_INFO, CHANNEL_POLICIES,
+    CONFIG, NEXT_INIT_ID, ORPHANED_ACCOUNTS, PENDING, RECOVERY_ADDRESSES, RESULTS,
 };
-use crate::state::{Config, ACCOUNTS, CONFIG, PENDING, RESULTS};
 
 pub const RECEIVE_DISPATCH_ID: u64 = 1234;
+/// Reflect instantiations get a reply id of at least this value, one per pending channel.
 pub const INIT_CALLBACK_ID: u64 = 7890;
 pub const PACKET_LIFETIME: u64 = 60 * 60;
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
     // we store the reflect_id for creating accounts later
     let cfg = Config {
         cw1_code_id: msg.cw1_code_id,
+        admin,
+        allowed_ports: msg.allowed_ports,
+        allowed_connections: msg.allowed_connections,
     };
     CONFIG.save(deps.storage, &cfg)?;
 
     Ok(Response::new())
 }
 
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        ExecuteMsg::UpdateAdmin { admin } => {
+            cfg.admin = deps.api.addr_validate(&admin)?;
+            CONFIG.save(deps.storage, &cfg)?;
+            Ok(Response::new()
+                .add_attribute("action", "update_admin")
+                .add_attribute("admin", admin))
+        }
+        ExecuteMsg::UpdateAllowlist {
+            allowed_ports,
+            allowed_connections,
+        } => {
+            cfg.allowed_ports = allowed_ports;
+            cfg.allowed_connections = allowed_connections;
+            CONFIG.save(deps.storage, &cfg)?;
+            Ok(Response::new().add_attribute("action", "update_allowlist"))
+        }
+        ExecuteMsg::SetChannelPolicy { channel_id, policy } => {
+            let policy = policy.validate(deps.api)?;
+            CHANNEL_POLICIES.save(deps.storage, &channel_id, &policy)?;
+            Ok(Response::new()
+                .add_attribute("action", "set_channel_policy")
+                .add_attribute("channel_id", channel_id))
+        }
+        ExecuteMsg::RemoveChannelPolicy { channel_id } => {
+            CHANNEL_POLICIES.remove(deps.storage, &channel_id);
+            Ok(Response::new()
+                .add_attribute("action", "remove_channel_policy")
+                .add_attribute("channel_id", channel_id))
+        }
+        ExecuteMsg::SetRecoveryAddress {
+            connection_id,
+            counterparty_port,
+            address,
+        } => {
+            // the address lives on the controller chain, so we can't validate it here
+            RECOVERY_ADDRESSES.save(
+                deps.storage,
+                (&connection_id, &counterparty_port),
+                &address,
+            )?;
+            Ok(Response::new()
+                .add_attribute("action", "set_recovery_address")
+                .add_attribute("address", address))
+        }
+        ExecuteMsg::RecoverFunds {
+            channel_id,
+            transfer_channel,
+        } => execute_recover_funds(deps, env, channel_id, transfer_channel),
+    }
+}
+
+/// Transfers the current balance of an orphaned account back to the controller chain.
+/// The orphan record is kept so funds that are refunded on a timeout can be recovered again.
+pub fn execute_recover_funds(
+    deps: DepsMut,
+    env: Env,
+    channel_id: String,
+    transfer_channel: String,
+) -> Result<Response, ContractError> {
+    let orphan = ORPHANED_ACCOUNTS
+        .may_load(deps.storage, &channel_id)?
+        .ok_or_else(|| ContractError::NoOrphanedAccount(channel_id.clone()))?;
+    let recovery_address = RECOVERY_ADDRESSES
+        .may_load(
+            deps.storage,
+            (&orphan.connection_id, &orphan.counterparty_port),
+        )?
+        .ok_or_else(|| ContractError::NoRecoveryAddress {
+            connection_id: orphan.connection_id.clone(),
+            port: orphan.counterparty_port.clone(),
+        })?;
+
+    let coins = deps.querier.query_all_balances(&orphan.account)?;
+    let msgs: Vec<CosmosMsg> = coins
+        .into_iter()
+        .map(|coin| {
+            IbcMsg::Transfer {
+                channel_id: transfer_channel.clone(),
+                to_address: recovery_address.clone(),
+                amount: coin,
+                timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+            }
+            .into()
+        })
+        .collect();
+    let transfers = msgs.len();
+    let mut response = Response::new()
+        .add_attribute("action", "recover_funds")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("recovery_address", recovery_address)
+        .add_attribute("transfers", transfers.to_string());
+    if transfers > 0 {
+        let reflect_msg = cw1_whitelist::msg::ExecuteMsg::Execute { msgs };
+        response = response.add_message(wasm_execute(orphan.account, &reflect_msg, vec![])?);
+    }
+    Ok(response)
+}
+
 #[entry_point]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
     match msg {
         QueryMsg::Account { channel_id } => to_binary(&query_account(deps, channel_id)?),
-        QueryMsg::ListAccounts {} => to_binary(&query_list_accounts(deps)?),
+        QueryMsg::ListAccounts { start_after, limit } => {
+            to_binary(&query_list_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::Balances { channel_id } => to_binary(&query_balances(deps, channel_id)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::ChannelPolicy { channel_id } => {
+            to_binary(&query_channel_policy(deps, channel_id)?)
+        }
+        QueryMsg::OrphanedAccounts { start_after, limit } => {
+            to_binary(&query_orphaned_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::RecoveryAddress {
+            connection_id,
+            counterparty_port,
+        } => to_binary(&RecoveryAddressResponse {
+            address: RECOVERY_ADDRESSES
+                .may_load(deps.storage, (&connection_id, &counterparty_port))?,
+        }),
     }
 }
 
+pub fn query_orphaned_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OrphanedAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let accounts = ORPHANED_ACCOUNTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(OrphanedAccountsResponse { accounts })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        cw1_code_id: cfg.cw1_code_id,
+        admin: cfg.admin.into(),
+        allowed_ports: cfg.allowed_ports,
+        allowed_connections: cfg.allowed_connections,
+    })
+}
+
+pub fn query_channel_policy(deps: Deps, channel_id: String) -> StdResult<ChannelPolicyResponse> {
+    let policy = CHANNEL_POLICIES.may_load(deps.storage, &channel_id)?;
+    Ok(ChannelPolicyResponse { policy })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // Channels that were connected before their info was tracked get it from the IBC module.
+    let channels = ACCOUNTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    let mut backfilled = 0u32;
+    for channel_id in channels {
+        if CHANNEL_INFO.has(deps.storage, &channel_id) {
+            continue;
+        }
+        let res: ChannelResponse = deps.querier.query(&QueryRequest::Ibc(IbcQuery::Channel {
+            channel_id: channel_id.clone(),
+            port_id: None,
+        }))?;
+        let channel = res
+            .channel
+            .ok_or_else(|| StdError::not_found(format!("channel {}", channel_id)))?;
+        CHANNEL_INFO.save(
+            deps.storage,
+            &channel_id,
+            &ChannelInfo {
+                connection_id: channel.connection_id,
+                counterparty_port: channel.counterparty_endpoint.port_id,
+                created_at: env.block.time,
+                last_packet: None,
+            },
+        )?;
+        backfilled += 1;
+    }
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("backfilled_channels", backfilled.to_string()))
 }
 
 pub fn query_account(deps: Deps, channel_id: String) -> StdResult<AccountResponse> {
@@ -69,30 +278,66 @@ pub fn query_account(deps: Deps, channel_id: String) -> StdResult<AccountRespons
     })
 }
 
-pub fn query_list_accounts(deps: Deps) -> StdResult<ListAccountsResponse> {
+pub fn query_list_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
     let accounts = ACCOUNTS
-        .range(deps.storage, None, None, Order::Ascending)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
         .map(|item| {
             let (channel_id, account) = item?;
+            let info = CHANNEL_INFO.load(deps.storage, &channel_id)?;
             Ok(AccountInfo {
                 account: account.into(),
                 channel_id,
+                connection_id: info.connection_id,
+                counterparty_port: info.counterparty_port,
+                created_at: info.created_at,
+                last_packet: info.last_packet,
             })
         })
         .collect::<StdResult<_>>()?;
     Ok(ListAccountsResponse { accounts })
 }
 
+pub fn query_balances(deps: Deps, channel_id: String) -> StdResult<BalancesResponse> {
+    let account = ACCOUNTS.load(deps.storage, &channel_id)?;
+    let balances = deps.querier.query_all_balances(&account)?;
+    Ok(BalancesResponse {
+        account: account.into(),
+        balances,
+    })
+}
+
 #[entry_point]
 /// enforces ordering and versioing constraints
 pub fn ibc_channel_open(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     msg: IbcChannelOpenMsg,
 ) -> Result<IbcChannelOpenResponse, ContractError> {
     let channel = msg.channel();
 
     check_order(&channel.order)?;
+    // only accept channels from allowed counterparties
+    let cfg = CONFIG.load(deps.storage)?;
+    let port = &channel.counterparty_endpoint.port_id;
+    if let Some(allowed_ports) = &cfg.allowed_ports {
+        if !allowed_ports.contains(port) {
+            return Err(ContractError::PortNotAllowed(port.clone()));
+        }
+    }
+    if let Some(allowed_connections) = &cfg.allowed_connections {
+        if !allowed_connections.contains(&channel.connection_id) {
+            return Err(ContractError::ConnectionNotAllowed(
+                channel.connection_id.clone(),
+            ));
+        }
+    }
     // In ibcv3 we don't check the version string passed in the message
     // and only check the counterparty version.
     if let Some(counter_version) = msg.counterparty_version() {
@@ -107,6 +352,7 @@ pub fn ibc_channel_open(
 
 #[entry_point]
 /// once it's established, we create the reflect contract
+/// or take over the orphaned account of a previous channel with the same counterparty.
 pub fn ibc_channel_connect(
     deps: DepsMut,
     env: Env,
@@ -115,6 +361,33 @@ pub fn ibc_channel_connect(
     let channel = msg.channel();
     let cfg = CONFIG.load(deps.storage)?;
     let chan_id = &channel.endpoint.channel_id;
+    let connection_id = &channel.connection_id;
+    let counterparty_port = &channel.counterparty_endpoint.port_id;
+
+    CHANNEL_INFO.save(
+        deps.storage,
+        chan_id,
+        &ChannelInfo {
+            connection_id: connection_id.clone(),
+            counterparty_port: counterparty_port.clone(),
+            created_at: env.block.time,
+            last_packet: None,
+        },
+    )?;
+    let response = IbcBasicResponse::new()
+        .add_attribute("action", "ibc_connect")
+        .add_attribute("channel_id", chan_id)
+        .add_event(Event::new("ibc").add_attribute("channel", "connect"));
+
+    if let Some((closed_channel, orphan)) =
+        find_orphaned_account(deps.as_ref(), connection_id, counterparty_port)?
+    {
+        ORPHANED_ACCOUNTS.remove(deps.storage, &closed_channel);
+        ACCOUNTS.save(deps.storage, chan_id, &orphan.account)?;
+        return Ok(response
+            .add_attribute("reclaimed_from", closed_channel)
+            .add_attribute("account", orphan.account));
+    }
 
     let init_msg = cw1_whitelist::msg::InstantiateMsg {
         admins: vec![env.contract.address.into_string()],
@@ -127,21 +400,42 @@ pub fn ibc_channel_connect(
         funds: vec![],
         label: format!("ibc-reflect-{}", chan_id),
     };
-    let msg = SubMsg::reply_on_success(msg, INIT_CALLBACK_ID);
+    // every handshake gets its own reply id so they can be in flight concurrently
+    let reply_id = NEXT_INIT_ID
+        .may_load(deps.storage)?
+        .unwrap_or(INIT_CALLBACK_ID);
+    NEXT_INIT_ID.save(deps.storage, &(reply_id + 1))?;
+    let msg = SubMsg::reply_on_success(msg, reply_id);
 
     // store the channel id for the reply handler
-    PENDING.save(deps.storage, chan_id)?;
+    PENDING.save(deps.storage, reply_id, chan_id)?;
 
-    Ok(IbcBasicResponse::new()
-        .add_submessage(msg)
-        .add_attribute("action", "ibc_connect")
-        .add_attribute("channel_id", chan_id)
-        .add_event(Event::new("ibc").add_attribute("channel", "connect")))
+    Ok(response.add_submessage(msg))
+}
+
+/// Returns the orphaned account (and its closed channel) of a counterparty, if any.
+fn find_orphaned_account(
+    deps: Deps,
+    connection_id: &str,
+    counterparty_port: &str,
+) -> StdResult<Option<(String, OrphanedAccount)>> {
+    ORPHANED_ACCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .find(|item| {
+            item.as_ref()
+                .map(|(_, orphan)| {
+                    orphan.connection_id == connection_id
+                        && orphan.counterparty_port == counterparty_port
+                })
+                .unwrap_or(true)
+        })
+        .transpose()
 }
 
 #[entry_point]
-/// On closed channel, we take all tokens from reflect contract to this contract.
-/// We also delete the channel entry from accounts.
+/// On closed channel, we keep the reflect contract and its funds as an orphaned account.
+/// It can be reclaimed by a new channel from the same counterparty
+/// or its funds can be returned to the counterparty's recovery address by the admin.
 pub fn ibc_channel_close(
     deps: DepsMut,
     env: Env,
@@ -152,36 +446,29 @@ pub fn ibc_channel_close(
     let channel_id = channel.endpoint.channel_id.as_str();
     let reflect_addr = ACCOUNTS.load(deps.storage, channel_id)?;
     ACCOUNTS.remove(deps.storage, channel_id);
+    CHANNEL_INFO.remove(deps.storage, channel_id);
 
-    // transfer current balance if any to this host contract
-    let amount = deps.querier.query_all_balances(&reflect_addr)?;
-    let messages: Vec<SubMsg<Empty>> = if !amount.is_empty() {
-        let bank_msg = BankMsg::Send {
-            to_address: env.contract.address.into(),
-            amount,
-        };
-        let reflect_msg = ReflectExecuteMsg::ReflectMsg {
-            msgs: vec![bank_msg.into()],
-        };
-        let wasm_msg = wasm_execute(reflect_addr, &reflect_msg, vec![])?;
-        vec![SubMsg::new(wasm_msg)]
-    } else {
-        vec![]
+    let balances = deps.querier.query_all_balances(&reflect_addr)?;
+    let orphan = OrphanedAccount {
+        account: reflect_addr,
+        connection_id: channel.connection_id.clone(),
+        counterparty_port: channel.counterparty_endpoint.port_id.clone(),
+        balances,
+        closed_at: env.block.time,
     };
-    let rescue_funds = !messages.is_empty();
+    ORPHANED_ACCOUNTS.save(deps.storage, channel_id, &orphan)?;
 
     Ok(IbcBasicResponse::new()
-        .add_submessages(messages)
         .add_attribute("action", "ibc_close")
         .add_attribute("channel_id", channel_id)
-        .add_attribute("rescue_funds", rescue_funds.to_string()))
+        .add_attribute("orphaned_account", orphan.account))
 }
 
 #[entry_point]
 pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
         RECEIVE_DISPATCH_ID => reply_dispatch_callback(deps, reply),
-        INIT_CALLBACK_ID => reply_init_callback(deps, reply),
+        id if id >= INIT_CALLBACK_ID => reply_init_callback(deps, reply),
         _ => Err(ContractError::InvalidReplyId),
     }
 }
@@ -199,8 +486,10 @@ pub fn reply_dispatch_callback(deps: DepsMut, reply: Reply) -> Result<Response,
 
 pub fn reply_init_callback(deps: DepsMut, reply: Reply) -> Result<Response, ContractError> {
     // we use storage to pass info from the caller to the reply
-    let id = PENDING.load(deps.storage)?;
-    PENDING.remove(deps.storage);
+    let id = PENDING
+        .may_load(deps.storage, reply.id)?
+        .ok_or(ContractError::InvalidReplyId)?;
+    PENDING.remove(deps.storage, reply.id);
 
     // parse contract info from data
     let raw_addr = parse_reply_instantiate_data(reply)?.contract_address;
@@ -228,10 +517,19 @@ pub fn ibc_packet_receive(
     let packet = msg.packet;
     // which local channel did this packet come on
     let caller = packet.dest.channel_id;
+    // channels connected before the info was tracked have none until the contract is migrated
+    if let Some(mut info) = CHANNEL_INFO.may_load(deps.storage, &caller)? {
+        info.last_packet = Some(PacketInfo {
+            sequence: packet.sequence,
+            received_at: env.block.time,
+        });
+        CHANNEL_INFO.save(deps.storage, &caller, &info)?;
+    }
     let msg: PacketMsg = from_slice(&packet.data)?;
     match msg {
         PacketMsg::Dispatch { msgs, .. } => receive_dispatch(deps, caller, msgs),
         PacketMsg::IbcQuery { msgs, .. } => receive_query(deps.as_ref(), msgs),
+        PacketMsg::TwapQuery { requests, .. } => receive_twap_query(deps.as_ref(), env, requests),
         PacketMsg::WhoAmI {} => receive_who_am_i(deps, caller),
         PacketMsg::Balances {} => receive_balances(deps, caller),
         PacketMsg::SendAllBack {
@@ -299,6 +597,67 @@ fn receive_query(
         .add_attribute("action", "receive_ibc_query"))
 }
 
+// processes PacketMsg::TwapQuery variant
+fn receive_twap_query(
+    deps: Deps,
+    env: Env,
+    requests: Vec<TwapRequest>,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let twap_querier = TwapQuerier::new(&deps.querier);
+    let mut results = Vec::with_capacity(requests.len());
+
+    for request in requests {
+        let end_time = request.end_time.unwrap_or(env.block.time);
+        let start = Some(proto_timestamp(request.start_time));
+        let end = Some(proto_timestamp(end_time));
+        let twap = match request.kind {
+            TwapKind::Arithmetic => {
+                twap_querier
+                    .arithmetic_twap(
+                        request.pool_id,
+                        request.base_asset.clone(),
+                        request.quote_asset.clone(),
+                        start,
+                        end,
+                    )?
+                    .arithmetic_twap
+            }
+            TwapKind::Geometric => {
+                twap_querier
+                    .geometric_twap(
+                        request.pool_id,
+                        request.base_asset.clone(),
+                        request.quote_asset.clone(),
+                        start,
+                        end,
+                    )?
+                    .geometric_twap
+            }
+        };
+        results.push(TwapResult {
+            pool_id: request.pool_id,
+            base_asset: request.base_asset,
+            quote_asset: request.quote_asset,
+            kind: request.kind,
+            start_time: request.start_time,
+            end_time,
+            twap: Decimal::from_str(&twap)?,
+        });
+    }
+
+    let acknowledgement = StdAck::success(&TwapQueryResponse { results });
+    Ok(IbcReceiveResponse::new()
+        .set_ack(acknowledgement)
+        .add_attribute("action", "receive_twap_query"))
+}
+
+fn proto_timestamp(time: cosmwasm_std::Timestamp) -> Timestamp {
+    Timestamp {
+        seconds: time.seconds() as i64,
+        nanos: time.subsec_nanos() as i32,
+    }
+}
+
 // processes PacketMsg::WhoAmI variant
 fn receive_who_am_i(deps: DepsMut, caller: String) -> Result<IbcReceiveResponse, ContractError> {
     let account = ACCOUNTS.load(deps.storage, &caller)?;
@@ -335,51 +694,16 @@ fn receive_dispatch(
 ) -> Result<IbcReceiveResponse, ContractError> {
     // what is the reflect contract here
     let reflect_addr = ACCOUNTS.load(deps.storage, &caller)?;
+    let msgs = match reflect_msgs(deps.as_ref(), &caller, &reflect_addr, msgs) {
+        Ok(msgs) => msgs,
+        Err(err) => return Ok(dispatch_failed(err, "receive_dispatch")),
+    };
 
     // let them know we're fine
     let response = DispatchResponse { results: vec![] };
 
-    let mut swap_msg: Option<CosmosMsg> = None;
-    // let acknowledgement = StdAck::success(&response);
     // create the message to re-dispatch to the reflect contract
-    for msg in msgs {
-        match msg {
-            CosmosMsg::Custom(osmo_msg) => match osmo_msg {
-                OsmosisMsg::Swap {
-                    first,
-                    route,
-                    amount,
-                } => {
-                    let pool_id = first.pool_id;
-                    let token_out_denom = first.denom_out;
-                    let input = match amount {
-                        osmo_bindings::SwapAmountWithLimit::ExactIn { input, min_output } => input,
-                        osmo_bindings::SwapAmountWithLimit::ExactOut { output, max_input } => {
-                            todo!()
-                        }
-                    };
-                    swap_msg = Some(
-                        MsgSwapExactAmountIn {
-                            sender: reflect_addr.to_string(),
-                            routes: vec![SwapAmountInRoute{ pool_id, token_out_denom}],
-                            token_in: Some(Coin {
-                                denom: first.denom_in,
-                                amount: input.to_string(),
-                            }),
-                            token_out_min_amount: "0".to_string(),
-                        }
-                        .into(),
-                    );
-                }
-                _ => panic!("only swaps supported"),
-            },
-            _ => (),
-        }
-    }
-    let report_swap = swap_msg.clone();
-    let reflect_msg = cw1_whitelist::msg::ExecuteMsg::Execute {
-        msgs: vec![swap_msg.unwrap()],
-    };
+    let reflect_msg = cw1_whitelist::msg::ExecuteMsg::Execute { msgs };
     let balances = deps.querier.query_all_balances(&reflect_addr)?;
     let response = if balances.is_empty() {
         let ack = StdAck::fail("funds not yet received".to_string());
@@ -388,6 +712,7 @@ fn receive_dispatch(
             .add_attribute("action", "receive_dispatch")
     } else {
         let ack = StdAck::success(&response);
+        // we wrap it in a submessage to properly report results
         let wasm_msg = wasm_execute(reflect_addr, &reflect_msg, vec![])?;
         let msg = SubMsg::reply_on_success(wasm_msg, RECEIVE_DISPATCH_ID);
         IbcReceiveResponse::new()
@@ -395,12 +720,119 @@ fn receive_dispatch(
             .add_submessage(msg)
             .add_attribute("action", "receive_dispatch")
     };
-    // we wrap it in a submessage to properly report results
-    
+
     // reset the data field
     RESULTS.save(deps.storage, &vec![])?;
 
-    Ok(response.add_attribute("swap_report", format!("{:?}",report_swap)))
+    Ok(response)
+}
+
+/// Checks the messages against the policy of the channel and converts them to messages
+/// the reflect account executes.
+fn reflect_msgs(
+    deps: Deps,
+    channel_id: &str,
+    reflect_addr: &Addr,
+    msgs: Vec<CosmosMsg<OsmosisMsg>>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    // ensure the controller is allowed to dispatch these messages
+    if let Some(policy) = CHANNEL_POLICIES.may_load(deps.storage, channel_id)? {
+        policy.check(&msgs)?;
+    }
+    msgs.into_iter()
+        .map(|msg| reflect_msg(reflect_addr, msg))
+        .collect()
+}
+
+/// Messages other than osmosis swaps are executed as they are.
+fn reflect_msg(
+    reflect_addr: &Addr,
+    msg: CosmosMsg<OsmosisMsg>,
+) -> Result<CosmosMsg, ContractError> {
+    #[allow(unreachable_patterns)]
+    let msg = match msg {
+        CosmosMsg::Custom(OsmosisMsg::Swap {
+            first,
+            route,
+            amount,
+        }) => swap_msg(reflect_addr, first, route, amount),
+        CosmosMsg::Custom(osmo_msg) => {
+            return Err(ContractError::MsgNotSupported(
+                osmosis_msg_kind(&osmo_msg).to_string(),
+            ))
+        }
+        CosmosMsg::Bank(msg) => CosmosMsg::Bank(msg),
+        CosmosMsg::Wasm(msg) => CosmosMsg::Wasm(msg),
+        CosmosMsg::Ibc(msg) => CosmosMsg::Ibc(msg),
+        CosmosMsg::Stargate { type_url, value } => CosmosMsg::Stargate { type_url, value },
+        CosmosMsg::Gov(msg) => CosmosMsg::Gov(msg),
+        other => return Err(ContractError::MsgNotSupported(msg_kind(&other).to_string())),
+    };
+    Ok(msg)
+}
+
+/// The gamm message of an osmosis swap binding, which the reflect account can't execute.
+fn swap_msg(
+    reflect_addr: &Addr,
+    first: osmo_bindings::Swap,
+    route: Vec<Step>,
+    amount: SwapAmountWithLimit,
+) -> CosmosMsg {
+    match amount {
+        SwapAmountWithLimit::ExactIn { input, min_output } => {
+            let routes = std::iter::once(SwapAmountInRoute {
+                pool_id: first.pool_id,
+                token_out_denom: first.denom_out,
+            })
+            .chain(route.into_iter().map(|step| SwapAmountInRoute {
+                pool_id: step.pool_id,
+                token_out_denom: step.denom_out,
+            }))
+            .collect();
+            MsgSwapExactAmountIn {
+                sender: reflect_addr.to_string(),
+                routes,
+                token_in: Some(Coin {
+                    denom: first.denom_in,
+                    amount: input.to_string(),
+                }),
+                token_out_min_amount: min_output.to_string(),
+            }
+            .into()
+        }
+        SwapAmountWithLimit::ExactOut { output, max_input } => {
+            // every pool takes in what the previous one returned
+            let mut routes = vec![SwapAmountOutRoute {
+                pool_id: first.pool_id,
+                token_in_denom: first.denom_in,
+            }];
+            let mut denom_out = first.denom_out;
+            for step in route {
+                routes.push(SwapAmountOutRoute {
+                    pool_id: step.pool_id,
+                    token_in_denom: denom_out,
+                });
+                denom_out = step.denom_out;
+            }
+            MsgSwapExactAmountOut {
+                sender: reflect_addr.to_string(),
+                routes,
+                token_in_max_amount: max_input.to_string(),
+                token_out: Some(Coin {
+                    denom: denom_out,
+                    amount: output.to_string(),
+                }),
+            }
+            .into()
+        }
+    }
+}
+
+/// Acknowledges a dispatch that can't be executed with an error.
+fn dispatch_failed(err: ContractError, action: &str) -> IbcReceiveResponse {
+    IbcReceiveResponse::new()
+        .set_ack(StdAck::fail(err.to_string()))
+        .add_attribute("action", action)
 }
 
 // processes PacketMsg::Dispatch variant
@@ -420,7 +852,7 @@ fn receive_send_all_back(
     let account = ACCOUNTS.load(deps.storage, &caller)?;
     let coins = deps.querier.query_all_balances(&account)?;
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut msgs: Vec<CosmosMsg<OsmosisMsg>> = vec![];
     for coin in coins {
         msgs.push(
             IbcMsg::Transfer {
@@ -432,6 +864,11 @@ fn receive_send_all_back(
             .into(),
         )
     }
+    // the transfers are subject to the channel policy like any dispatch
+    let msgs = match reflect_msgs(deps.as_ref(), &caller, &reflect_addr, msgs) {
+        Ok(msgs) => msgs,
+        Err(err) => return Ok(dispatch_failed(err, "receive_dispatch")),
+    };
     // create the message to re-dispatch to the reflect contract
     let reflect_msg = cw1_whitelist::msg::ExecuteMsg::Execute { msgs };
     let wasm_msg: CosmosMsg<Empty> = wasm_execute(account, &reflect_msg, vec![])?.into();
@@ -472,16 +909,22 @@ pub fn ibc_packet_timeout(
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{
-        mock_dependencies, mock_env, mock_ibc_channel_close_init, mock_ibc_channel_connect_ack,
-        mock_ibc_channel_open_init, mock_ibc_channel_open_try, mock_ibc_packet_recv, mock_info,
-        mock_wasmd_attr, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+        mock_dependencies, mock_env, mock_ibc_channel, mock_ibc_channel_close_init,
+        mock_ibc_channel_connect_ack, mock_ibc_channel_open_init, mock_ibc_channel_open_try,
+        mock_ibc_packet_recv, mock_info, mock_wasmd_attr, MockApi, MockQuerier, MockStorage,
     };
     use cosmwasm_std::{
-        attr, coin, coins, from_slice, BankMsg, Binary, OwnedDeps, SubMsgResponse, SubMsgResult,
-        WasmMsg,
+        attr, coin, coins, from_slice, BankMsg, Binary, IbcChannel, OverflowError,
+        OverflowOperation, OwnedDeps, Querier, QuerierResult, SubMsgResponse, SubMsgResult,
+        Uint128, WasmMsg,
+    };
+    use osmosis_std::types::osmosis::twap::v1beta1::{
+        ArithmeticTwapRequest, ArithmeticTwapResponse, GeometricTwapRequest, GeometricTwapResponse,
     };
     use simple_ica::{APP_ORDER, BAD_APP_ORDER};
 
+    use crate::policy::ChannelPolicy;
+
     const CREATOR: &str = "creator";
     // code id of the reflect contract
     const REFLECT_ID: u64 = 101;
@@ -492,6 +935,9 @@ mod tests {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             cw1_code_id: REFLECT_ID,
+            admin: None,
+            allowed_ports: None,
+            allowed_connections: None,
         };
         let info = mock_info(CREATOR, &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -547,11 +993,41 @@ mod tests {
         reply(deps.branch(), mock_env(), response).unwrap();
     }
 
+    /// The messages that the reflect account is told to execute
+    fn reflected_msgs(msg: &CosmosMsg, account: &str) -> Vec<CosmosMsg> {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                assert_eq!(account, contract_addr.as_str());
+                assert_eq!(0, funds.len());
+                match from_slice(msg).unwrap() {
+                    cw1_whitelist::msg::ExecuteMsg::Execute { msgs } => msgs,
+                    msg => panic!("unexpected reflect message {:?}", msg),
+                }
+            }
+            msg => panic!("invalid return message: {:?}", msg),
+        }
+    }
+
+    /// The error a packet was acknowledged with
+    fn ack_error(res: IbcReceiveResponse) -> String {
+        let ack: StdAck = from_slice(&res.acknowledgement).unwrap();
+        ack.unwrap_err()
+    }
+
     #[test]
     fn instantiate_works() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { cw1_code_id: 17 };
+        let msg = InstantiateMsg {
+            cw1_code_id: 17,
+            admin: None,
+            allowed_ports: None,
+            allowed_connections: None,
+        };
         let info = mock_info("creator", &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(0, res.messages.len())
@@ -604,7 +1080,15 @@ mod tests {
         }
 
         // no accounts set yet
-        let raw = query(deps.as_ref(), mock_env(), QueryMsg::ListAccounts {}).unwrap();
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ListAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
         let res: ListAccountsResponse = from_slice(&raw).unwrap();
         assert_eq!(0, res.accounts.len());
 
@@ -619,16 +1103,22 @@ mod tests {
         reply(deps.as_mut(), mock_env(), response).unwrap();
 
         // ensure this is now registered
-        let raw = query(deps.as_ref(), mock_env(), QueryMsg::ListAccounts {}).unwrap();
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ListAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
         let res: ListAccountsResponse = from_slice(&raw).unwrap();
         assert_eq!(1, res.accounts.len());
-        assert_eq!(
-            &res.accounts[0],
-            &AccountInfo {
-                account: REFLECT_ADDR.into(),
-                channel_id: channel_id.to_string(),
-            }
-        );
+        let info = &res.accounts[0];
+        assert_eq!(info.account, REFLECT_ADDR);
+        assert_eq!(info.channel_id, channel_id);
+        assert_eq!(info.created_at, mock_env().block.time);
+        assert_eq!(info.last_packet, None);
 
         // and the account query also works
         let raw = query(
@@ -668,7 +1158,14 @@ mod tests {
         // register the channel
         connect(deps.as_mut(), channel_id, account);
 
-        // receive a packet for an unregistered channel returns app-level error (not Result::Err)
+        // the account has to be funded first
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: StdAck = from_slice(&res.acknowledgement).unwrap();
+        assert_eq!(ack.unwrap_err(), "funds not yet received");
+        deps.querier
+            .update_balance(account, coins(123456789, "uatom"));
+
         let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
         let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
 
@@ -679,34 +1176,591 @@ mod tests {
         // and we dispatch the BankMsg via submessage
         assert_eq!(1, res.messages.len());
         assert_eq!(RECEIVE_DISPATCH_ID, res.messages[0].id);
-
-        // parse the output, ensuring it matches
-        if let CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr,
-            msg,
-            funds,
-        }) = &res.messages[0].msg
-        {
-            assert_eq!(account, contract_addr.as_str());
-            assert_eq!(0, funds.len());
-            // parse the message - should callback with proper channel_id
-            let _rmsg: cw1_whitelist::msg::ExecuteMsg = from_slice(msg).unwrap();
-            // assert_eq!(
-            //     rmsg,
-            //     cw1_whitelist::msg::ExecuteMsg::Execute {
-            //         msgs: msgs_to_dispatch
-            //     }
-            // );
-        } else {
-            panic!("invalid return message: {:?}", res.messages[0]);
-        }
+        assert_eq!(
+            reflected_msgs(&res.messages[0].msg, account),
+            vec![BankMsg::Send {
+                to_address: "my-friend".into(),
+                amount: coins(123456789, "uatom"),
+            }
+            .into()]
+        );
 
         // invalid packet format on registered channel also returns error
-        let bad_data = InstantiateMsg { cw1_code_id: 12345 };
+        let bad_data = InstantiateMsg {
+            cw1_code_id: 12345,
+            admin: None,
+            allowed_ports: None,
+            allowed_connections: None,
+        };
         let msg = mock_ibc_packet_recv(channel_id, &bad_data).unwrap();
         ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap_err();
     }
 
+    #[test]
+    fn channel_open_checks_allowlist() {
+        let mut deps = setup();
+
+        // only the admin can update the allowlist
+        let msg = ExecuteMsg::UpdateAllowlist {
+            allowed_ports: Some(vec!["wasm.controller".to_string()]),
+            allowed_connections: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+
+        // the mocked counterparty port is not on the list
+        let handshake_open = mock_ibc_channel_open_init("channel-1", APP_ORDER, IBC_APP_VERSION);
+        let port = handshake_open
+            .channel()
+            .counterparty_endpoint
+            .port_id
+            .clone();
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), handshake_open).unwrap_err();
+        assert_eq!(err, ContractError::PortNotAllowed(port.clone()));
+
+        // but can open once it is
+        let msg = ExecuteMsg::UpdateAllowlist {
+            allowed_ports: Some(vec![port]),
+            allowed_connections: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+        let handshake_open = mock_ibc_channel_open_init("channel-1", APP_ORDER, IBC_APP_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), handshake_open).unwrap();
+    }
+
+    #[test]
+    fn dispatch_checks_channel_policy() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        let account = "acct-123";
+        connect(deps.as_mut(), channel_id, account);
+
+        let policy = ChannelPolicy {
+            allowed_osmosis_msgs: Some(vec!["swap".to_string()]),
+            allowed_contracts: Some(vec!["allowed-contract".to_string()]),
+            max_transfer: Some(coins(100, "uosmo")),
+        };
+        let msg = ExecuteMsg::SetChannelPolicy {
+            channel_id: channel_id.to_string(),
+            policy: policy.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+        let res = query_channel_policy(deps.as_ref(), channel_id.to_string()).unwrap();
+        assert_eq!(res.policy, Some(policy));
+
+        let dispatch = |msgs: Vec<CosmosMsg<OsmosisMsg>>| PacketMsg::Dispatch {
+            msgs,
+            sender: account.to_string(),
+            callback_id: None,
+        };
+
+        // transfers above the limit are rejected
+        let ibc_msg = dispatch(vec![
+            BankMsg::Send {
+                to_address: "my-friend".into(),
+                amount: coins(60, "uosmo"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: "my-friend".into(),
+                amount: coins(60, "uosmo"),
+            }
+            .into(),
+        ]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::TransferLimitExceeded {
+                denom: "uosmo".to_string(),
+                max: 100u128.into()
+            }
+            .to_string()
+        );
+
+        // as are denoms without a limit
+        let ibc_msg = dispatch(vec![BankMsg::Send {
+            to_address: "my-friend".into(),
+            amount: coins(1, "uatom"),
+        }
+        .into()]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+
+        // and contracts that are not on the list
+        let ibc_msg = dispatch(vec![WasmMsg::Execute {
+            contract_addr: "other-contract".to_string(),
+            msg: b"{}".into(),
+            funds: vec![],
+        }
+        .into()]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::ContractNotAllowed("other-contract".to_string()).to_string()
+        );
+
+        // funds sent to allowed contracts count towards the limit
+        let ibc_msg = dispatch(vec![WasmMsg::Execute {
+            contract_addr: "allowed-contract".to_string(),
+            msg: b"{}".into(),
+            funds: coins(101, "uosmo"),
+        }
+        .into()]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::TransferLimitExceeded {
+                denom: "uosmo".to_string(),
+                max: 100u128.into()
+            }
+            .to_string()
+        );
+
+        // messages the policy doesn't know are rejected
+        let ibc_msg = dispatch(vec![BankMsg::Burn {
+            amount: coins(1, "uosmo"),
+        }
+        .into()]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::MsgNotAllowed("bank_burn".to_string()).to_string()
+        );
+
+        let ibc_msg = dispatch(vec![CosmosMsg::Stargate {
+            type_url: "/cosmos.gov.v1beta1.MsgVote".to_string(),
+            value: Binary::default(),
+        }]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::MsgNotAllowed("stargate".to_string()).to_string()
+        );
+
+        // as are the inputs of swaps
+        let ibc_msg = dispatch(vec![CosmosMsg::Custom(OsmosisMsg::Swap {
+            first: osmo_bindings::Swap {
+                pool_id: 1,
+                denom_in: "uosmo".to_string(),
+                denom_out: "uatom".to_string(),
+            },
+            route: vec![],
+            amount: SwapAmountWithLimit::ExactIn {
+                input: 101u128.into(),
+                min_output: 1u128.into(),
+            },
+        })]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        assert_eq!(
+            err,
+            ContractError::TransferLimitExceeded {
+                denom: "uosmo".to_string(),
+                max: 100u128.into()
+            }
+            .to_string()
+        );
+
+        // totals that overflow are rejected too
+        let send_max = || -> CosmosMsg<OsmosisMsg> {
+            BankMsg::Send {
+                to_address: "my-friend".into(),
+                amount: vec![coin(u128::MAX, "uosmo")],
+            }
+            .into()
+        };
+        let ibc_msg = dispatch(vec![send_max(), send_max()]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let err = ack_error(ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap());
+        let overflow = OverflowError::new(OverflowOperation::Add, Uint128::MAX, Uint128::MAX);
+        assert_eq!(err, ContractError::Std(overflow.into()).to_string());
+    }
+
+    #[test]
+    fn dispatch_forwards_swaps() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        let account = "acct-123";
+        connect(deps.as_mut(), channel_id, account);
+        deps.querier.update_balance(account, coins(100, "uosmo"));
+
+        let dispatch = |msgs: Vec<CosmosMsg<OsmosisMsg>>| PacketMsg::Dispatch {
+            msgs,
+            sender: account.to_string(),
+            callback_id: None,
+        };
+        // uosmo for ujuno, through atom
+        let swap = |amount: SwapAmountWithLimit| {
+            CosmosMsg::Custom(OsmosisMsg::Swap {
+                first: osmo_bindings::Swap {
+                    pool_id: 1,
+                    denom_in: "uosmo".to_string(),
+                    denom_out: "uatom".to_string(),
+                },
+                route: vec![Step {
+                    pool_id: 2,
+                    denom_out: "ujuno".to_string(),
+                }],
+                amount,
+            })
+        };
+        let ibc_msg = dispatch(vec![
+            swap(SwapAmountWithLimit::ExactIn {
+                input: 100u128.into(),
+                min_output: 90u128.into(),
+            }),
+            swap(SwapAmountWithLimit::ExactOut {
+                output: 90u128.into(),
+                max_input: 100u128.into(),
+            }),
+        ]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: StdAck = from_slice(&res.acknowledgement).unwrap();
+        ack.unwrap();
+
+        let expected: Vec<CosmosMsg> = vec![
+            MsgSwapExactAmountIn {
+                sender: account.to_string(),
+                routes: vec![
+                    SwapAmountInRoute {
+                        pool_id: 1,
+                        token_out_denom: "uatom".to_string(),
+                    },
+                    SwapAmountInRoute {
+                        pool_id: 2,
+                        token_out_denom: "ujuno".to_string(),
+                    },
+                ],
+                token_in: Some(Coin {
+                    denom: "uosmo".to_string(),
+                    amount: "100".to_string(),
+                }),
+                token_out_min_amount: "90".to_string(),
+            }
+            .into(),
+            MsgSwapExactAmountOut {
+                sender: account.to_string(),
+                routes: vec![
+                    SwapAmountOutRoute {
+                        pool_id: 1,
+                        token_in_denom: "uosmo".to_string(),
+                    },
+                    SwapAmountOutRoute {
+                        pool_id: 2,
+                        token_in_denom: "uatom".to_string(),
+                    },
+                ],
+                token_in_max_amount: "100".to_string(),
+                token_out: Some(Coin {
+                    denom: "ujuno".to_string(),
+                    amount: "90".to_string(),
+                }),
+            }
+            .into(),
+        ];
+        assert_eq!(reflected_msgs(&res.messages[0].msg, account), expected);
+
+        // other osmosis messages can't be executed by the reflect account
+        let ibc_msg = dispatch(vec![CosmosMsg::Custom(OsmosisMsg::CreateDenom {
+            subdenom: "test".to_string(),
+        })]);
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            ack_error(res),
+            ContractError::MsgNotSupported("create_denom".to_string()).to_string()
+        );
+    }
+
+    #[test]
+    fn send_all_back_checks_channel_policy() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        let account = "acct-123";
+        connect(deps.as_mut(), channel_id, account);
+        let policy = ChannelPolicy {
+            max_transfer: Some(coins(100, "uosmo")),
+            ..Default::default()
+        };
+        let msg = ExecuteMsg::SetChannelPolicy {
+            channel_id: channel_id.to_string(),
+            policy,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+
+        let ibc_msg = PacketMsg::SendAllBack {
+            sender: account.to_string(),
+            transfer_channel: "channel-0".to_string(),
+        };
+        deps.querier.update_balance(account, coins(101, "uosmo"));
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            ack_error(res),
+            ContractError::TransferLimitExceeded {
+                denom: "uosmo".to_string(),
+                max: 100u128.into()
+            }
+            .to_string()
+        );
+
+        deps.querier.update_balance(account, coins(100, "uosmo"));
+        let msg = mock_ibc_packet_recv(channel_id, &ibc_msg).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: StdAck = from_slice(&res.acknowledgement).unwrap();
+        ack.unwrap();
+        assert_eq!(
+            reflected_msgs(&res.messages[0].msg, account),
+            vec![IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: account.to_string(),
+                amount: coin(100, "uosmo"),
+                timeout: mock_env().block.time.plus_seconds(PACKET_LIFETIME).into(),
+            }
+            .into()]
+        );
+    }
+
+    #[test]
+    fn channel_policy_contracts_are_validated() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::SetChannelPolicy {
+            channel_id: "channel-123".to_string(),
+            policy: ChannelPolicy {
+                allowed_contracts: Some(vec!["x".to_string()]),
+                ..Default::default()
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap_err();
+        let res = query_channel_policy(deps.as_ref(), "channel-123".to_string()).unwrap();
+        assert_eq!(res.policy, None);
+    }
+
+    #[test]
+    fn list_accounts_paginates() {
+        let mut deps = setup();
+
+        connect(deps.as_mut(), "channel-1", "acct-1");
+        connect(deps.as_mut(), "channel-2", "acct-2");
+        connect(deps.as_mut(), "channel-3", "acct-3");
+
+        let page = query_list_accounts(deps.as_ref(), None, Some(2)).unwrap();
+        let channels: Vec<_> = page
+            .accounts
+            .iter()
+            .map(|a| a.channel_id.as_str())
+            .collect();
+        assert_eq!(channels, vec!["channel-1", "channel-2"]);
+
+        let page =
+            query_list_accounts(deps.as_ref(), Some("channel-2".to_string()), Some(2)).unwrap();
+        assert_eq!(1, page.accounts.len());
+        assert_eq!(page.accounts[0].account, "acct-3");
+    }
+
+    /// Answers IBC channel queries with `channel` and everything else with the mock querier
+    struct ChannelQuerier {
+        base: MockQuerier,
+        channel: IbcChannel,
+    }
+
+    impl Querier for ChannelQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_slice(bin_request) {
+                Ok(QueryRequest::<Empty>::Ibc(IbcQuery::Channel { .. })) => {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&ChannelResponse {
+                            channel: Some(self.channel.clone()),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    #[test]
+    fn migrate_backfills_channel_info() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        connect(deps.as_mut(), channel_id, "acct-123");
+        // as if the channel was connected before its info was tracked
+        CHANNEL_INFO.remove(deps.as_mut().storage, channel_id);
+
+        // packets are still received
+        let msg = mock_ibc_packet_recv(channel_id, &PacketMsg::WhoAmI {}).unwrap();
+        ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let channel = mock_ibc_channel(channel_id, APP_ORDER, IBC_APP_VERSION);
+        let querier = ChannelQuerier {
+            base: MockQuerier::new(&[]),
+            channel: channel.clone(),
+        };
+        let migrate_deps = DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&querier),
+        };
+        migrate(migrate_deps, mock_env(), MigrateMsg {}).unwrap();
+
+        let info = CHANNEL_INFO.load(&deps.storage, channel_id).unwrap();
+        assert_eq!(info.connection_id, channel.connection_id);
+        assert_eq!(
+            info.counterparty_port,
+            channel.counterparty_endpoint.port_id
+        );
+        assert_eq!(info.created_at, mock_env().block.time);
+        assert_eq!(info.last_packet, None);
+    }
+
+    /// Answers the osmosis TWAP queries with the length of the window in seconds
+    struct TwapMockQuerier {
+        base: MockQuerier,
+    }
+
+    impl Querier for TwapMockQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let window = |start: Option<Timestamp>, end: Option<Timestamp>| {
+                (end.unwrap().seconds - start.unwrap().seconds).to_string()
+            };
+            let response = match from_slice(bin_request) {
+                Ok(QueryRequest::<Empty>::Stargate { path, data })
+                    if path == "/osmosis.twap.v1beta1.Query/ArithmeticTwap" =>
+                {
+                    let request = ArithmeticTwapRequest::try_from(data).unwrap();
+                    to_binary(&ArithmeticTwapResponse {
+                        arithmetic_twap: window(request.start_time, request.end_time),
+                    })
+                }
+                Ok(QueryRequest::<Empty>::Stargate { path, data })
+                    if path == "/osmosis.twap.v1beta1.Query/GeometricTwap" =>
+                {
+                    let request = GeometricTwapRequest::try_from(data).unwrap();
+                    to_binary(&GeometricTwapResponse {
+                        geometric_twap: window(request.start_time, request.end_time),
+                    })
+                }
+                _ => return self.base.raw_query(bin_request),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        }
+    }
+
+    #[test]
+    fn receive_twap_query() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        connect(deps.as_mut(), channel_id, "acct-123");
+
+        let now = mock_env().block.time;
+        let requests = vec![
+            TwapRequest {
+                pool_id: 1,
+                base_asset: "uatom".to_string(),
+                quote_asset: "uosmo".to_string(),
+                kind: TwapKind::Arithmetic,
+                start_time: now.minus_seconds(600),
+                end_time: None,
+            },
+            // same pool, other window
+            TwapRequest {
+                pool_id: 1,
+                base_asset: "uatom".to_string(),
+                quote_asset: "uosmo".to_string(),
+                kind: TwapKind::Geometric,
+                start_time: now.minus_seconds(60),
+                end_time: Some(now.minus_seconds(30)),
+            },
+        ];
+        let msg = mock_ibc_packet_recv(
+            channel_id,
+            &PacketMsg::TwapQuery {
+                sender: "client".to_string(),
+                requests: requests.clone(),
+                callback_id: None,
+            },
+        )
+        .unwrap();
+        let querier = TwapMockQuerier {
+            base: MockQuerier::new(&[]),
+        };
+        let twap_deps = DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&querier),
+        };
+        let res = ibc_packet_receive(twap_deps, mock_env(), msg).unwrap();
+
+        // the results are in the order of the requests
+        let ack: StdAck = from_slice(&res.acknowledgement).unwrap();
+        let TwapQueryResponse { results } = ack.unwrap_into();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].answers(&requests[0]));
+        assert_eq!(results[0].end_time, now);
+        assert_eq!(results[0].twap, Decimal::from_str("600").unwrap());
+        assert!(results[1].answers(&requests[1]));
+        assert_eq!(results[1].twap, Decimal::from_str("30").unwrap());
+        assert!(!results[0].answers(&requests[1]));
+    }
+
+    #[test]
+    fn query_balances_and_last_packet() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        let account = "acct-123";
+        connect(deps.as_mut(), channel_id, account);
+        let funds = vec![coin(123456, "uatom")];
+        deps.querier.update_balance(account, funds.clone());
+
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Balances {
+                channel_id: channel_id.to_string(),
+            },
+        )
+        .unwrap();
+        let res: BalancesResponse = from_slice(&raw).unwrap();
+        assert_eq!(res.account, account);
+        assert_eq!(res.balances, funds);
+
+        // receiving a packet updates the channel info
+        let msg = mock_ibc_packet_recv(channel_id, &PacketMsg::WhoAmI {}).unwrap();
+        let sequence = msg.packet.sequence;
+        ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let page = query_list_accounts(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            page.accounts[0].last_packet,
+            Some(PacketInfo {
+                sequence,
+                received_at: mock_env().block.time,
+            })
+        );
+    }
+
     #[test]
     fn check_close_channel() {
         let mut deps = setup();
@@ -721,7 +1775,15 @@ mod tests {
         deps.querier.update_balance(account, funds.clone());
 
         // channel should be listed and have balance
-        let raw = query(deps.as_ref(), mock_env(), QueryMsg::ListAccounts {}).unwrap();
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ListAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
         let res: ListAccountsResponse = from_slice(&raw).unwrap();
         assert_eq!(1, res.accounts.len());
         let balance = deps.as_ref().querier.query_all_balances(account).unwrap();
@@ -731,34 +1793,172 @@ mod tests {
         let channel = mock_ibc_channel_close_init(channel_id, APP_ORDER, IBC_APP_VERSION);
         let res = ibc_channel_close(deps.as_mut(), mock_env(), channel).unwrap();
 
-        // it pulls out all money from the reflect contract
+        // the funds stay in the reflect contract
+        assert_eq!(0, res.messages.len());
+        let balance = deps.as_ref().querier.query_all_balances(account).unwrap();
+        assert_eq!(funds, balance);
+
+        // and removes the account lookup
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ListAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: ListAccountsResponse = from_slice(&raw).unwrap();
+        assert_eq!(0, res.accounts.len());
+
+        // but records the orphaned account
+        let res = query_orphaned_accounts(deps.as_ref(), None, None).unwrap();
+        assert_eq!(1, res.accounts.len());
+        let (closed_channel, orphan) = &res.accounts[0];
+        assert_eq!(closed_channel, channel_id);
+        assert_eq!(orphan.account, account);
+        assert_eq!(orphan.balances, funds);
+    }
+
+    #[test]
+    fn reopened_channel_reclaims_account() {
+        let mut deps = setup();
+
+        let account = "acct-123";
+        connect(deps.as_mut(), "channel-1", account);
+        let channel = mock_ibc_channel_close_init("channel-1", APP_ORDER, IBC_APP_VERSION);
+        ibc_channel_close(deps.as_mut(), mock_env(), channel).unwrap();
+
+        // a new channel from the same counterparty doesn't create a new account
+        let handshake_open = mock_ibc_channel_open_init("channel-2", APP_ORDER, IBC_APP_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), handshake_open).unwrap();
+        let handshake_connect =
+            mock_ibc_channel_connect_ack("channel-2", APP_ORDER, IBC_APP_VERSION);
+        let res = ibc_channel_connect(deps.as_mut(), mock_env(), handshake_connect).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it takes over the orphaned one
+        let res = query_account(deps.as_ref(), "channel-2".to_string()).unwrap();
+        assert_eq!(res.account.unwrap(), account);
+        let res = query_orphaned_accounts(deps.as_ref(), None, None).unwrap();
+        assert_eq!(0, res.accounts.len());
+    }
+
+    #[test]
+    fn admin_recovers_orphaned_funds() {
+        let mut deps = setup();
+
+        let channel_id = "channel-123";
+        let account = "acct-123";
+        connect(deps.as_mut(), channel_id, account);
+        let funds = vec![coin(123456, "uatom")];
+        deps.querier.update_balance(account, funds.clone());
+        let channel = mock_ibc_channel_close_init(channel_id, APP_ORDER, IBC_APP_VERSION);
+        let connection_id = channel.channel().connection_id.clone();
+        let counterparty_port = channel.channel().counterparty_endpoint.port_id.clone();
+        ibc_channel_close(deps.as_mut(), mock_env(), channel).unwrap();
+
+        let recover = ExecuteMsg::RecoverFunds {
+            channel_id: channel_id.to_string(),
+            transfer_channel: "channel-ics20".to_string(),
+        };
+        // no recovery address set yet
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(CREATOR, &[]),
+            recover.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NoRecoveryAddress {
+                connection_id: connection_id.clone(),
+                port: counterparty_port.clone(),
+            }
+        );
+
+        let msg = ExecuteMsg::SetRecoveryAddress {
+            connection_id,
+            counterparty_port,
+            address: "juno1recovery".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+
+        // only the admin can recover
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), recover.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), recover).unwrap();
         assert_eq!(1, res.messages.len());
         if let CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr, msg, ..
         }) = &res.messages[0].msg
         {
             assert_eq!(contract_addr.as_str(), account);
-            let reflect: ReflectExecuteMsg = from_slice(msg).unwrap();
-            match reflect {
-                ReflectExecuteMsg::ReflectMsg { msgs } => {
-                    assert_eq!(1, msgs.len());
-                    assert_eq!(
-                        &msgs[0],
-                        &BankMsg::Send {
-                            to_address: MOCK_CONTRACT_ADDR.into(),
-                            amount: funds
-                        }
-                        .into()
-                    )
+            let rmsg: cw1_whitelist::msg::ExecuteMsg = from_slice(msg).unwrap();
+            assert_eq!(
+                rmsg,
+                cw1_whitelist::msg::ExecuteMsg::Execute {
+                    msgs: vec![IbcMsg::Transfer {
+                        channel_id: "channel-ics20".to_string(),
+                        to_address: "juno1recovery".to_string(),
+                        amount: funds[0].clone(),
+                        timeout: mock_env().block.time.plus_seconds(PACKET_LIFETIME).into(),
+                    }
+                    .into()]
                 }
-            }
+            );
         } else {
             panic!("Unexpected message: {:?}", &res.messages[0]);
         }
+    }
 
-        // and removes the account lookup
-        let raw = query(deps.as_ref(), mock_env(), QueryMsg::ListAccounts {}).unwrap();
-        let res: ListAccountsResponse = from_slice(&raw).unwrap();
-        assert_eq!(0, res.accounts.len());
+    #[test]
+    fn concurrent_handshakes() {
+        let mut deps = setup();
+
+        // both channels connect before any reflect account is instantiated
+        let mut reply_ids = vec![];
+        for channel_id in ["channel-1", "channel-2"] {
+            let handshake_open = mock_ibc_channel_open_init(channel_id, APP_ORDER, IBC_APP_VERSION);
+            ibc_channel_open(deps.as_mut(), mock_env(), handshake_open).unwrap();
+            let handshake_connect =
+                mock_ibc_channel_connect_ack(channel_id, APP_ORDER, IBC_APP_VERSION);
+            let res = ibc_channel_connect(deps.as_mut(), mock_env(), handshake_connect).unwrap();
+            assert_eq!(1, res.messages.len());
+            reply_ids.push(res.messages[0].id);
+        }
+        assert_ne!(reply_ids[0], reply_ids[1]);
+
+        // the replies arrive in reverse order
+        for (id, account) in [(reply_ids[1], "acct-2"), (reply_ids[0], "acct-1")] {
+            let response = Reply {
+                id,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: fake_events(account),
+                    data: Some(fake_data(account)),
+                }),
+            };
+            reply(deps.as_mut(), mock_env(), response).unwrap();
+        }
+
+        // and each channel gets its own account
+        let res = query_account(deps.as_ref(), "channel-1".to_string()).unwrap();
+        assert_eq!(res.account.unwrap(), "acct-1");
+        let res = query_account(deps.as_ref(), "channel-2".to_string()).unwrap();
+        assert_eq!(res.account.unwrap(), "acct-2");
+
+        // a reply can't be handled twice
+        let response = Reply {
+            id: reply_ids[0],
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: fake_events("acct-3"),
+                data: Some(fake_data("acct-3")),
+            }),
+        };
+        let err = reply(deps.as_mut(), mock_env(), response).unwrap_err();
+        assert_eq!(err, ContractError::InvalidReplyId);
     }
 }
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use cw_utils::ParseReplyError;
 
 use simple_ica::SimpleIcaError;
@@ -21,4 +21,31 @@ pub enum ContractError {
 
     #[error("Invalid reply id")]
     InvalidReplyId,
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Counterparty port {0} is not allowed to open a channel")]
+    PortNotAllowed(String),
+
+    #[error("Connection {0} is not allowed to open a channel")]
+    ConnectionNotAllowed(String),
+
+    #[error("Message {0} is not allowed on this channel")]
+    MsgNotAllowed(String),
+
+    #[error("Message {0} can't be executed by the reflect account")]
+    MsgNotSupported(String),
+
+    #[error("Contract {0} is not allowed on this channel")]
+    ContractNotAllowed(String),
+
+    #[error("Transfer of {denom} exceeds the channel limit of {max}")]
+    TransferLimitExceeded { denom: String, max: Uint128 },
+
+    #[error("No orphaned account for channel {0}")]
+    NoOrphanedAccount(String),
+
+    #[error("No recovery address set for port {port} on connection {connection_id}")]
+    NoRecoveryAddress { connection_id: String, port: String },
 }
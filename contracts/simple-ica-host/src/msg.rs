@@ -1,11 +1,57 @@
-use cosmwasm_std::CosmosMsg;
+use cosmwasm_std::{CosmosMsg, Timestamp};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    policy::ChannelPolicy,
+    state::{OrphanedAccount, PacketInfo},
+};
+
 /// Just needs to know the code_id of a reflect contract to spawn sub-accounts
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub cw1_code_id: u64,
+    /// Defaults to the sender
+    pub admin: Option<String>,
+    pub allowed_ports: Option<Vec<String>>,
+    pub allowed_connections: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    UpdateAdmin {
+        admin: String,
+    },
+    /// Replace the counterparty allowlists checked when a channel is opened.
+    /// Unset lists allow any counterparty.
+    UpdateAllowlist {
+        allowed_ports: Option<Vec<String>>,
+        allowed_connections: Option<Vec<String>>,
+    },
+    /// Set the policy applied to dispatches received on a channel
+    SetChannelPolicy {
+        channel_id: String,
+        policy: ChannelPolicy,
+    },
+    /// Remove the policy of a channel, making it unrestricted
+    RemoveChannelPolicy {
+        channel_id: String,
+    },
+    /// Set the address on the controller chain that receives the funds
+    /// recovered from orphaned accounts of this counterparty.
+    SetRecoveryAddress {
+        connection_id: String,
+        counterparty_port: String,
+        address: String,
+    },
+    /// ICS-20 transfer the balance of the orphaned account of a closed channel
+    /// to the recovery address of its counterparty.
+    RecoverFunds {
+        channel_id: String,
+        /// The ics20 channel that connects to the controller chain
+        transfer_channel: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -14,9 +60,27 @@ pub enum QueryMsg {
     /// Returns (reflect) account that is attached to this channel,
     /// or none.
     Account { channel_id: String },
-    /// Returns all (channel, reflect_account) pairs.
-    /// No pagination - this is a test contract
-    ListAccounts {},
+    /// Returns the (channel, reflect_account) pairs, ordered by channel id.
+    ListAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the balances of the reflect account attached to this channel
+    Balances { channel_id: String },
+    /// Returns the host configuration
+    Config {},
+    /// Returns the policy of a channel, if any.
+    ChannelPolicy { channel_id: String },
+    /// Returns the reflect accounts of closed channels, ordered by channel id.
+    OrphanedAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the recovery address of a counterparty, if any.
+    RecoveryAddress {
+        connection_id: String,
+        counterparty_port: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,6 +88,30 @@ pub struct AccountResponse {
     pub account: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub cw1_code_id: u64,
+    pub admin: String,
+    pub allowed_ports: Option<Vec<String>>,
+    pub allowed_connections: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelPolicyResponse {
+    pub policy: Option<ChannelPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrphanedAccountsResponse {
+    /// (closed channel id, orphaned account)
+    pub accounts: Vec<(String, OrphanedAccount)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecoveryAddressResponse {
+    pub address: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ListAccountsResponse {
     pub accounts: Vec<AccountInfo>,
@@ -33,6 +121,10 @@ pub struct ListAccountsResponse {
 pub struct AccountInfo {
     pub account: String,
     pub channel_id: String,
+    pub connection_id: String,
+    pub counterparty_port: String,
+    pub created_at: Timestamp,
+    pub last_packet: Option<PacketInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -42,6 +134,4 @@ pub enum ReflectExecuteMsg {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {
-    
-}
\ No newline at end of file
+pub struct MigrateMsg {}
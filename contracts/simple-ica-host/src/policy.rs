@@ -0,0 +1,152 @@
+//! # Channel policies
+//! Restricts the messages that a remote controller can dispatch on its reflect account.
+
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Api, BankMsg, Coin, CosmosMsg, IbcMsg, StdError, StdResult, Uint128, WasmMsg};
+use osmo_bindings::{OsmosisMsg, SwapAmountWithLimit};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+/// Restrictions applied to every dispatch received on a channel.
+/// A `None` field means that kind of message is unrestricted.
+/// Messages other than osmosis, wasm, bank sends and IBC transfers are never allowed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ChannelPolicy {
+    /// Allowed `OsmosisMsg` variants, by their snake_case name (e.g. "swap").
+    pub allowed_osmosis_msgs: Option<Vec<String>>,
+    /// Contracts that can be executed or migrated. Instantiations are blocked when set.
+    pub allowed_contracts: Option<Vec<String>>,
+    /// Max amount of each denom that can be transferred out in one dispatch, funds sent
+    /// along with contract executions and instantiations and swap inputs included.
+    /// Denoms that aren't listed can't be transferred.
+    pub max_transfer: Option<Vec<Coin>>,
+}
+
+impl ChannelPolicy {
+    /// Validates the allowed contract addresses
+    pub fn validate(mut self, api: &dyn Api) -> StdResult<Self> {
+        if let Some(allowed) = self.allowed_contracts.as_mut() {
+            for contract in allowed.iter_mut() {
+                *contract = api.addr_validate(contract)?.into_string();
+            }
+        }
+        Ok(self)
+    }
+
+    /// Errors if any of the messages is not allowed by this policy.
+    pub fn check(&self, msgs: &[CosmosMsg<OsmosisMsg>]) -> Result<(), ContractError> {
+        let mut transferred: BTreeMap<&str, Uint128> = BTreeMap::new();
+        for msg in msgs {
+            match msg {
+                CosmosMsg::Custom(osmo_msg) => {
+                    if let Some(allowed) = &self.allowed_osmosis_msgs {
+                        let kind = osmosis_msg_kind(osmo_msg);
+                        if !allowed.iter().any(|a| a == kind) {
+                            return Err(ContractError::MsgNotAllowed(kind.to_string()));
+                        }
+                    }
+                    if let OsmosisMsg::Swap { first, amount, .. } = osmo_msg {
+                        let input = match amount {
+                            SwapAmountWithLimit::ExactIn { input, .. } => *input,
+                            SwapAmountWithLimit::ExactOut { max_input, .. } => *max_input,
+                        };
+                        add_transfer(&mut transferred, &first.denom_in, input)?;
+                    }
+                }
+                CosmosMsg::Wasm(wasm_msg) => {
+                    self.check_wasm(wasm_msg)?;
+                    if let WasmMsg::Execute { funds, .. } | WasmMsg::Instantiate { funds, .. } =
+                        wasm_msg
+                    {
+                        for coin in funds {
+                            add_transfer(&mut transferred, &coin.denom, coin.amount)?;
+                        }
+                    }
+                }
+                CosmosMsg::Bank(BankMsg::Send { amount, .. }) => {
+                    for coin in amount {
+                        add_transfer(&mut transferred, &coin.denom, coin.amount)?;
+                    }
+                }
+                CosmosMsg::Ibc(IbcMsg::Transfer { amount, .. }) => {
+                    add_transfer(&mut transferred, &amount.denom, amount.amount)?;
+                }
+                other => return Err(ContractError::MsgNotAllowed(msg_kind(other).to_string())),
+            }
+        }
+        if let Some(max_transfer) = &self.max_transfer {
+            for (denom, amount) in transferred {
+                let max = max_transfer
+                    .iter()
+                    .find(|c| c.denom == denom)
+                    .map(|c| c.amount)
+                    .unwrap_or_default();
+                if amount > max {
+                    return Err(ContractError::TransferLimitExceeded {
+                        denom: denom.to_string(),
+                        max,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_wasm(&self, msg: &WasmMsg) -> Result<(), ContractError> {
+        let allowed = match &self.allowed_contracts {
+            Some(allowed) => allowed,
+            None => return Ok(()),
+        };
+        match msg {
+            WasmMsg::Execute { contract_addr, .. } | WasmMsg::Migrate { contract_addr, .. } => {
+                if allowed.contains(contract_addr) {
+                    Ok(())
+                } else {
+                    Err(ContractError::ContractNotAllowed(contract_addr.clone()))
+                }
+            }
+            _ => Err(ContractError::MsgNotAllowed("wasm".to_string())),
+        }
+    }
+}
+
+/// Adds `amount` to the total transferred of `denom`.
+fn add_transfer<'a>(
+    transferred: &mut BTreeMap<&'a str, Uint128>,
+    denom: &'a str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let total = transferred.entry(denom).or_default();
+    *total = total.checked_add(amount).map_err(StdError::from)?;
+    Ok(())
+}
+
+/// Name of a message kind that policies don't allow.
+pub(crate) fn msg_kind(msg: &CosmosMsg<OsmosisMsg>) -> &'static str {
+    #[allow(unreachable_patterns)]
+    match msg {
+        CosmosMsg::Bank(BankMsg::Burn { .. }) => "bank_burn",
+        CosmosMsg::Bank(_) => "bank",
+        CosmosMsg::Ibc(_) => "ibc",
+        CosmosMsg::Stargate { .. } => "stargate",
+        CosmosMsg::Gov(_) => "gov",
+        // staking and distribution messages, depending on the enabled cosmwasm-std features
+        _ => "unknown",
+    }
+}
+
+/// Name of the message variant as it's serialized.
+pub(crate) fn osmosis_msg_kind(msg: &OsmosisMsg) -> &'static str {
+    #[allow(unreachable_patterns)]
+    match msg {
+        OsmosisMsg::Swap { .. } => "swap",
+        OsmosisMsg::CreateDenom { .. } => "create_denom",
+        OsmosisMsg::ChangeAdmin { .. } => "change_admin",
+        OsmosisMsg::MintTokens { .. } => "mint_tokens",
+        OsmosisMsg::BurnTokens { .. } => "burn_tokens",
+        _ => "unknown",
+    }
+}
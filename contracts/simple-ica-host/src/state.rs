@@ -1,17 +1,66 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp};
 use cw_storage_plus::{Item, Map};
 
+use crate::policy::ChannelPolicy;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub cw1_code_id: u64,
+    /// Can update the config and channel policies
+    pub admin: Addr,
+    /// Counterparty ports that can open a channel, any port if not set
+    pub allowed_ports: Option<Vec<String>>,
+    /// Connections that can open a channel, any connection if not set
+    pub allowed_connections: Option<Vec<String>>,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const PENDING: Item<String> = Item::new("pending");
+/// reply id -> channel id of the reflect instantiations that are in flight
+pub const PENDING: Map<u64, String> = Map::new("pending_channels");
+/// Reply id of the next reflect instantiation
+pub const NEXT_INIT_ID: Item<u64> = Item::new("next_init_id");
 pub const ACCOUNTS: Map<&str, Addr> = Map::new("accounts");
+pub const CHANNEL_INFO: Map<&str, ChannelInfo> = Map::new("channel_info");
+/// Restrictions on the messages that can be dispatched on a channel.
+/// Channels without a policy are unrestricted.
+pub const CHANNEL_POLICIES: Map<&str, ChannelPolicy> = Map::new("channel_policies");
+/// Reflect accounts of closed channels, keyed by the closed channel id.
+pub const ORPHANED_ACCOUNTS: Map<&str, OrphanedAccount> = Map::new("orphaned_accounts");
+/// (connection_id, counterparty_port) -> address on the controller chain that receives recovered funds
+pub const RECOVERY_ADDRESSES: Map<(&str, &str), String> = Map::new("recovery_addresses");
+
+/// Metadata of a connected channel
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelInfo {
+    pub connection_id: String,
+    pub counterparty_port: String,
+    /// Time at which the channel was connected.
+    /// Channels that were connected before this was tracked have the time of the migration.
+    pub created_at: Timestamp,
+    /// Last packet that was received on this channel
+    pub last_packet: Option<PacketInfo>,
+}
+
+/// Reflect account that was left behind when its channel closed.
+/// A new channel from the same counterparty takes it over on connect.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrphanedAccount {
+    pub account: Addr,
+    pub connection_id: String,
+    pub counterparty_port: String,
+    /// Balances of the account when the channel closed
+    pub balances: Vec<Coin>,
+    pub closed_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PacketInfo {
+    pub sequence: u64,
+    pub received_at: Timestamp,
+}
 
 // this stores all results from current dispatch
 pub const RESULTS: Item<Vec<Binary>> = Item::new("results");
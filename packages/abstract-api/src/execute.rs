@@ -35,6 +35,10 @@ impl<'a, T: Serialize + DeserializeOwned> ApiContract<'a, T> {
         match msg {
             ExecuteMsg::Request(request) => {
                 let core = match request.proxy_address {
+                    // Requests that the api sends to itself act on the proxy they name.
+                    Some(addr) if sender == &env.contract.address => {
+                        self.verify_sender_is_proxy(deps.as_ref(), &Addr::unchecked(addr))?
+                    }
                     Some(addr) => {
                         let traders = self
                             .traders
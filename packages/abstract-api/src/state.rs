@@ -34,6 +34,15 @@ impl<'a, T: Serialize + DeserializeOwned> Default for ApiContract<'a, T> {
     }
 }
 
+impl<'a, T: Serialize + DeserializeOwned> Clone for ApiContract<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            target_os: self.target_os.clone(),
+            ..Self::new(self.dependencies)
+        }
+    }
+}
+
 /// Constructor
 impl<'a, T: Serialize + DeserializeOwned> ApiContract<'a, T> {
     pub const fn new(dependencies: &'static [&'static str]) -> Self {
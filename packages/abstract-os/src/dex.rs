@@ -3,7 +3,8 @@
 //! `abstract_os::dex` is a generic dex-interfacing contract that handles address retrievals and dex-interactions.
 
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_asset::Asset;
 
 use crate::objects::{AssetEntry, ContractEntry};
 
@@ -28,11 +29,17 @@ pub enum RequestMsg {
         paired_assets: Vec<AssetEntry>,
         /// Name of the Dex to use.
         dex: Option<DexName>,
+        /// Max deviation of the pool price from `belief_price`, defaults to 0.5%
+        max_spread: Option<Decimal>,
+        /// Expected price of the offered asset in the first paired asset, the pool price isn't checked when unset
+        belief_price: Option<Decimal>,
     },
     WithdrawLiquidity {
         lp_token: AssetEntry,
         amount: Uint128,
         dex: Option<DexName>,
+        /// Minimum amounts of the pool assets to receive
+        min_assets: Option<Vec<OfferAsset>>,
     },
     Swap {
         offer_asset: OfferAsset,
@@ -41,6 +48,27 @@ pub enum RequestMsg {
         max_spread: Option<Decimal>,
         belief_price: Option<Decimal>,
     },
+    /// Swap through a path of pools, each consecutive pair of assets in the path is one hop.
+    RouteSwap {
+        offer_asset: OfferAsset,
+        /// Assets to swap through, the last asset is the asset that is received.
+        path: Vec<AssetEntry>,
+        dex: Option<DexName>,
+        /// Max spread allowed on each hop
+        max_spread: Option<Decimal>,
+    },
+    /// Next hop of a [`RequestMsg::RouteSwap`], swaps the amount of `offer_asset` that the previous hop returned.
+    /// Only sent by the dex API to itself after the previous hop was executed.
+    RouteSwapHop {
+        /// Asset returned by the previous hop
+        offer_asset: AssetEntry,
+        /// Balance of `offer_asset` held by the proxy before the previous hop
+        balance_before: Uint128,
+        /// Remaining assets to swap through
+        path: Vec<AssetEntry>,
+        dex: DexName,
+        max_spread: Option<Decimal>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -52,6 +80,26 @@ pub enum ApiQueryMsg {
         ask_asset: AssetEntry,
         dex: Option<DexName>,
     },
+    /// Reserves, LP token supply and fee tier of the pool that trades the provided assets.
+    #[returns(PoolInfoResponse)]
+    PoolInfo {
+        assets: Vec<AssetEntry>,
+        dex: Option<DexName>,
+    },
+    /// Spot price of the offer asset, denominated in the ask asset.
+    #[returns(SpotPriceResponse)]
+    SpotPrice {
+        offer_asset: AssetEntry,
+        ask_asset: AssetEntry,
+        dex: Option<DexName>,
+    },
+    /// Underlying assets that are redeemable for an amount of LP tokens.
+    /// The assets are queried from the pair that minted the LP token.
+    #[returns(LpTokenValueResponse)]
+    LpTokenValue {
+        lp_token: OfferAsset,
+        dex: Option<DexName>,
+    },
 }
 
 // LP/protocol fees could be withheld from either input or output so commission asset must be included.
@@ -65,3 +113,29 @@ pub struct SimulateSwapResponse {
     /// Commission charged for the swap
     pub commission: (AssetEntry, Uint128),
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct PoolInfoResponse {
+    pub pool: ContractEntry,
+    /// Amount of each asset held by the pool, in the order they were requested.
+    pub reserves: Vec<(AssetEntry, Uint128)>,
+    /// Total supply of the pool's LP token
+    pub total_lp_supply: Uint128,
+    /// Fee charged by the pool on each swap
+    pub fee: Decimal,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SpotPriceResponse {
+    pub pool: ContractEntry,
+    /// Amount of ask_asset received for one unit of offer_asset, excluding fees and spread.
+    pub price: Decimal,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LpTokenValueResponse {
+    /// Pair that minted the LP token
+    pub pair_address: Addr,
+    /// Share of each pool asset that the LP tokens represent
+    pub assets: Vec<Asset>,
+}
@@ -12,14 +12,25 @@
 //! - Providing module name to address resolution.
 //!
 //! **The manager should be set as the contract/CosmWasm admin by default on your modules.**
+//!
+//! ## Dependencies
+//! A module can only be installed when the modules it depends on, as declared in version control, are installed.
+//! Missing dependencies are installed first when `install_dependencies` is set on [`ExecuteMsg::CreateModule`].
+//! Modules that other installed modules depend on can't be removed.
+//! ## Timelock
+//! Root can put a delay on sensitive messages, listed by their [`ExecuteMsg::timelock_names`] in [`state::Timelock`].
+//! Those messages are proposed with [`ExecuteMsg::ProposeAction`], can be executed once the delay has passed and can be cancelled meanwhile.
+//! While a delay is set, [`ExecuteMsg::UpdateTimelock`] is itself timelocked.
 //! ## Migration
 //! Migrating this contract is done by calling `ExecuteMsg::Upgrade` with `abstract::manager` as module.
 pub mod state {
     pub use crate::objects::core::OS_ID;
-    use cosmwasm_std::Addr;
+    use cosmwasm_std::{Addr, Timestamp};
     use cw_controllers::Admin;
     use cw_storage_plus::{Item, Map};
 
+    use super::ExecuteMsg;
+
     pub type Subscribed = bool;
     pub type ModuleId<'a> = &'a str;
 
@@ -39,6 +50,24 @@ pub mod state {
         pub link: Option<String>,
     }
 
+    /// Messages that have to be proposed before root can execute them
+    #[cosmwasm_schema::cw_serde]
+    #[derive(Default)]
+    pub struct Timelock {
+        /// Seconds between the proposal and the execution of an action
+        pub delay: u64,
+        /// Timelock names of the messages that are timelocked
+        pub locked_actions: Vec<String>,
+    }
+
+    /// Action proposed by root
+    #[cosmwasm_schema::cw_serde]
+    pub struct QueuedAction {
+        pub action: ExecuteMsg,
+        /// Time from which the action can be executed
+        pub eta: Timestamp,
+    }
+
     /// Subscription status
     pub const STATUS: Item<Subscribed> = Item::new("\u{0}{6}status");
     /// Configuration
@@ -51,6 +80,14 @@ pub mod state {
     pub const ROOT: Admin = Admin::new("root");
     /// Enabled Abstract modules
     pub const OS_MODULES: Map<ModuleId, Addr> = Map::new("os_modules");
+    /// Installed modules that depend on a module
+    pub const DEPENDENTS: Map<ModuleId, Vec<String>> = Map::new("dependents");
+    /// Timelock configuration
+    pub const TIMELOCK: Item<Timelock> = Item::new("timelock");
+    /// Proposed actions by id
+    pub const QUEUED_ACTIONS: Map<u64, QueuedAction> = Map::new("queued_actions");
+    /// Id of the next proposed action
+    pub const NEXT_ACTION_ID: Item<u64> = Item::new("next_action_id");
 }
 
 use cosmwasm_schema::QueryResponses;
@@ -59,7 +96,7 @@ use cw2::ContractVersion;
 
 use crate::objects::module::{Module, ModuleInfo};
 
-use self::state::OsInfo;
+use self::state::{OsInfo, QueuedAction};
 
 #[cosmwasm_schema::cw_serde]
 pub struct MigrateMsg {}
@@ -95,13 +132,17 @@ pub enum ExecuteMsg {
         module: ModuleInfo,
         /// Instantiate message used to instantiate the contract.
         init_msg: Option<Binary>,
+        /// Install the missing dependencies of the module first, without a root init message.
+        #[serde(default)]
+        install_dependencies: bool,
     },
     /// Registers a module after creation.
     /// Only callable by module factory.
     RegisterModule { module_addr: String, module: Module },
     /// Remove a module
     RemoveModule { module_id: String },
-    /// Upgrade the module to a new version
+    /// Upgrade the module to a new version, versions are resolved by version control.
+    /// Modules can only be migrated to a higher version than the installed one.
     /// If module is `abstract::manager` then the contract will do a self-migration.
     Upgrade {
         module: ModuleInfo,
@@ -118,8 +159,65 @@ pub enum ExecuteMsg {
         root: String,
         governance_type: Option<String>,
     },
-    /// Suspend manager contract
+    /// Suspends or re-activates the OS, callable by the subscription contract or by root if the OS has none.
+    /// A suspended manager only accepts this message and its proxy refuses module actions.
     SuspendOs { new_status: bool },
+    /// Queues a timelocked action, callable by root.
+    /// The action can be executed once the timelock delay has passed.
+    ProposeAction { action: Box<ExecuteMsg> },
+    /// Executes a queued action whose delay has passed, callable by root.
+    ExecuteAction { action_id: u64 },
+    /// Removes a queued action, callable by root.
+    CancelAction { action_id: u64 },
+    /// Updates the timelock delay (in seconds) and the timelock names of the locked messages, callable by root.
+    UpdateTimelock {
+        delay: Option<u64>,
+        locked_actions: Option<Vec<String>>,
+    },
+}
+
+/// Prefix of the timelock name of [`ExecuteMsg::ExecOnModule`] for a specific module
+pub const EXEC_ON_MODULE_PREFIX: &str = "exec_on_module:";
+
+/// Timelock names of the messages
+pub const TIMELOCK_NAMES: &[&str] = &[
+    "exec_on_module",
+    "update_module_addresses",
+    "create_module",
+    "remove_module",
+    "upgrade",
+    "update_info",
+    "set_root",
+    "update_timelock",
+];
+
+impl ExecuteMsg {
+    /// Names under which the message can be timelocked: its snake_case name,
+    /// and `exec_on_module:<module_id>` for messages that are forwarded to a module.
+    /// Module registration, status changes and the timelock actions themselves can't be timelocked.
+    pub fn timelock_names(&self) -> Vec<String> {
+        let name = match self {
+            ExecuteMsg::ExecOnModule { module_id, .. } => {
+                return vec![
+                    "exec_on_module".to_string(),
+                    format!("{}{}", EXEC_ON_MODULE_PREFIX, module_id),
+                ]
+            }
+            ExecuteMsg::UpdateModuleAddresses { .. } => "update_module_addresses",
+            ExecuteMsg::CreateModule { .. } => "create_module",
+            ExecuteMsg::RemoveModule { .. } => "remove_module",
+            ExecuteMsg::Upgrade { .. } => "upgrade",
+            ExecuteMsg::UpdateInfo { .. } => "update_info",
+            ExecuteMsg::SetRoot { .. } => "set_root",
+            ExecuteMsg::UpdateTimelock { .. } => "update_timelock",
+            ExecuteMsg::RegisterModule { .. }
+            | ExecuteMsg::SuspendOs { .. }
+            | ExecuteMsg::ProposeAction { .. }
+            | ExecuteMsg::ExecuteAction { .. }
+            | ExecuteMsg::CancelAction { .. } => return vec![],
+        };
+        vec![name.to_string()]
+    }
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -143,6 +241,20 @@ pub enum QueryMsg {
     /// Returns [`InfoResponse`]
     #[returns(InfoResponse)]
     Info {},
+    /// Returns the installed modules that depend on a module
+    /// [`DependentsResponse`]
+    #[returns(DependentsResponse)]
+    Dependents { module_id: String },
+    /// Returns [`TimelockResponse`]
+    #[returns(TimelockResponse)]
+    Timelock {},
+    /// Returns the queued actions with the time from which they can be executed
+    /// [`QueuedActionsResponse`]
+    #[returns(QueuedActionsResponse)]
+    QueuedActions {
+        page_token: Option<u64>,
+        page_size: Option<u8>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -163,6 +275,11 @@ pub struct ConfigResponse {
     pub os_id: Uint64,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct DependentsResponse {
+    pub dependents: Vec<String>,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct InfoResponse {
     pub info: OsInfo,
@@ -179,3 +296,14 @@ pub struct ManagerModuleInfo {
 pub struct ModuleInfosResponse {
     pub module_infos: Vec<ManagerModuleInfo>,
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct TimelockResponse {
+    pub delay: u64,
+    pub locked_actions: Vec<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct QueuedActionsResponse {
+    pub actions: Vec<(u64, QueuedAction)>,
+}
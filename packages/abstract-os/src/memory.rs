@@ -4,6 +4,10 @@
 //!
 //! ## Description
 //! Contract and asset addresses are stored on the memory contract and are retrievable trough smart or raw queries.
+//!
+//! ## Cross-chain assets
+//! Assets that are transferable over IBC are stored as [`CrossChainAsset`]s, which provide the denom of the asset on each connected chain.
+//! The channels between chains are stored per (source chain, destination chain) pair.
 
 use cosmwasm_schema::QueryResponses;
 use cw_asset::{AssetInfo, AssetInfoUnchecked};
@@ -11,6 +15,7 @@ use cw_asset::{AssetInfo, AssetInfoUnchecked};
 use crate::objects::{
     asset_entry::AssetEntry,
     contract_entry::{ContractEntry, UncheckedContractEntry},
+    cross_chain::{ChannelRecord, CrossChainAsset},
 };
 
 /// Memory state details
@@ -20,7 +25,11 @@ pub mod state {
     use cw_controllers::Admin;
     use cw_storage_plus::Map;
 
-    use crate::objects::{asset_entry::AssetEntry, contract_entry::ContractEntry};
+    use crate::objects::{
+        asset_entry::AssetEntry,
+        contract_entry::ContractEntry,
+        cross_chain::{ChannelRecord, CrossChainAsset},
+    };
 
     /// Admin address store
     pub const ADMIN: Admin = Admin::new("admin");
@@ -32,6 +41,12 @@ pub mod state {
     /// Pairs are stored here as (dex_name, pair_id)
     /// pair_id is "asset1_asset2" where the asset names are sorted alphabetically.
     pub const CONTRACT_ADDRESSES: Map<ContractEntry, Addr> = Map::new("contracts");
+
+    /// Stores assets that are transferable over IBC
+    pub const CROSS_CHAIN_ASSETS: Map<AssetEntry, CrossChainAsset> = Map::new("xc_assets");
+
+    /// Stores the channel to reach a chain, keyed by (source chain, destination chain)
+    pub const CHANNELS: Map<(&str, &str), ChannelRecord> = Map::new("channels");
 }
 
 /// Memory Instantiate msg
@@ -55,6 +70,20 @@ pub enum ExecuteMsg {
         /// Assets to remove
         to_remove: Vec<String>,
     },
+    /// Updates the cross-chain assets
+    UpdateCrossChainAssets {
+        /// Assets to update or add
+        to_add: Vec<(String, CrossChainAsset)>,
+        /// Assets to remove
+        to_remove: Vec<String>,
+    },
+    /// Updates the channels between chains
+    UpdateChannels {
+        /// Channels to update or add, keyed by (source chain, destination chain)
+        to_add: Vec<((String, String), ChannelRecord)>,
+        /// Channels to remove
+        to_remove: Vec<(String, String)>,
+    },
     /// Sets a new Admin
     SetAdmin { admin: String },
 }
@@ -91,6 +120,21 @@ pub enum QueryMsg {
         page_token: Option<String>,
         page_size: Option<u8>,
     },
+    /// Queries cross-chain assets based on name
+    /// returns [`CrossChainAssetsResponse`]
+    #[returns(CrossChainAssetsResponse)]
+    CrossChainAssets { names: Vec<String> },
+    /// Queries the denom of a cross-chain asset on a chain
+    /// returns [`AssetDenomResponse`]
+    #[returns(AssetDenomResponse)]
+    AssetDenom { name: String, chain: String },
+    /// Queries the channel that reaches `dst_chain` from `src_chain`
+    /// returns [`ChannelResponse`]
+    #[returns(ChannelResponse)]
+    Channel {
+        src_chain: String,
+        dst_chain: String,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -115,6 +159,21 @@ pub struct AssetListResponse {
     pub assets: Vec<(AssetEntry, AssetInfo)>,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct CrossChainAssetsResponse {
+    pub assets: Vec<(AssetEntry, CrossChainAsset)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AssetDenomResponse {
+    pub denom: String,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ChannelResponse {
+    pub channel: ChannelRecord,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct ContractListResponse {
     /// Contracts (name, address)
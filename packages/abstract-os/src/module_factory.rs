@@ -4,7 +4,12 @@
 //!
 //! ## Description  
 //! This contract is instantiated by Abstract and only used internally. Adding or upgrading modules is done using the [`crate::manager::ExecuteMsg`] endpoint.  
+//!
+//! ## OS templates
+//! An [`OsTemplate`] lists the modules an OS is created with, together with its proxy assets and trader grants.
+//! Templates are stored here by name and applied by the [`crate::os_factory`] when an OS is created with a template.
 pub mod state {
+    use super::OsTemplate;
     use crate::{
         objects::module::{Module, ModuleInfo},
         version_control::Core,
@@ -31,14 +36,60 @@ pub mod state {
     pub const CONFIG: Item<Config> = Item::new("\u{0}{5}config");
     pub const CONTEXT: Item<Context> = Item::new("\u{0}{7}context");
     pub const MODULE_INIT_BINARIES: Map<ModuleInfo, Binary> = Map::new("module_init_binaries");
+    /// OS templates by name
+    pub const OS_TEMPLATES: Map<&str, OsTemplate> = Map::new("os_templates");
 }
 
 use crate::{
-    objects::module::{Module, ModuleInfo},
+    objects::{
+        module::{Module, ModuleInfo},
+        proxy_asset::UncheckedProxyAsset,
+    },
     version_control::Core,
 };
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::Binary;
+use cosmwasm_std::{Addr, Binary};
+
+/// Placeholder in template init messages that's replaced by the proxy address of the new OS
+pub const PROXY_PLACEHOLDER: &str = "{proxy}";
+/// Placeholder in template init messages that's replaced by the manager address of the new OS
+pub const MANAGER_PLACEHOLDER: &str = "{manager}";
+/// Placeholder in template init messages that's replaced by the memory address
+pub const MEMORY_PLACEHOLDER: &str = "{memory}";
+
+/// Modules and proxy configuration a new OS is set up with.
+#[cosmwasm_schema::cw_serde]
+pub struct OsTemplate {
+    /// Modules that are installed in order, dependencies must be listed before their dependents.
+    pub modules: Vec<TemplateModule>,
+    /// Assets that are added to the proxy once the modules are installed
+    pub proxy_assets: Vec<UncheckedProxyAsset>,
+    /// Module ids of installed apis, with the ids of the installed modules that become traders on them
+    pub traders: Vec<(String, Vec<String>)>,
+}
+
+/// Module that's installed by an [`OsTemplate`]
+#[cosmwasm_schema::cw_serde]
+pub struct TemplateModule {
+    pub module: ModuleInfo,
+    /// JSON init message of the module.
+    /// Placeholders for the proxy, manager and memory addresses are substituted before it's sent.
+    pub init_msg: Option<String>,
+}
+
+impl TemplateModule {
+    /// Init message with the placeholders replaced by the addresses of the OS
+    pub fn format_init_msg(&self, core: &Core, memory: &Addr) -> Option<Binary> {
+        self.init_msg.as_ref().map(|msg| {
+            Binary::from(
+                msg.replace(PROXY_PLACEHOLDER, core.proxy.as_str())
+                    .replace(MANAGER_PLACEHOLDER, core.manager.as_str())
+                    .replace(MEMORY_PLACEHOLDER, memory.as_str())
+                    .into_bytes(),
+            )
+        })
+    }
+}
 
 #[cosmwasm_schema::cw_serde]
 pub struct InstantiateMsg {
@@ -66,6 +117,11 @@ pub enum ExecuteMsg {
         to_add: Vec<(ModuleInfo, Binary)>,
         to_remove: Vec<ModuleInfo>,
     },
+    /// Adds, replaces or removes OS templates
+    UpdateOsTemplates {
+        to_add: Vec<(String, OsTemplate)>,
+        to_remove: Vec<String>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -75,6 +131,8 @@ pub enum QueryMsg {
     Config {},
     #[returns(ContextResponse)]
     Context {},
+    #[returns(OsTemplateResponse)]
+    OsTemplate { name: String },
 }
 
 // We define a custom struct for each query response
@@ -91,6 +149,11 @@ pub struct ContextResponse {
     pub module: Option<Module>,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct OsTemplateResponse {
+    pub template: OsTemplate,
+}
+
 /// We currently take no arguments for migrations
 #[cosmwasm_schema::cw_serde]
 pub struct MigrateMsg {}
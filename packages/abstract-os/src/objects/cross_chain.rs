@@ -0,0 +1,110 @@
+//! # Cross-chain assets
+//! Records of assets that are transferable over IBC and of the channels that connect chains.
+//! They're stored in the [`crate::memory`] contract. Chain names are stored lowercase.
+
+use cosmwasm_std::{StdError, StdResult};
+use sha2::{Digest, Sha256};
+
+/// An asset that's native to one chain and can be transferred to others over IBC.
+#[cosmwasm_schema::cw_serde]
+pub struct CrossChainAsset {
+    /// Chain on which the asset is native
+    pub home_chain: String,
+    /// Denom of the asset on its home chain
+    pub denom: String,
+    /// IBC path of the asset on each connected chain, as (chain, path).
+    /// The path is the trace of ports and channels the asset took, e.g. "transfer/channel-0"
+    pub ibc_paths: Vec<(String, String)>,
+}
+
+impl CrossChainAsset {
+    /// Lowercases the chain names
+    pub fn check(mut self) -> Self {
+        self.home_chain = self.home_chain.to_ascii_lowercase();
+        for (chain, _) in self.ibc_paths.iter_mut() {
+            *chain = chain.to_ascii_lowercase();
+        }
+        self
+    }
+
+    /// Denom of the asset on `chain`
+    pub fn denom_on(&self, chain: &str) -> StdResult<String> {
+        let chain = chain.to_ascii_lowercase();
+        if chain == self.home_chain {
+            return Ok(self.denom.clone());
+        }
+        self.ibc_paths
+            .iter()
+            .find(|(c, _)| c == &chain)
+            .map(|(_, path)| ibc_denom(path, &self.denom))
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "asset {} from {} is not available on {}",
+                    self.denom, self.home_chain, chain
+                ))
+            })
+    }
+}
+
+/// Channel that connects a chain to another chain
+#[cosmwasm_schema::cw_serde]
+pub struct ChannelRecord {
+    pub port: String,
+    pub channel_id: String,
+    pub counterparty_channel_id: String,
+}
+
+/// Denom of an asset with `base_denom` that was transferred over `path`, e.g. "ibc/27394FB0..."
+pub fn ibc_denom(path: &str, base_denom: &str) -> String {
+    let hash = Sha256::digest(format!("{}/{}", path, base_denom).as_bytes());
+    let hex: String = hash.iter().map(|byte| format!("{:02X}", byte)).collect();
+    format!("ibc/{}", hex)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn atom() -> CrossChainAsset {
+        CrossChainAsset {
+            home_chain: "cosmoshub".into(),
+            denom: "uatom".into(),
+            ibc_paths: vec![("osmosis".into(), "transfer/channel-0".into())],
+        }
+    }
+
+    #[test]
+    fn computes_ibc_denoms() {
+        assert_eq!(
+            ibc_denom("transfer/channel-0", "uatom"),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test]
+    fn denom_per_chain() {
+        let atom = atom();
+        assert_eq!(atom.denom_on("cosmoshub").unwrap(), "uatom");
+        assert_eq!(
+            atom.denom_on("osmosis").unwrap(),
+            ibc_denom("transfer/channel-0", "uatom")
+        );
+        atom.denom_on("juno").unwrap_err();
+
+        let atom = CrossChainAsset {
+            home_chain: "CosmosHub".into(),
+            ..atom
+        }
+        .check();
+        assert_eq!(atom.denom_on("COSMOSHUB").unwrap(), "uatom");
+        // chains the asset was transferred to are matched case-insensitively too
+        assert_eq!(
+            atom.denom_on("Osmosis").unwrap(),
+            ibc_denom("transfer/channel-0", "uatom")
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! # Module Dependencies
+//! Modules declare the modules they depend on in version control.
+//! The manager uses these declarations to check, order and guard module installations and removals.
+
+use cosmwasm_std::{StdError, StdResult};
+use semver::{Version, VersionReq};
+
+/// Module that must be installed on the OS before the module that depends on it.
+#[cosmwasm_schema::cw_serde]
+pub struct Dependency {
+    /// Id of the module, formatted as "provider:name"
+    pub id: String,
+    /// Semver requirements the installed version must fulfill, e.g. ">=0.1.0" and "<0.3".
+    /// Any version is accepted when empty.
+    pub version_req: Vec<String>,
+}
+
+impl Dependency {
+    pub fn new(id: impl Into<String>, version_req: Vec<String>) -> Self {
+        Self {
+            id: id.into(),
+            version_req,
+        }
+    }
+
+    /// Errors if the id isn't a lowercase "provider:name" or a version requirement can't be parsed.
+    pub fn validate(&self) -> StdResult<()> {
+        let parts: Vec<&str> = self.id.split(':').collect();
+        if parts.len() != 2
+            || parts.iter().any(|part| part.is_empty())
+            || self.id != self.id.to_lowercase()
+        {
+            return Err(StdError::generic_err(format!(
+                "dependency id {} must be formatted as provider:name in lowercase",
+                self.id
+            )));
+        }
+        self.requirements().map(|_| ())
+    }
+
+    /// Returns whether `version` fulfills all the version requirements.
+    pub fn matches(&self, version: &str) -> StdResult<bool> {
+        let version = Version::parse(version).map_err(|e| {
+            StdError::generic_err(format!("invalid version {} of {}: {}", version, self.id, e))
+        })?;
+        Ok(self.requirements()?.iter().all(|req| req.matches(&version)))
+    }
+
+    fn requirements(&self) -> StdResult<Vec<VersionReq>> {
+        self.version_req
+            .iter()
+            .map(|req| {
+                VersionReq::parse(req).map_err(|e| {
+                    StdError::generic_err(format!(
+                        "invalid version requirement {} for {}: {}",
+                        req, self.id, e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Lists the modules that have to be installed to install `module`, dependencies first and `module` last.
+/// Modules that are in `installed` are not listed and their dependencies are not resolved.
+/// `dependencies` returns the declared dependencies of a module id.
+pub fn install_order<F>(
+    module: &str,
+    installed: &[String],
+    dependencies: F,
+) -> StdResult<Vec<String>>
+where
+    F: Fn(&str) -> StdResult<Vec<Dependency>>,
+{
+    let mut order = vec![];
+    let mut path = vec![];
+    visit(module, installed, &dependencies, &mut path, &mut order)?;
+    Ok(order)
+}
+
+// Depth first, `path` holds the modules that are being resolved to detect cycles.
+fn visit<F>(
+    module: &str,
+    installed: &[String],
+    dependencies: &F,
+    path: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> StdResult<()>
+where
+    F: Fn(&str) -> StdResult<Vec<Dependency>>,
+{
+    if order.iter().any(|m| m == module) || installed.iter().any(|m| m == module) {
+        return Ok(());
+    }
+    if path.iter().any(|m| m == module) {
+        return Err(StdError::generic_err(format!(
+            "circular dependency: {} -> {}",
+            path.join(" -> "),
+            module
+        )));
+    }
+    path.push(module.to_string());
+    for dependency in dependencies(module)? {
+        visit(&dependency.id, installed, dependencies, path, order)?;
+    }
+    path.pop();
+    order.push(module.to_string());
+    Ok(())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn declared(module: &str) -> StdResult<Vec<Dependency>> {
+        Ok(match module {
+            "abstract:etf" => vec![
+                Dependency::new("abstract:balancer", vec![]),
+                Dependency::new("abstract:dex", vec!["^0.1".into()]),
+            ],
+            "abstract:balancer" => vec![Dependency::new("abstract:dex", vec![])],
+            "test:loop_a" => vec![Dependency::new("test:loop_b", vec![])],
+            "test:loop_b" => vec![Dependency::new("test:loop_a", vec![])],
+            _ => vec![],
+        })
+    }
+
+    #[test]
+    fn matches_requirements() {
+        let dependency = Dependency::new("abstract:dex", vec![">=0.1.0".into(), "<0.3".into()]);
+        assert!(dependency.matches("0.1.0").unwrap());
+        assert!(dependency.matches("0.2.5").unwrap());
+        assert!(!dependency.matches("0.3.0").unwrap());
+        assert!(dependency.matches("latest").is_err());
+        assert!(Dependency::new("abstract:dex", vec![])
+            .matches("9.0.0")
+            .unwrap());
+        assert!(Dependency::new("abstract:dex", vec!["not a req".into()])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn validates_ids() {
+        Dependency::new("abstract:dex", vec!["^0.1".into()])
+            .validate()
+            .unwrap();
+        for id in [
+            "dex",
+            "abstract:",
+            ":dex",
+            "abstract:dex:v1",
+            "Abstract:dex",
+        ] {
+            assert_eq!(
+                Dependency::new(id, vec![]).validate().unwrap_err(),
+                StdError::generic_err(format!(
+                    "dependency id {} must be formatted as provider:name in lowercase",
+                    id
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn orders_dependencies_first() {
+        let order = install_order("abstract:etf", &[], declared).unwrap();
+        assert_eq!(
+            order,
+            vec!["abstract:dex", "abstract:balancer", "abstract:etf"]
+        );
+
+        let order = install_order("abstract:etf", &["abstract:dex".to_string()], declared).unwrap();
+        assert_eq!(order, vec!["abstract:balancer", "abstract:etf"]);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let err = install_order("test:loop_a", &[], declared).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("circular dependency: test:loop_a -> test:loop_b -> test:loop_a")
+        );
+    }
+}
@@ -1,10 +1,12 @@
 //! # Governance structure object
 
+use cw4::Member;
+use cw_utils::{Duration, Threshold};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Governance types
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
 
 pub enum GovernanceDetails {
     /// A single address is admin
@@ -19,6 +21,25 @@ pub enum GovernanceDetails {
         /// Governance type used for doing extra off-chain queries depending on the type.
         governance_type: String,
     },
+    /// A cw3 fixed multisig with the members as voters is instantiated as root
+    Multisig {
+        /// Voters and their weights
+        members: Vec<Member>,
+        /// Weight required for a proposal to pass
+        threshold: Threshold,
+        /// Time a proposal is open for voting
+        max_voting_period: Duration,
+    },
+    /// A cw4 group with a cw3 flex multisig on top is instantiated as root.
+    /// The multisig is admin of the group, so members are changed by proposal.
+    Dao {
+        /// Initial members of the group and their weights
+        members: Vec<Member>,
+        /// Weight required for a proposal to pass
+        threshold: Threshold,
+        /// Time a proposal is open for voting
+        max_voting_period: Duration,
+    },
 }
 
 impl ToString for GovernanceDetails {
@@ -29,6 +50,8 @@ impl ToString for GovernanceDetails {
                 governance_address: _,
                 governance_type,
             } => governance_type.clone(),
+            GovernanceDetails::Multisig { .. } => "multisig".to_string(),
+            GovernanceDetails::Dao { .. } => "dao".to_string(),
         }
     }
 }
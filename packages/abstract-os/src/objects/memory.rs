@@ -4,9 +4,19 @@ use cosmwasm_std::{Addr, Deps, StdError, StdResult};
 
 use cw_asset::AssetInfo;
 
-use crate::memory::state::{ASSET_ADDRESSES, CONTRACT_ADDRESSES};
+use crate::memory::{
+    state::{ASSET_ADDRESSES, CHANNELS, CONTRACT_ADDRESSES, CROSS_CHAIN_ASSETS},
+    AssetsResponse, ContractsResponse, QueryMsg,
+};
 
-use super::{asset_entry::AssetEntry, contract_entry::ContractEntry};
+use super::{
+    asset_entry::AssetEntry,
+    contract_entry::ContractEntry,
+    cross_chain::{ChannelRecord, CrossChainAsset},
+};
+
+/// Above this number of keys, entries are resolved with a single smart query instead of a raw query per key.
+pub const BATCH_QUERY_THRESHOLD: usize = 3;
 
 /// Struct that provides easy in-contract memory querying.
 #[cosmwasm_schema::cw_serde]
@@ -16,12 +26,23 @@ pub struct Memory {
 }
 
 impl Memory {
-    /// Raw Query to Memory contract
+    /// Query to Memory contract, batched in one smart query above the [`BATCH_QUERY_THRESHOLD`]
     pub fn query_contracts(
         &self,
         deps: Deps,
         contracts: Vec<ContractEntry>,
     ) -> StdResult<BTreeMap<ContractEntry, Addr>> {
+        if contracts.len() > BATCH_QUERY_THRESHOLD {
+            let res: ContractsResponse = deps
+                .querier
+                .query_wasm_smart(&self.address, &QueryMsg::Contracts { names: contracts })?;
+            // Addresses are checked when stored.
+            return Ok(res
+                .contracts
+                .into_iter()
+                .map(|(entry, addr)| (entry, Addr::unchecked(addr)))
+                .collect());
+        }
         let mut resolved_contracts: BTreeMap<ContractEntry, Addr> = BTreeMap::new();
 
         // Query over keys
@@ -47,12 +68,19 @@ impl Memory {
         Ok(Addr::unchecked(result))
     }
 
-    /// Raw Query to Memory contract
+    /// Query to Memory contract, batched in one smart query above the [`BATCH_QUERY_THRESHOLD`]
     pub fn query_assets(
         &self,
         deps: Deps,
         assets: Vec<AssetEntry>,
     ) -> StdResult<BTreeMap<AssetEntry, AssetInfo>> {
+        if assets.len() > BATCH_QUERY_THRESHOLD {
+            let names = assets.into_iter().map(|a| a.as_str().to_string()).collect();
+            let res: AssetsResponse = deps
+                .querier
+                .query_wasm_smart(&self.address, &QueryMsg::Assets { names })?;
+            return Ok(res.assets.into_iter().collect());
+        }
         let mut resolved_assets: BTreeMap<AssetEntry, AssetInfo> = BTreeMap::new();
 
         for asset in assets.into_iter() {
@@ -76,6 +104,61 @@ impl Memory {
         Ok(result)
     }
 
+    /// Raw query of a cross-chain asset
+    pub fn query_cross_chain_asset(
+        &self,
+        deps: Deps,
+        asset: &AssetEntry,
+    ) -> StdResult<CrossChainAsset> {
+        self.may_query_cross_chain_asset(deps, asset)?
+            .ok_or_else(|| {
+                StdError::generic_err(format!("cross-chain asset {} not found in memory", asset))
+            })
+    }
+
+    /// Raw query of a cross-chain asset, `None` when it isn't registered
+    pub fn may_query_cross_chain_asset(
+        &self,
+        deps: Deps,
+        asset: &AssetEntry,
+    ) -> StdResult<Option<CrossChainAsset>> {
+        CROSS_CHAIN_ASSETS.query(&deps.querier, self.address.clone(), asset.clone())
+    }
+
+    /// Denom of a cross-chain asset on `chain`
+    pub fn query_asset_denom(
+        &self,
+        deps: Deps,
+        asset: &AssetEntry,
+        chain: &str,
+    ) -> StdResult<String> {
+        self.query_cross_chain_asset(deps, asset)?.denom_on(chain)
+    }
+
+    /// Raw query of the channel that reaches `dst_chain` from `src_chain`
+    pub fn query_channel(
+        &self,
+        deps: Deps,
+        src_chain: &str,
+        dst_chain: &str,
+    ) -> StdResult<ChannelRecord> {
+        CHANNELS
+            .query(
+                &deps.querier,
+                self.address.clone(),
+                (
+                    src_chain.to_ascii_lowercase().as_str(),
+                    dst_chain.to_ascii_lowercase().as_str(),
+                ),
+            )?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "no channel from {} to {} in memory",
+                    src_chain, dst_chain
+                ))
+            })
+    }
+
     // Query single pair address from mem
     // pub fn query_pair_address(
     //     &self,
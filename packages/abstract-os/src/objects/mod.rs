@@ -5,6 +5,8 @@ pub(crate) mod asset_entry;
 pub mod common_namespace;
 pub(crate) mod contract_entry;
 pub mod core;
+pub mod cross_chain;
+pub mod dependency;
 pub mod deposit_info;
 pub mod deposit_manager;
 pub mod fee;
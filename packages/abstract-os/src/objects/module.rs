@@ -3,6 +3,7 @@ use std::fmt::{self, Display};
 use cosmwasm_std::{to_binary, Binary, StdError, StdResult};
 use cw2::ContractVersion;
 use cw_storage_plus::{Key, KeyDeserialize, Prefixer, PrimaryKey};
+use semver::{Version, VersionReq};
 
 use super::module_reference::ModuleReference;
 
@@ -37,7 +38,7 @@ impl ModuleInfo {
 
     pub fn assert_version_variant(&self) -> StdResult<()> {
         match self.version {
-            ModuleVersion::Latest {} => Err(StdError::generic_err(
+            ModuleVersion::Latest {} | ModuleVersion::Requirement(_) => Err(StdError::generic_err(
                 "Module version must be set for this action.",
             )),
             ModuleVersion::Version(_) => Ok(()),
@@ -58,11 +59,7 @@ impl<'a> PrimaryKey<'a> for ModuleInfo {
     fn key(&self) -> Vec<cw_storage_plus::Key> {
         let mut keys = self.provider.key();
         keys.extend(self.name.key());
-        let temp = match &self.version {
-            ModuleVersion::Latest {} => "latest".key(),
-            ModuleVersion::Version(ver) => ver.key(),
-        };
-        keys.extend(temp);
+        keys.extend(self.version.prefix());
         keys
     }
 }
@@ -78,11 +75,12 @@ impl<'a> Prefixer<'a> for ModuleInfo {
 
 impl<'a> Prefixer<'a> for ModuleVersion {
     fn prefix(&self) -> Vec<Key> {
-        let self_as_bytes = match &self {
-            ModuleVersion::Latest {} => "latest".as_bytes(),
-            ModuleVersion::Version(ver) => ver.as_bytes(),
-        };
-        vec![Key::Ref(self_as_bytes)]
+        match &self {
+            ModuleVersion::Latest {} => vec![Key::Ref("latest".as_bytes())],
+            ModuleVersion::Version(ver) => vec![Key::Ref(ver.as_bytes())],
+            // prefixed so it can't collide with the exact version it resembles
+            ModuleVersion::Requirement(_) => vec![Key::Owned(self.to_string().into_bytes())],
+        }
     }
 }
 
@@ -115,6 +113,8 @@ impl KeyDeserialize for ModuleVersion {
         let val = String::from_utf8(value).map_err(StdError::invalid_utf8)?;
         if &val == "latest" {
             Ok(Self::Latest {})
+        } else if let Some(req) = val.strip_prefix(REQUIREMENT_PREFIX) {
+            Ok(Self::Requirement(req.to_string()))
         } else {
             Ok(Self::Version(val))
         }
@@ -131,23 +131,96 @@ fn parse_length(value: &[u8]) -> StdResult<usize> {
     .into())
 }
 
+/// Version of a module. Modules are registered with an exact semver [`ModuleVersion::Version`],
+/// [`ModuleVersion::Latest`] and [`ModuleVersion::Requirement`] are resolved to the highest matching registered version.
 #[cosmwasm_schema::cw_serde]
 pub enum ModuleVersion {
     Latest {},
     Version(String),
+    /// Semver requirement, e.g. "^0.1"
+    Requirement(String),
+}
+
+impl ModuleVersion {
+    /// Returns whether `version` is selected by this module version.
+    pub fn matches(&self, version: &Version) -> StdResult<bool> {
+        match self {
+            ModuleVersion::Latest {} => Ok(true),
+            ModuleVersion::Version(ver) => Ok(&parse_version(ver)? == version),
+            ModuleVersion::Requirement(req) => Ok(VersionReq::parse(req)
+                .map_err(|e| {
+                    StdError::generic_err(format!("invalid version requirement {}: {}", req, e))
+                })?
+                .matches(version)),
+        }
+    }
 }
 
+/// Semver doesn't allow ':' so requirements never display as a registered version
+const REQUIREMENT_PREFIX: &str = "req:";
+
 // Do not change!!
 impl Display for ModuleVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let print_str = match self {
             ModuleVersion::Latest {} => "latest".to_string(),
             ModuleVersion::Version(ver) => ver.to_owned(),
+            ModuleVersion::Requirement(req) => format!("{}{}", REQUIREMENT_PREFIX, req),
         };
         f.write_str(&print_str)
     }
 }
 
+/// Parse a registered module version
+pub fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version)
+        .map_err(|e| StdError::generic_err(format!("invalid module version {}: {}", version, e)))
+}
+
+/// Sorts versions in ascending semver order.
+/// Versions that aren't valid semver, like a legacy "latest" entry, are left out.
+pub fn sort_versions(versions: Vec<String>) -> Vec<String> {
+    let mut parsed: Vec<(Version, String)> = versions
+        .into_iter()
+        .filter_map(|ver| Some((Version::parse(&ver).ok()?, ver)))
+        .collect();
+    parsed.sort();
+    parsed.into_iter().map(|(_, ver)| ver).collect()
+}
+
+/// Returns the highest of `versions` that's selected by `wanted`.
+/// Versions that aren't valid semver, like a legacy "latest" entry, are ignored.
+pub fn resolve_version(
+    wanted: &ModuleVersion,
+    versions: impl IntoIterator<Item = String>,
+) -> StdResult<Option<String>> {
+    let mut highest: Option<(Version, String)> = None;
+    for ver in versions {
+        let parsed = match Version::parse(&ver) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+        if !wanted.matches(&parsed)? {
+            continue;
+        }
+        if highest.as_ref().map_or(true, |(max, _)| &parsed > max) {
+            highest = Some((parsed, ver));
+        }
+    }
+    Ok(highest.map(|(_, ver)| ver))
+}
+
+/// Errors unless `new` is a higher version than `current`, modules can only be migrated forward.
+pub fn assert_version_upgrade(current: &str, new: &str) -> StdResult<()> {
+    if parse_version(new)? <= parse_version(current)? {
+        return Err(StdError::generic_err(format!(
+            "Module can only be upgraded to a higher version, current: {}, proposed: {}",
+            current, new
+        )));
+    }
+    Ok(())
+}
+
 impl From<Option<String>> for ModuleVersion {
     fn from(value: Option<String>) -> Self {
         value.map_or(ModuleVersion::Latest {}, ModuleVersion::Version)
@@ -392,4 +465,76 @@ mod test {
 
         assert_eq!(items[1], ("2.0.0".to_string(), 999));
     }
+
+    #[test]
+    fn sorts_versions() {
+        let versions = vec!["1.10.0".to_string(), "1.9.0".into(), "0.1.0".into()];
+        assert_eq!(
+            sort_versions(versions),
+            vec!["0.1.0".to_string(), "1.9.0".into(), "1.10.0".into()]
+        );
+        assert_eq!(
+            sort_versions(vec!["latest".to_string(), "0.1.0".into()]),
+            vec!["0.1.0".to_string()]
+        );
+    }
+
+    #[test]
+    fn requirement_keys_are_distinct() {
+        let mut deps = mock_dependencies();
+        let map: Map<ModuleInfo, u64> = Map::new("map");
+        let with_version = |version| ModuleInfo {
+            version,
+            ..mock_key()
+        };
+        let exact = with_version(ModuleVersion::Version("1.9.9".into()));
+        let requirement = with_version(ModuleVersion::Requirement("1.9.9".into()));
+        assert_eq!(exact.version.to_string(), "1.9.9");
+        assert_eq!(requirement.version.to_string(), "req:1.9.9");
+
+        map.save(deps.as_mut().storage, exact.clone(), &1).unwrap();
+        map.save(deps.as_mut().storage, requirement.clone(), &2)
+            .unwrap();
+        let items = map
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![(exact, 1), (requirement, 2)]);
+    }
+
+    #[test]
+    fn resolves_versions() {
+        let versions = || {
+            vec![
+                "0.1.0".to_string(),
+                "0.1.3".into(),
+                "0.2.0".into(),
+                "latest".into(),
+            ]
+        };
+        assert_eq!(
+            resolve_version(&ModuleVersion::Latest {}, versions()).unwrap(),
+            Some("0.2.0".to_string())
+        );
+        assert_eq!(
+            resolve_version(&ModuleVersion::Requirement("^0.1".into()), versions()).unwrap(),
+            Some("0.1.3".to_string())
+        );
+        assert_eq!(
+            resolve_version(&ModuleVersion::Version("0.1.0".into()), versions()).unwrap(),
+            Some("0.1.0".to_string())
+        );
+        assert_eq!(
+            resolve_version(&ModuleVersion::Requirement(">=1".into()), versions()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn only_upgrades_forward() {
+        assert_version_upgrade("0.1.0", "0.1.1").unwrap();
+        assert_version_upgrade("0.9.0", "0.10.0").unwrap();
+        assert_version_upgrade("0.1.1", "0.1.1").unwrap_err();
+        assert_version_upgrade("0.2.0", "0.1.9").unwrap_err();
+    }
 }
@@ -2,6 +2,8 @@ use cosmwasm_std::Addr;
 
 #[cosmwasm_schema::cw_serde]
 pub enum ModuleReference {
+    /// Contracts instantiated by the os factory: the manager, the proxy and the governance contracts
+    Core(u64),
     Extension(Addr),
     App(u64),
     Perk(u64),
@@ -1,4 +1,4 @@
-use cosmwasm_std::{DepsMut, Order, StdError, StdResult, Storage};
+use cosmwasm_std::{Deps, DepsMut, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map, Path};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -6,18 +6,37 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
 const MAX_MSG_LIMIT: u32 = 15;
+/// Pages of failed elements that a pagination retries before it gives up, see [`ErrorPolicy::Retry`].
+const MAX_RETRIES: u32 = 10;
 
 pub type PaginationResult<Acum, PageResult> = StdResult<(Option<Acum>, PageResult)>;
 pub type PaginationAccumulatorFunction<T, Acum, C, FuncResult> =
     fn(&[u8], &mut dyn Storage, T, &mut Acum, &C) -> StdResult<Option<FuncResult>>;
 pub type PaginationFunction<T, C, FuncResult> =
     fn(&[u8], &mut dyn Storage, T, &C) -> StdResult<Option<FuncResult>>;
+pub type PaginationQueryFunction<T, Acum, C, FuncResult> =
+    fn(&[u8], Deps, T, &mut Acum, &C) -> StdResult<Option<FuncResult>>;
 /// Allows for multi-transaction computation on a dataset. Required for large datasets due to gas constraints.
 pub struct PagedMap<'a, T, Acum> {
     /// Actual data store
     data: Map<'a, &'a [u8], T>,
     /// Pagination progress status
     pub status: Item<'a, PaginationInfo<Acum>>,
+    /// Handling of errors returned by the pagination functions
+    error_policy: ErrorPolicy,
+}
+
+/// How errors returned by a pagination function are handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// Return the error, the page is not processed.
+    Abort,
+    /// Skip the element and record its key in [`PaginationInfo::failed_keys`].
+    Skip,
+    /// Record the key and process the element again after all other elements are processed.
+    /// A pagination with an accumulator only completes once every failed element succeeded.
+    /// After [`MAX_RETRIES`] pages of retries the pagination errors until it's reset.
+    Retry,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -28,13 +47,29 @@ pub struct PaginationInfo<Acum> {
     pub last_processed_item: Option<Vec<u8>>,
     /// Accumulator item available for use in pagination function
     pub accumulator: Option<Acum>,
+    /// Keys of the elements for which the pagination function failed
+    #[serde(default)]
+    pub failed_keys: Vec<Vec<u8>>,
+    /// Pages of failed elements retried in the current pagination
+    #[serde(default)]
+    pub retries: u32,
 }
 
 impl<'a, T, Acum> PagedMap<'a, T, Acum> {
+    /// Errors of the pagination functions are returned, see [`ErrorPolicy::Abort`].
     pub const fn new(namespace: &'a str, status_namespace: &'a str) -> Self {
         PagedMap {
             data: Map::new(namespace),
             status: Item::new(status_namespace),
+            error_policy: ErrorPolicy::Abort,
+        }
+    }
+
+    pub const fn with_error_policy(self, error_policy: ErrorPolicy) -> Self {
+        PagedMap {
+            data: self.data,
+            status: self.status,
+            error_policy,
         }
     }
 
@@ -49,6 +84,8 @@ impl<'a, T, Acum> PagedMap<'a, T, Acum> {
                 is_locked: false,
                 accumulator: None,
                 last_processed_item: None,
+                failed_keys: vec![],
+                retries: 0,
             },
         )
     }
@@ -147,7 +184,7 @@ impl<'a, T, Acum> PagedMap<'a, T, Acum> {
     /// Perform some operation on a page of the map.
     /// Returns an optional result of that computation.
     /// Repeat until state unlocks to page over the whole map
-    /// Omits errors from f()
+    /// Errors from f() are handled according to the [`ErrorPolicy`] of the map.
     pub fn page_with_accumulator<C, FuncResult>(
         &self,
         deps: DepsMut,
@@ -165,53 +202,92 @@ impl<'a, T, Acum> PagedMap<'a, T, Acum> {
             status.is_locked = true;
             status.accumulator = Some(Acum::default());
             status.last_processed_item = None;
+            status.failed_keys.clear();
+            status.retries = 0;
         }
 
-        let start = status.last_processed_item.clone().map(Bound::ExclusiveRaw);
-
-        let result: Vec<(Vec<u8>, T)> = self
-            .data
-            .range(deps.storage, start, None, Order::Ascending)
-            .take(limit)
-            .collect::<StdResult<Vec<(Vec<u8>, T)>>>()?;
-
-        // If not all items processed, update last item
-        let return_accumulator = if !result.is_empty() {
-            let last_key = result.last().unwrap().0.clone();
-            status.last_processed_item = Some(last_key);
-            None
-        } else {
-            // Everything processed, set to None and return accumulator
-            let accumulator: Option<Acum> = status.accumulator.clone();
+        let (page, is_retry) = self.next_page(deps.storage, &mut status, limit)?;
+        // Everything processed when there's nothing left to process or retry
+        let is_done = page.is_empty()
+            && (self.error_policy != ErrorPolicy::Retry || status.failed_keys.is_empty());
+
+        let mut accumulator = status.accumulator.take().unwrap_or_default();
+        let function_results = self.process_page(
+            &mut status,
+            page,
+            is_retry,
+            &mut accumulator,
+            |key, element, acc| f(key, deps.storage, element, acc, context),
+        )?;
+
+        let return_accumulator = if is_done {
+            // Everything processed, unlock and return accumulator
             status.is_locked = false;
-            status.accumulator = None;
-            accumulator
+            status.last_processed_item = None;
+            Some(accumulator)
+        } else {
+            status.accumulator = Some(accumulator);
+            None
         };
+        self.status.save(deps.storage, &status)?;
 
-        let function_results = result
-            .into_iter()
-            .filter_map(|(key, element)| {
-                f(
-                    &key,
-                    deps.storage,
-                    element,
-                    status
-                        .accumulator
-                        .as_mut()
-                        .expect("accumulator contains some value"),
-                    context,
-                )
-                .ok()
-                .unwrap_or(None)
-            })
-            .collect::<Vec<FuncResult>>();
+        Ok((return_accumulator, function_results))
+    }
 
+    /// Accumulate over a page of the map with a function that can query other contracts.
+    /// Unlike [`Self::page_with_accumulator`] the accumulator is returned as soon as the last page is processed.
+    /// Errors from f() are handled according to the [`ErrorPolicy`] of the map.
+    pub fn page_query_with_accumulator<C, FuncResult>(
+        &self,
+        deps: DepsMut,
+        limit: Option<u32>,
+        context: &C,
+        f: PaginationQueryFunction<T, Acum, C, FuncResult>,
+    ) -> PaginationResult<Acum, Vec<FuncResult>>
+    where
+        T: Serialize + DeserializeOwned,
+        Acum: Serialize + DeserializeOwned + Default + Clone,
+    {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let mut status = self.status.load(deps.storage)?;
+        if !status.is_locked {
+            status.is_locked = true;
+            status.accumulator = Some(Acum::default());
+            status.last_processed_item = None;
+            status.failed_keys.clear();
+            status.retries = 0;
+        }
+
+        let (page, is_retry) = self.next_page(deps.storage, &mut status, limit)?;
+        let is_last_page = is_retry || page.len() < limit;
+
+        let mut accumulator = status.accumulator.take().unwrap_or_default();
+        let function_results = self.process_page(
+            &mut status,
+            page,
+            is_retry,
+            &mut accumulator,
+            |key, element, acc| f(key, deps.as_ref(), element, acc, context),
+        )?;
+
+        let is_done = is_last_page
+            && (self.error_policy != ErrorPolicy::Retry || status.failed_keys.is_empty());
+        let return_accumulator = if is_done {
+            // Everything processed, unlock and return accumulator
+            status.is_locked = false;
+            status.last_processed_item = None;
+            Some(accumulator)
+        } else {
+            status.accumulator = Some(accumulator);
+            None
+        };
         self.status.save(deps.storage, &status)?;
 
         Ok((return_accumulator, function_results))
     }
 
-    /// Will apply function on each element (key, value) of the map. Errors on function f() are neglected.
+    /// Will apply function on each element (key, value) of the map.
+    /// Errors from f() are handled according to the [`ErrorPolicy`] of the map.
     /// Will not lock the set as no accumulator is used so map state changes are allowed.
     pub fn page_without_accumulator<C, FuncResult>(
         &self,
@@ -226,32 +302,134 @@ impl<'a, T, Acum> PagedMap<'a, T, Acum> {
     {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_MSG_LIMIT) as usize;
         let mut status = self.status.load(deps.storage)?;
+        if status.last_processed_item.is_none() {
+            // Starting a new pass over the map
+            status.failed_keys.clear();
+            status.retries = 0;
+        }
+
+        let (page, is_retry) = self.next_page(deps.storage, &mut status, limit)?;
+        if page.is_empty() {
+            // Everything processed, start from the beginning on the next call
+            status.last_processed_item = None;
+        }
+
+        let function_results =
+            self.process_page(&mut status, page, is_retry, &mut (), |key, element, _| {
+                f(key, deps.storage, element, context)
+            })?;
+
+        self.status.save(deps.storage, &status)?;
+
+        Ok(function_results)
+    }
+
+    /// Elements that the next pagination with an accumulator will process, without changing the status.
+    /// Useful to batch the lookups the page needs.
+    pub fn peek_page(&self, store: &dyn Storage, limit: Option<u32>) -> StdResult<Vec<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        Acum: Serialize + DeserializeOwned + Default + Clone,
+    {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let mut status = self.status.load(store)?;
+        if !status.is_locked {
+            status.last_processed_item = None;
+            status.failed_keys.clear();
+            status.retries = 0;
+        }
+        let (page, _) = self.next_page(store, &mut status, limit)?;
+        Ok(page.into_iter().map(|(_, element)| element).collect())
+    }
 
+    /// Keys of the elements for which the pagination function failed in the current or last pagination.
+    pub fn failed_keys(&self, store: &dyn Storage) -> StdResult<Vec<Vec<u8>>>
+    where
+        T: Serialize + DeserializeOwned,
+        Acum: Serialize + DeserializeOwned + Default + Clone,
+    {
+        Ok(self.status.load(store)?.failed_keys)
+    }
+
+    /// Cancels the current pagination. Unlocks the map and drops the accumulator and failed keys.
+    pub fn reset(&self, store: &mut dyn Storage) -> StdResult<()>
+    where
+        T: Serialize + DeserializeOwned,
+        Acum: Serialize + DeserializeOwned + Default + Clone,
+    {
+        self.instantiate(store)
+    }
+
+    /// Loads the next page of the map.
+    /// When all elements are processed and the error policy is [`ErrorPolicy::Retry`], the page consists of
+    /// previously failed elements instead, indicated by the returned boolean.
+    fn next_page(
+        &self,
+        store: &dyn Storage,
+        status: &mut PaginationInfo<Acum>,
+        limit: usize,
+    ) -> StdResult<(Vec<(Vec<u8>, T)>, bool)>
+    where
+        T: Serialize + DeserializeOwned,
+    {
         let start = status.last_processed_item.clone().map(Bound::ExclusiveRaw);
-        let result: Vec<(Vec<u8>, T)> = self
+        let page: Vec<(Vec<u8>, T)> = self
             .data
-            .range(deps.storage, start, None, Order::Ascending)
+            .range(store, start, None, Order::Ascending)
             .take(limit)
             .collect::<StdResult<Vec<(Vec<u8>, T)>>>()?;
+        if !page.is_empty()
+            || self.error_policy != ErrorPolicy::Retry
+            || status.failed_keys.is_empty()
+        {
+            return Ok((page, false));
+        }
 
-        // If not all items processed, update last item
-        if !result.is_empty() {
-            let last_key = result.last().unwrap().0.clone();
-            status.last_processed_item = Some(last_key);
-        } else {
-            // Everything processed, unlock map
-            status.last_processed_item = None;
-        };
-
-        let function_results = result
-            .into_iter()
-            .filter_map(|(key, element)| {
-                f(&key, deps.storage, element, context).ok().unwrap_or(None)
-            })
-            .collect::<Vec<FuncResult>>();
-
-        self.status.save(deps.storage, &status)?;
+        if status.retries >= MAX_RETRIES {
+            return Err(StdError::generic_err(format!(
+                "pagination gave up after {} retries, {} elements keep failing",
+                MAX_RETRIES,
+                status.failed_keys.len()
+            )));
+        }
+        status.retries += 1;
+
+        let retry_count = limit.min(status.failed_keys.len());
+        let mut retries = vec![];
+        for key in status.failed_keys.drain(..retry_count).collect::<Vec<_>>() {
+            // Elements that were removed in the meantime don't need to be retried
+            if let Some(element) = self.data.may_load(store, key.as_slice())? {
+                retries.push((key, element));
+            }
+        }
+        Ok((retries, true))
+    }
 
+    /// Applies f to each element of the page and handles its errors according to the error policy.
+    fn process_page<A, FuncResult>(
+        &self,
+        status: &mut PaginationInfo<Acum>,
+        page: Vec<(Vec<u8>, T)>,
+        is_retry: bool,
+        accumulator: &mut A,
+        mut f: impl FnMut(&[u8], T, &mut A) -> StdResult<Option<FuncResult>>,
+    ) -> StdResult<Vec<FuncResult>> {
+        let mut function_results = vec![];
+        for (key, element) in page {
+            match f(&key, element, accumulator) {
+                Ok(Some(res)) => function_results.push(res),
+                Ok(None) => (),
+                Err(err) if self.error_policy == ErrorPolicy::Abort => return Err(err),
+                Err(_) => {
+                    if !status.failed_keys.contains(&key) {
+                        status.failed_keys.push(key.clone());
+                    }
+                }
+            }
+            if !is_retry {
+                status.last_processed_item = Some(key);
+            }
+        }
         Ok(function_results)
     }
 }
@@ -475,4 +653,189 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn page_query_with_accumulator() {
+        const USERS: PagedMap<Data, IncomeAcc> =
+            PagedMap::new("people", "status").with_error_policy(ErrorPolicy::Abort);
+
+        fn sum_balances(
+            _key: &[u8],
+            _deps: Deps,
+            value: Data,
+            acc: &mut IncomeAcc,
+            max_balance: &u32,
+        ) -> StdResult<Option<u32>> {
+            if value.balance > *max_balance {
+                return Err(StdError::generic_err("balance too high"));
+            }
+            acc.total += value.balance;
+            Ok(Some(value.balance))
+        }
+
+        let mut deps = mock_dependencies();
+        USERS.instantiate(&mut deps.storage).unwrap();
+        for i in 0..25u32 {
+            let data = Data {
+                name: "IrrelevantName".to_string(),
+                balance: i,
+            };
+            USERS
+                .save(&mut deps.storage, &i.to_be_bytes(), &data)
+                .unwrap();
+        }
+
+        // first page, map gets locked
+        let peeked = USERS.peek_page(&deps.storage, Some(10)).unwrap();
+        let (acc, balances) = USERS
+            .page_query_with_accumulator(deps.as_mut(), Some(10), &100, sum_balances)
+            .unwrap();
+        assert_eq!(acc, None);
+        assert_eq!(balances, (0..10).collect::<Vec<u32>>());
+        assert_eq!(
+            peeked.iter().map(|data| data.balance).collect::<Vec<u32>>(),
+            balances
+        );
+        assert!(USERS.status.load(&deps.storage).unwrap().is_locked);
+        let peeked = USERS.peek_page(&deps.storage, Some(10)).unwrap();
+        assert_eq!(peeked[0].balance, 10);
+
+        // failing page doesn't move the pagination
+        USERS
+            .page_query_with_accumulator(deps.as_mut(), Some(10), &15, sum_balances)
+            .unwrap_err();
+        let (acc, balances) = USERS
+            .page_query_with_accumulator(deps.as_mut(), Some(10), &100, sum_balances)
+            .unwrap();
+        assert_eq!(acc, None);
+        assert_eq!(balances, (10..20).collect::<Vec<u32>>());
+
+        // last page returns the accumulator and unlocks the map
+        let (acc, balances) = USERS
+            .page_query_with_accumulator(deps.as_mut(), Some(10), &100, sum_balances)
+            .unwrap();
+        assert_eq!(acc.unwrap().total, (0..25).sum::<u32>());
+        assert_eq!(balances, (20..25).collect::<Vec<u32>>());
+        let status = USERS.status.load(&deps.storage).unwrap();
+        assert!(!status.is_locked);
+        assert!(status.accumulator.is_none());
+    }
+
+    #[test]
+    fn error_policies() {
+        // Fails on odd balances unless the context allows them
+        fn sum_even_balances(
+            _key: &[u8],
+            _store: &mut dyn Storage,
+            value: Data,
+            acc: &mut IncomeAcc,
+            allow_odd: &bool,
+        ) -> StdResult<Option<u32>> {
+            if value.balance % 2 == 1 && !allow_odd {
+                return Err(StdError::generic_err("odd balance"));
+            }
+            acc.total += value.balance;
+            Ok(None)
+        }
+
+        const SKIPPING: PagedMap<Data, IncomeAcc> =
+            PagedMap::new("people", "status").with_error_policy(ErrorPolicy::Skip);
+        const RETRYING: PagedMap<Data, IncomeAcc> =
+            PagedMap::new("people", "status").with_error_policy(ErrorPolicy::Retry);
+
+        let mut deps = mock_dependencies();
+        SKIPPING.instantiate(&mut deps.storage).unwrap();
+        for i in 0..6u32 {
+            let data = Data {
+                name: "IrrelevantName".to_string(),
+                balance: i,
+            };
+            SKIPPING
+                .save(&mut deps.storage, &i.to_be_bytes(), &data)
+                .unwrap();
+        }
+
+        // skipped elements are recorded
+        let mut acc = None;
+        while acc.is_none() {
+            acc = SKIPPING
+                .page_with_accumulator(deps.as_mut(), Some(4), &false, sum_even_balances)
+                .unwrap()
+                .0;
+        }
+        assert_eq!(acc.unwrap().total, 2 + 4);
+        let odd_keys: Vec<Vec<u8>> = [1u32, 3, 5]
+            .iter()
+            .map(|i| i.to_be_bytes().to_vec())
+            .collect();
+        assert_eq!(SKIPPING.failed_keys(&deps.storage).unwrap(), odd_keys);
+
+        // failed elements are retried until they succeed
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &false, sum_even_balances)
+            .unwrap();
+        let (acc, _) = RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &false, sum_even_balances)
+            .unwrap();
+        assert_eq!(acc, None);
+        assert!(RETRYING.load_status(&deps.storage).unwrap().is_locked);
+        assert_eq!(RETRYING.failed_keys(&deps.storage).unwrap(), odd_keys);
+
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &true, sum_even_balances)
+            .unwrap();
+        let (acc, _) = RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &true, sum_even_balances)
+            .unwrap();
+        assert_eq!(acc.unwrap().total, (0..6).sum::<u32>());
+        assert!(RETRYING.failed_keys(&deps.storage).unwrap().is_empty());
+
+        // a locked pagination can be cancelled
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(2), &false, sum_even_balances)
+            .unwrap();
+        RETRYING
+            .save(
+                &mut deps.storage,
+                b"new",
+                &Data {
+                    name: "New".to_string(),
+                    balance: 0,
+                },
+            )
+            .unwrap_err();
+        RETRYING.reset(&mut deps.storage).unwrap();
+        let status = RETRYING.load_status(&deps.storage).unwrap();
+        assert!(!status.is_locked);
+        assert!(status.accumulator.is_none());
+        assert!(status.failed_keys.is_empty());
+
+        // retries are capped
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &false, sum_even_balances)
+            .unwrap();
+        for _ in 0..MAX_RETRIES {
+            let (acc, _) = RETRYING
+                .page_with_accumulator(deps.as_mut(), Some(10), &false, sum_even_balances)
+                .unwrap();
+            assert_eq!(acc, None);
+        }
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &true, sum_even_balances)
+            .unwrap_err();
+        RETRYING.reset(&mut deps.storage).unwrap();
+        RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &true, sum_even_balances)
+            .unwrap();
+        let (acc, _) = RETRYING
+            .page_with_accumulator(deps.as_mut(), Some(10), &true, sum_even_balances)
+            .unwrap();
+        assert_eq!(acc.unwrap().total, (0..6).sum::<u32>());
+
+        // errors are returned by default
+        USERS
+            .page_with_accumulator(deps.as_mut(), Some(10), &false, sum_even_balances)
+            .unwrap_err();
+        assert!(!USERS.load_status(&deps.storage).unwrap().is_locked);
+    }
 }
@@ -1,11 +1,11 @@
-use std::ops::Mul;
-
-use cosmwasm_std::{Decimal, Env, StdResult, Storage, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Decimal, Env, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const DEFAULT_PRECISION: u8 = 6;
+/// Values are accumulated with at most the precision of a [`Decimal`].
+const MAX_PRECISION: u8 = 18;
 
 /// Time Weighted Average (TWA) helper
 pub struct TimeWeightedAverage<'a>(Item<'a, TimeWeightedAverageData>);
@@ -21,18 +21,20 @@ impl<'a> TimeWeightedAverage<'a> {
         precision: Option<u8>,
         averaging_period: u64,
     ) -> StdResult<()> {
-        let block_time = env.block.time.seconds();
-
-        let twa = TimeWeightedAverageData {
-            cumulative_value: 0,
-            last_block_time: block_time,
-            precision: precision.unwrap_or(DEFAULT_PRECISION),
-            average_value: Decimal::zero(),
-            averaging_period,
-            last_averaging_cumulative_value: 0,
-            last_averaging_block_time: block_time,
-            last_averaging_block_height: env.block.height,
-        };
+        let twa = TimeWeightedAverageData::new(env, precision, averaging_period);
+        self.0.save(store, &twa)
+    }
+    /// Instantiate with a history of checkpoints, allowing [`Self::average_over`] queries.
+    pub fn instantiate_with_history(
+        &self,
+        store: &mut dyn Storage,
+        env: &Env,
+        precision: Option<u8>,
+        averaging_period: u64,
+        history: HistoryConfig,
+    ) -> StdResult<()> {
+        let twa = TimeWeightedAverageData::new(env, precision, averaging_period)
+            .with_history(history)?;
         self.0.save(store, &twa)
     }
     pub fn accumulate(
@@ -42,27 +44,22 @@ impl<'a> TimeWeightedAverage<'a> {
         current_value: Decimal,
     ) -> StdResult<Option<(u128, u64)>> {
         let mut twa = self.0.load(store)?;
-        let block_time = env.block.time.seconds();
-        if block_time <= twa.last_block_time {
-            return Ok(None);
+        let accumulated = twa.accumulate(env, current_value)?;
+        if accumulated.is_some() {
+            self.0.save(store, &twa)?;
         }
-
-        let time_elapsed = Uint128::from(block_time - twa.last_block_time);
-        twa.last_block_time = block_time;
-
-        if !current_value.is_zero() {
-            twa.cumulative_value = twa
-                .cumulative_value
-                .wrapping_add(time_elapsed.mul(current_value).u128());
-        };
-        self.0.save(store, &twa)?;
-        Ok(Some((twa.cumulative_value, block_time)))
+        Ok(accumulated)
     }
 
     pub fn get_value(&self, store: &dyn Storage) -> StdResult<Decimal> {
         Ok(self.0.load(store)?.average_value)
     }
 
+    /// Average over the last `window` seconds, see [`TimeWeightedAverageData::average_over`].
+    pub fn average_over(&self, store: &dyn Storage, window: u64) -> StdResult<Decimal> {
+        self.0.load(store)?.average_over(window)
+    }
+
     pub fn load(&self, store: &dyn Storage) -> StdResult<TimeWeightedAverageData> {
         self.0.load(store)
     }
@@ -74,31 +71,11 @@ impl<'a> TimeWeightedAverage<'a> {
         store: &mut dyn Storage,
     ) -> StdResult<Option<Decimal>> {
         let mut twa = self.0.load(store)?;
-
-        let block_time = env.block.time.seconds();
-
-        let time_elapsed = block_time - twa.last_averaging_block_time;
-
-        // Ensure that at least one full period has passed since the last update
-        if time_elapsed < twa.averaging_period {
-            return Ok(None);
+        let new_average_value = twa.try_update_value(env)?;
+        if new_average_value.is_some() {
+            self.0.save(store, &twa)?;
         }
-
-        // (current_cum - last_cum) / time
-        let new_average_value = Decimal::from_ratio(
-            twa.cumulative_value
-                .wrapping_sub(twa.last_averaging_cumulative_value),
-            time_elapsed,
-        );
-
-        twa = TimeWeightedAverageData {
-            average_value: new_average_value,
-            last_averaging_block_time: block_time,
-            last_averaging_cumulative_value: twa.cumulative_value,
-            ..twa
-        };
-        self.0.save(store, &twa)?;
-        Ok(Some(new_average_value))
+        Ok(new_average_value)
     }
 
     pub fn update_settings(
@@ -111,13 +88,134 @@ impl<'a> TimeWeightedAverage<'a> {
         twa.averaging_period = averaging_period;
         self.0.save(store, &twa)
     }
+
+    /// Rescales data stored before the precision was applied, to be called on contract migration.
+    /// See [`TimeWeightedAverageData::rescale_legacy`].
+    pub fn migrate(&self, store: &mut dyn Storage) -> StdResult<bool> {
+        let mut twa = self.0.load(store)?;
+        let rescaled = twa.rescale_legacy()?;
+        if rescaled {
+            self.0.save(store, &twa)?;
+        }
+        Ok(rescaled)
+    }
+}
+
+/// Time Weighted Average (TWA) helper for a set of values, e.g. a price per pool.
+pub struct TimeWeightedAverages<'a, K>(Map<'a, K, TimeWeightedAverageData>);
+
+impl<'a, K: PrimaryKey<'a>> TimeWeightedAverages<'a, K> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self(Map::new(namespace))
+    }
+
+    pub fn instantiate(
+        &self,
+        store: &mut dyn Storage,
+        env: &Env,
+        key: K,
+        precision: Option<u8>,
+        averaging_period: u64,
+    ) -> StdResult<()> {
+        let twa = TimeWeightedAverageData::new(env, precision, averaging_period);
+        self.0.save(store, key, &twa)
+    }
+
+    /// Instantiate with a history of checkpoints, allowing [`Self::average_over`] queries.
+    pub fn instantiate_with_history(
+        &self,
+        store: &mut dyn Storage,
+        env: &Env,
+        key: K,
+        precision: Option<u8>,
+        averaging_period: u64,
+        history: HistoryConfig,
+    ) -> StdResult<()> {
+        let twa = TimeWeightedAverageData::new(env, precision, averaging_period)
+            .with_history(history)?;
+        self.0.save(store, key, &twa)
+    }
+
+    pub fn has(&self, store: &dyn Storage, key: K) -> bool {
+        self.0.has(store, key)
+    }
+
+    pub fn accumulate(
+        &self,
+        env: &Env,
+        store: &mut dyn Storage,
+        key: K,
+        current_value: Decimal,
+    ) -> StdResult<Option<(u128, u64)>> {
+        let mut twa = self.0.load(store, key.clone())?;
+        let accumulated = twa.accumulate(env, current_value)?;
+        if accumulated.is_some() {
+            self.0.save(store, key, &twa)?;
+        }
+        Ok(accumulated)
+    }
+
+    /// Average value of `key`, none if no average has been computed yet.
+    pub fn may_get_value(&self, store: &dyn Storage, key: K) -> StdResult<Option<Decimal>> {
+        Ok(self
+            .0
+            .may_load(store, key)?
+            .map(|twa| twa.average_value)
+            .filter(|value| !value.is_zero()))
+    }
+
+    /// Average of `key` over the last `window` seconds,
+    /// see [`TimeWeightedAverageData::average_over`].
+    pub fn average_over(&self, store: &dyn Storage, key: K, window: u64) -> StdResult<Decimal> {
+        self.0.load(store, key)?.average_over(window)
+    }
+
+    pub fn load(&self, store: &dyn Storage, key: K) -> StdResult<TimeWeightedAverageData> {
+        self.0.load(store, key)
+    }
+
+    /// Get average value, updates when possible
+    pub fn try_update_value(
+        &self,
+        env: &Env,
+        store: &mut dyn Storage,
+        key: K,
+    ) -> StdResult<Option<Decimal>> {
+        let mut twa = self.0.load(store, key.clone())?;
+        let new_average_value = twa.try_update_value(env)?;
+        if new_average_value.is_some() {
+            self.0.save(store, key, &twa)?;
+        }
+        Ok(new_average_value)
+    }
+
+    pub fn remove(&self, store: &mut dyn Storage, key: K) {
+        self.0.remove(store, key)
+    }
+
+    /// Rescales the data of `key` if it was stored before the precision was applied,
+    /// to be called on contract migration. See [`TimeWeightedAverageData::rescale_legacy`].
+    pub fn migrate(&self, store: &mut dyn Storage, key: K) -> StdResult<bool> {
+        let mut twa = self.0.load(store, key.clone())?;
+        let rescaled = twa.rescale_legacy()?;
+        if rescaled {
+            self.0.save(store, key, &twa)?;
+        }
+        Ok(rescaled)
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct TimeWeightedAverageData {
     // settings for accumulating value data
+    /// Number of decimals of the value that are accumulated
     pub precision: u8,
+    /// Whether the cumulative values are scaled by `10^precision`.
+    /// Data stored before the precision was applied isn't, it's rescaled on its next update.
+    #[serde(default)]
+    pub scaled: bool,
     pub last_block_time: u64,
+    /// Sum of `value * 10^precision * seconds`
     pub cumulative_value: u128,
 
     // Data to get average price
@@ -127,4 +225,344 @@ pub struct TimeWeightedAverageData {
     pub averaging_period: u64,
     /// The requested average value
     average_value: Decimal,
+
+    // Rolling history of cumulative values
+    #[serde(default)]
+    pub history: Option<HistoryConfig>,
+    /// Ring buffer of checkpoints, `next_checkpoint` is the index of the oldest one once it's full.
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    #[serde(default)]
+    pub next_checkpoint: u32,
+}
+
+/// Configures the checkpoints that are kept to compute averages over different windows.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HistoryConfig {
+    /// Min amount of seconds between two checkpoints
+    pub interval: u64,
+    /// Largest window (in seconds) that can be queried
+    pub max_window: u64,
+}
+
+impl HistoryConfig {
+    /// Amount of checkpoints needed to cover the max window
+    pub fn capacity(&self) -> u32 {
+        (self.max_window / self.interval + 1) as u32
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Checkpoint {
+    pub block_time: u64,
+    pub cumulative_value: u128,
+}
+
+impl TimeWeightedAverageData {
+    pub fn new(env: &Env, precision: Option<u8>, averaging_period: u64) -> Self {
+        let block_time = env.block.time.seconds();
+        Self {
+            cumulative_value: 0,
+            last_block_time: block_time,
+            precision: precision.unwrap_or(DEFAULT_PRECISION).min(MAX_PRECISION),
+            scaled: true,
+            average_value: Decimal::zero(),
+            averaging_period,
+            last_averaging_cumulative_value: 0,
+            last_averaging_block_time: block_time,
+            last_averaging_block_height: env.block.height,
+            history: None,
+            checkpoints: vec![],
+            next_checkpoint: 0,
+        }
+    }
+
+    /// Keep a history of checkpoints, starting with the current cumulative value.
+    pub fn with_history(mut self, history: HistoryConfig) -> StdResult<Self> {
+        if history.interval == 0 || history.max_window < history.interval {
+            return Err(StdError::generic_err(
+                "history interval must be non-zero and at most the max window",
+            ));
+        }
+        self.checkpoints = vec![Checkpoint {
+            block_time: self.last_block_time,
+            cumulative_value: self.cumulative_value,
+        }];
+        self.next_checkpoint = 1 % history.capacity();
+        self.history = Some(history);
+        Ok(self)
+    }
+
+    pub fn average_value(&self) -> Decimal {
+        self.average_value
+    }
+
+    /// Adds `current_value` over the time since the last accumulation.
+    /// Returns `None` if it was already accumulated in this block.
+    pub fn accumulate(
+        &mut self,
+        env: &Env,
+        current_value: Decimal,
+    ) -> StdResult<Option<(u128, u64)>> {
+        self.rescale_legacy()?;
+        let block_time = env.block.time.seconds();
+        if block_time <= self.last_block_time {
+            return Ok(None);
+        }
+
+        let time_elapsed = Uint128::from(block_time - self.last_block_time);
+        self.last_block_time = block_time;
+
+        if !current_value.is_zero() {
+            let scaled_value = Uint128::new(self.scale()) * current_value;
+            self.cumulative_value = Uint128::new(self.cumulative_value)
+                .checked_add(time_elapsed.checked_mul(scaled_value)?)?
+                .u128();
+        };
+        self.checkpoint();
+        Ok(Some((self.cumulative_value, block_time)))
+    }
+
+    /// Updates the average value if a full averaging period has passed.
+    pub fn try_update_value(&mut self, env: &Env) -> StdResult<Option<Decimal>> {
+        self.rescale_legacy()?;
+        let block_time = env.block.time.seconds();
+
+        let time_elapsed = block_time - self.last_averaging_block_time;
+
+        // Ensure that at least one full period has passed since the last update
+        if time_elapsed < self.averaging_period {
+            return Ok(None);
+        }
+
+        // (current_cum - last_cum) / time
+        let new_average_value = self.average_between(
+            self.last_averaging_cumulative_value,
+            self.cumulative_value,
+            time_elapsed,
+        )?;
+
+        self.average_value = new_average_value;
+        self.last_averaging_block_time = block_time;
+        self.last_averaging_cumulative_value = self.cumulative_value;
+        Ok(Some(new_average_value))
+    }
+
+    /// Average over at least the last `window` seconds before the last accumulation.
+    /// Uses the most recent checkpoint that's at least `window` seconds old,
+    /// so the actual window can be up to one checkpoint interval longer.
+    pub fn average_over(&self, window: u64) -> StdResult<Decimal> {
+        let history = self
+            .history
+            .as_ref()
+            .ok_or_else(|| StdError::generic_err("no history is kept for this average"))?;
+        if window > history.max_window {
+            return Err(StdError::generic_err(format!(
+                "window of {} seconds exceeds the max window of {} seconds",
+                window, history.max_window
+            )));
+        }
+        let start = self
+            .checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.block_time + window <= self.last_block_time)
+            .max_by_key(|checkpoint| checkpoint.block_time)
+            .ok_or_else(|| {
+                StdError::generic_err(format!("not enough history for a {}s window", window))
+            })?;
+        if start.block_time == self.last_block_time {
+            return Err(StdError::generic_err("can not average over an empty window"));
+        }
+        self.average_between(
+            start.cumulative_value,
+            self.cumulative_value,
+            self.last_block_time - start.block_time,
+        )
+    }
+
+    /// Scales the cumulative values of data that was accumulated before the precision was applied.
+    /// Returns whether the data was rescaled, the average value is kept as it is.
+    pub fn rescale_legacy(&mut self) -> StdResult<bool> {
+        if self.scaled {
+            return Ok(false);
+        }
+        let scale = Uint128::new(self.scale());
+        self.cumulative_value = Uint128::new(self.cumulative_value)
+            .checked_mul(scale)?
+            .u128();
+        self.last_averaging_cumulative_value = Uint128::new(self.last_averaging_cumulative_value)
+            .checked_mul(scale)?
+            .u128();
+        self.scaled = true;
+        Ok(true)
+    }
+
+    /// Store the current cumulative value if an interval has passed since the last checkpoint.
+    fn checkpoint(&mut self) {
+        let history = match &self.history {
+            Some(history) => history,
+            None => return,
+        };
+        let capacity = history.capacity();
+        let last = (self.next_checkpoint + capacity - 1) % capacity;
+        let due = self
+            .checkpoints
+            .get(last as usize)
+            .map(|checkpoint| checkpoint.block_time + history.interval <= self.last_block_time)
+            .unwrap_or(true);
+        if !due {
+            return;
+        }
+        let checkpoint = Checkpoint {
+            block_time: self.last_block_time,
+            cumulative_value: self.cumulative_value,
+        };
+        if (self.next_checkpoint as usize) < self.checkpoints.len() {
+            self.checkpoints[self.next_checkpoint as usize] = checkpoint;
+        } else {
+            self.checkpoints.push(checkpoint);
+        }
+        self.next_checkpoint = (self.next_checkpoint + 1) % capacity;
+    }
+
+    fn average_between(&self, from: u128, to: u128, time_elapsed: u64) -> StdResult<Decimal> {
+        let accumulated = Uint128::new(to).checked_sub(Uint128::new(from))?;
+        let time_elapsed = Uint128::from(time_elapsed).checked_mul(Uint128::new(self.scale()))?;
+        Ok(Decimal::from_ratio(accumulated, time_elapsed))
+    }
+
+    fn scale(&self) -> u128 {
+        10u128.pow(self.precision.min(MAX_PRECISION) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    const TWAS: TimeWeightedAverages<&str> = TimeWeightedAverages::new("twas");
+
+    #[test]
+    fn averages_per_key() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        TWAS.instantiate(deps.as_mut().storage, &env, "a", None, 100)
+            .unwrap();
+        TWAS.instantiate(deps.as_mut().storage, &env, "b", None, 100)
+            .unwrap();
+        assert_eq!(TWAS.may_get_value(&deps.storage, "a").unwrap(), None);
+
+        env.block.time = env.block.time.plus_seconds(50);
+        TWAS.accumulate(&env, deps.as_mut().storage, "a", Decimal::percent(200))
+            .unwrap();
+        // can only accumulate once per block
+        assert_eq!(
+            TWAS.accumulate(&env, deps.as_mut().storage, "a", Decimal::percent(200))
+                .unwrap(),
+            None
+        );
+        // no full period yet
+        assert_eq!(
+            TWAS.try_update_value(&env, deps.as_mut().storage, "a")
+                .unwrap(),
+            None
+        );
+
+        env.block.time = env.block.time.plus_seconds(50);
+        TWAS.accumulate(&env, deps.as_mut().storage, "a", Decimal::percent(400))
+            .unwrap();
+        TWAS.try_update_value(&env, deps.as_mut().storage, "a")
+            .unwrap();
+        // (50 * 2 + 50 * 4) / 100
+        assert_eq!(
+            TWAS.may_get_value(&deps.storage, "a").unwrap(),
+            Some(Decimal::percent(300))
+        );
+        assert_eq!(TWAS.may_get_value(&deps.storage, "b").unwrap(), None);
+    }
+
+    #[test]
+    fn applies_precision() {
+        let env = mock_env();
+        let mut twa = TimeWeightedAverageData::new(&env, Some(3), 10);
+        let mut later = env.clone();
+        later.block.time = later.block.time.plus_seconds(10);
+        twa.accumulate(&later, Decimal::permille(1)).unwrap();
+        assert_eq!(twa.cumulative_value, 10);
+        assert_eq!(
+            twa.try_update_value(&later).unwrap(),
+            Some(Decimal::permille(1))
+        );
+    }
+
+    #[test]
+    fn rescales_legacy_data() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        TWAS.instantiate(deps.as_mut().storage, &env, "a", None, 200)
+            .unwrap();
+        // data of a previous version: value 2 accumulated for 100s without precision
+        env.block.time = env.block.time.plus_seconds(100);
+        let mut legacy = TWAS.load(&deps.storage, "a").unwrap();
+        legacy.scaled = false;
+        legacy.cumulative_value = 200;
+        legacy.last_block_time = env.block.time.seconds();
+        TWAS.0.save(deps.as_mut().storage, "a", &legacy).unwrap();
+
+        assert!(TWAS.migrate(deps.as_mut().storage, "a").unwrap());
+        assert!(!TWAS.migrate(deps.as_mut().storage, "a").unwrap());
+        assert_eq!(
+            TWAS.load(&deps.storage, "a").unwrap().cumulative_value,
+            200 * 10u128.pow(DEFAULT_PRECISION as u32)
+        );
+
+        // data that wasn't migrated is rescaled on its next update
+        TWAS.0.save(deps.as_mut().storage, "a", &legacy).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        TWAS.accumulate(&env, deps.as_mut().storage, "a", Decimal::percent(400))
+            .unwrap();
+        // (100 * 2 + 100 * 4) / 200
+        assert_eq!(
+            TWAS.try_update_value(&env, deps.as_mut().storage, "a")
+                .unwrap(),
+            Some(Decimal::percent(300))
+        );
+    }
+
+    #[test]
+    fn checked_overflow() {
+        let env = mock_env();
+        let mut twa = TimeWeightedAverageData::new(&env, Some(18), 10);
+        let mut later = env.clone();
+        later.block.time = later.block.time.plus_seconds(10);
+        twa.accumulate(&later, Decimal::MAX).unwrap_err();
+    }
+
+    #[test]
+    fn averages_over_windows() {
+        let mut env = mock_env();
+        let history = HistoryConfig {
+            interval: 60,
+            max_window: 300,
+        };
+        let mut twa = TimeWeightedAverageData::new(&env, None, 60)
+            .with_history(history)
+            .unwrap();
+
+        // value is 1 for 5 minutes, then 3 for 1 minute
+        for _ in 0..5 {
+            env.block.time = env.block.time.plus_seconds(60);
+            twa.accumulate(&env, Decimal::one()).unwrap();
+        }
+        env.block.time = env.block.time.plus_seconds(60);
+        twa.accumulate(&env, Decimal::percent(300)).unwrap();
+
+        // the first checkpoint was overwritten
+        assert_eq!(twa.checkpoints.len(), 6);
+        assert_eq!(twa.average_over(60).unwrap(), Decimal::percent(300));
+        assert_eq!(twa.average_over(120).unwrap(), Decimal::percent(200));
+        assert_eq!(twa.average_over(300).unwrap(), Decimal::percent(140));
+        twa.average_over(360).unwrap_err();
+    }
 }
@@ -7,7 +7,16 @@
 //! ## Create a new OS
 //! Call [`ExecuteMsg::CreateOs`] on this contract along with a [`crate::objects::gov_type`] and name you'd like to display on your OS.
 //!
+//! ## Templates
+//! When a template name is provided, the [`crate::module_factory::OsTemplate`] stored on the module factory is applied in the same transaction.
+//! The factory is root of the OS while it installs the modules, adds the proxy assets and grants the traders, root is handed over afterwards.
+//!
+//! ## Governance
+//! [`crate::objects::gov_type::GovernanceDetails::Multisig`] and [`crate::objects::gov_type::GovernanceDetails::Dao`] OSs are controlled by a cw3 multisig that the factory instantiates
+//! from the code-ids registered on version control. The multisig becomes root once the OS is set up.
+//!
 pub mod state {
+    use crate::{module_factory::OsTemplate, objects::gov_type::GovernanceDetails};
     use cosmwasm_std::Addr;
     use cw_controllers::Admin;
     use cw_storage_plus::Item;
@@ -28,9 +37,23 @@ pub mod state {
         pub os_manager_address: Addr,
     }
 
+    /// Setup of the OS that's being created while the factory is its root
+    #[derive(Serialize, Deserialize, Clone, Debug, Default)]
+    pub struct SetupContext {
+        /// Root the OS is handed over to once it's set up
+        pub root: Option<Addr>,
+        /// Template that's applied to the OS
+        pub template: Option<OsTemplate>,
+        /// Governance that's instantiated once its group exists
+        pub governance: Option<GovernanceDetails>,
+        /// Group of the governance, its admin is handed over to the governance multisig
+        pub group: Option<Addr>,
+    }
+
     pub const ADMIN: Admin = Admin::new("admin");
     pub const CONFIG: Item<Config> = Item::new("\u{0}{5}config");
     pub const CONTEXT: Item<Context> = Item::new("\u{0}{6}context");
+    pub const SETUP_CONTEXT: Item<SetupContext> = Item::new("setup_context");
 }
 
 use crate::objects::gov_type::GovernanceDetails;
@@ -69,13 +92,14 @@ pub enum ExecuteMsg {
     /// Creates the core contracts and sets the permissions.
     /// [`crate::manager`] and [`crate::proxy`]
     CreateOs {
-        /// Governance details
-        /// Use [`crate::objects::GovernanceDetails::Monarchy`] to use a custom governance modal.
-        /// TODO: add support for other types of gov.
+        /// Governance details, recorded as the governance type of the OS.
+        /// Multisig governance contracts are instantiated by the factory.
         governance: GovernanceDetails,
         name: String,
         description: Option<String>,
         link: Option<String>,
+        /// Name of the [`crate::module_factory::OsTemplate`] to set up the OS with
+        template: Option<String>,
     },
 }
 
@@ -9,6 +9,16 @@
 //! ## Proxy assets
 //! [Proxy assets](crate::objects::proxy_asset) are what allow the proxy contract to provide value queries for its assets. It needs to be configured using the [`ExecuteMsg::UpdateAssets`] endpoint.
 //! After configuring the proxy assets [`QueryMsg::TotalValue`] can be called to get the total holding value.
+//!
+//! ## Limits
+//! The amount of proxy assets and whitelisted modules is capped by the [`state::ProxyLimits`] of the OS.
+//! They can be changed by the OS root through the manager with [`ExecuteMsg::UpdateLimits`].
+//!
+//! ## NAV snapshots
+//! Large baskets can be valued over multiple transactions with [`ExecuteMsg::ComputeNav`].
+//! The resulting [`state::NavSnapshot`] is returned by [`QueryMsg::LatestNav`] and can be used to price deposits and withdrawals.
+//! Modules can't execute actions while a computation is in progress, so the pages of a snapshot see the same balances even when they span blocks.
+//! Consumers should still reject old snapshots, see [`state::NavSnapshot::check_consistent`].
 
 use cosmwasm_schema::QueryResponses;
 
@@ -18,24 +28,101 @@ use crate::objects::{
     proxy_asset::{ProxyAsset, UncheckedProxyAsset},
     AssetEntry,
 };
+use state::{NavSnapshot, ProxyLimits};
 
 pub mod state {
     pub use crate::objects::core::OS_ID;
     use cw_controllers::Admin;
 
-    use cosmwasm_std::Addr;
+    use cosmwasm_std::{Addr, BlockInfo, Decimal, StdError, StdResult, Uint128};
     use cw_storage_plus::{Item, Map};
 
-    use crate::objects::{asset_entry::AssetEntry, memory::Memory, proxy_asset::ProxyAsset};
+    use crate::objects::{
+        asset_entry::AssetEntry,
+        contract_entry::ContractEntry,
+        memory::Memory,
+        paged_map::{ErrorPolicy, PagedMap},
+        proxy_asset::ProxyAsset,
+        time_weighted_average::TimeWeightedAverages,
+    };
     #[cosmwasm_schema::cw_serde]
     pub struct State {
         pub modules: Vec<Addr>,
     }
 
+    /// Caps on the proxy configuration to prevent running out of gas when doing lookups
+    #[cosmwasm_schema::cw_serde]
+    pub struct ProxyLimits {
+        pub max_assets: u32,
+        pub max_modules: u32,
+    }
+
+    impl Default for ProxyLimits {
+        fn default() -> Self {
+            Self {
+                max_assets: 15,
+                max_modules: 15,
+            }
+        }
+    }
+
+    /// Value of the proxy assets so far in a NAV computation
+    #[cosmwasm_schema::cw_serde]
+    #[derive(Default)]
+    pub struct NavAccumulator {
+        pub value: Uint128,
+        pub per_asset: Vec<(AssetEntry, Uint128)>,
+        /// Block in which the first page was processed
+        pub start_block: Option<BlockInfo>,
+    }
+
+    /// Net asset value of the proxy as computed by the last completed [`super::ExecuteMsg::ComputeNav`] round
+    #[cosmwasm_schema::cw_serde]
+    pub struct NavSnapshot {
+        /// Total value, denominated in the base asset
+        pub value: Uint128,
+        /// Value of each proxy asset
+        pub per_asset: Vec<(AssetEntry, Uint128)>,
+        /// Block in which the first page was processed
+        pub start_block: BlockInfo,
+        /// Block in which the last page was processed
+        pub block: BlockInfo,
+    }
+
+    impl NavSnapshot {
+        /// Errors if the first page of the snapshot was valued more than `max_age` seconds before `now`.
+        pub fn check_consistent(&self, now: &BlockInfo, max_age: u64) -> StdResult<()> {
+            if self.start_block.time.plus_seconds(max_age) < now.time {
+                return Err(StdError::generic_err(format!(
+                    "NAV snapshot started in block {} is older than {} seconds",
+                    self.start_block.height, max_age
+                )));
+            }
+            Ok(())
+        }
+    }
+
     pub const MEMORY: Item<Memory> = Item::new("\u{0}{6}memory");
     pub const STATE: Item<State> = Item::new("\u{0}{5}state");
     pub const ADMIN: Admin = Admin::new("admin");
     pub const VAULT_ASSETS: Map<AssetEntry, ProxyAsset> = Map::new("proxy_assets");
+    /// Paged view on [`VAULT_ASSETS`], used to compute the NAV over multiple transactions.
+    /// Assets that can't be valued abort the computation.
+    pub const VAULT_VALUE: PagedMap<ProxyAsset, NavAccumulator> =
+        PagedMap::new("proxy_assets", "vault_value_status").with_error_policy(ErrorPolicy::Abort);
+    pub const LATEST_NAV: Item<NavSnapshot> = Item::new("latest_nav");
+    /// Limits of the OS, [`ProxyLimits::default`] if not set.
+    pub const LIMITS: Item<ProxyLimits> = Item::new("limits");
+    /// Time weighted average spot price of the pools that are used to value assets.
+    /// The price is that of the first asset of the pair, denominated in the second asset.
+    pub const POOL_TWAS: TimeWeightedAverages<ContractEntry> =
+        TimeWeightedAverages::new("pool_twas");
+    /// Spot price of each pool at its last observation, accumulated on the next observation.
+    pub const POOL_SPOT_PRICES: Map<ContractEntry, Decimal> = Map::new("pool_spot_prices");
+    /// Averaging period of the pool TWAs in seconds
+    pub const POOL_TWA_PERIOD: u64 = 60 * 30;
+    /// Max relative change of the spot price of a pool between two observations
+    pub const POOL_PRICE_MAX_DEVIATION: Decimal = Decimal::percent(10);
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -60,6 +147,19 @@ pub enum ExecuteMsg {
         to_add: Vec<UncheckedProxyAsset>,
         to_remove: Vec<String>,
     },
+    /// Accumulates the spot price of every pool that's used to value an asset.
+    /// Can be called by the admin and whitelisted modules. Pools that can't be priced are skipped.
+    UpdatePoolPrices {},
+    /// Updates the asset and module limits, can't be set below the current amount.
+    UpdateLimits {
+        max_assets: Option<u32>,
+        max_modules: Option<u32>,
+    },
+    /// Values the next `limit` proxy assets. A [`state::NavSnapshot`] is stored when the last asset is processed.
+    /// Can be called by the admin and whitelisted modules. Assets can't be updated while a computation is in progress.
+    ComputeNav { limit: Option<u32> },
+    /// Cancels the NAV computation that is in progress, unlocking the assets.
+    CancelNav {},
 }
 #[cosmwasm_schema::cw_serde]
 pub struct MigrateMsg {}
@@ -98,6 +198,13 @@ pub enum QueryMsg {
     /// Returns [`BaseAssetResponse`]
     #[returns(BaseAssetResponse)]
     BaseAsset {},
+    /// Returns [`LimitsResponse`]
+    #[returns(LimitsResponse)]
+    Limits {},
+    /// Returns the last completed NAV snapshot
+    /// [`LatestNavResponse`]
+    #[returns(LatestNavResponse)]
+    LatestNav {},
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -110,6 +217,20 @@ pub struct TotalValueResponse {
     pub value: Uint128,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct LatestNavResponse {
+    pub nav: Option<NavSnapshot>,
+    /// A new snapshot is being computed
+    pub in_progress: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LimitsResponse {
+    pub limits: ProxyLimits,
+    pub assets: u32,
+    pub modules: u32,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct HoldingValueResponse {
     pub value: Uint128,
@@ -18,6 +18,9 @@ pub const SUBSCRIPTION: &str = "abstract:subscription";
 pub const EXCHANGE: &str = "abstract:dex";
 pub const TENDERMINT_STAKING: &str = "abstract:tendermint_staking";
 pub const CW20_VESTING: &str = "abstract:cw20_vesting";
+pub const CW3_FIXED_MULTISIG: &str = "cw-plus:cw3_fixed_multisig";
+pub const CW3_FLEX_MULTISIG: &str = "cw-plus:cw3_flex_multisig";
+pub const CW4_GROUP: &str = "cw-plus:cw4_group";
 
 /// Useful when deploying version control
 #[allow(unused)]
@@ -26,3 +29,5 @@ pub static NATIVE_CONTRACTS: &[&str] =
 pub static API_CONTRACTS: &[&str] = &[EXCHANGE, TENDERMINT_STAKING];
 pub static APPS: &[&str] = &[ETF];
 pub static CORE: &[&str] = &[MANAGER, PROXY];
+/// Governance contracts the os factory instantiates as root of an OS
+pub static GOVERNANCE: &[&str] = &[CW3_FIXED_MULTISIG, CW3_FLEX_MULTISIG, CW4_GROUP];
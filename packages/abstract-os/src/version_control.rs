@@ -5,14 +5,22 @@
 //! ## Description
 //! Code-ids and api-contract addresses are stored on this address. This data can not be changed and allows for complex factory logic.
 //! Both code-ids and addresses are stored on a per-module version basis which allows users to easily upgrade their modules.
+//! Modules are registered with an exact semver version. [`ModuleVersion::Latest`](crate::objects::module::ModuleVersion::Latest)
+//! and requirement ranges are resolved to the highest matching registered version.
 //!
 //! An internal os-id store provides external verification for manager and proxy addresses.  
+//!
+//! ## Dependencies
+//! The [`Dependency`]s of each module version are stored here as well.
+//! The manager reads them to install the dependencies of a module first and to prevent removing modules that others depend on.
 
 pub mod state {
     use cw_controllers::Admin;
     use cw_storage_plus::Map;
 
-    use crate::objects::{module::ModuleInfo, module_reference::ModuleReference};
+    use crate::objects::{
+        dependency::Dependency, module::ModuleInfo, module_reference::ModuleReference,
+    };
 
     use super::Core;
 
@@ -20,7 +28,10 @@ pub mod state {
     pub const FACTORY: Admin = Admin::new("factory");
 
     // We can iterate over the map giving just the prefix to get all the versions
+    // Versions are exact semver versions, "latest" is not stored as a key.
     pub const MODULE_LIBRARY: Map<ModuleInfo, ModuleReference> = Map::new("module_lib");
+    /// Declared dependencies of a module version, modules without an entry have no dependencies
+    pub const MODULE_DEPENDENCIES: Map<ModuleInfo, Vec<Dependency>> = Map::new("module_deps");
     /// Maps OS ID to the address of its core contracts
     pub const OS_ADDRESSES: Map<u32, Core> = Map::new("os_core");
 }
@@ -29,6 +40,7 @@ use cosmwasm_schema::QueryResponses;
 use cosmwasm_std::Addr;
 
 use crate::objects::{
+    dependency::Dependency,
     module::{Module, ModuleInfo},
     module_reference::ModuleReference,
 };
@@ -51,6 +63,11 @@ pub enum ExecuteMsg {
     AddModules {
         modules: Vec<(ModuleInfo, ModuleReference)>,
     },
+    /// Set the dependencies of a registered module version
+    SetModuleDependencies {
+        module: ModuleInfo,
+        dependencies: Vec<Dependency>,
+    },
     /// Add a new OS to the deployed OSs.  
     /// Only Factory can call this
     AddOs { os_id: u32, core: Core },
@@ -67,10 +84,18 @@ pub enum QueryMsg {
     /// Returns [`OsCoreResponse`]
     #[returns(OsCoreResponse)]
     OsCore { os_id: u32 },
-    /// Queries api addresses
+    /// Queries api addresses, resolving the version of the module
     /// Returns [`ModuleResponse`]
     #[returns(ModuleResponse)]
     Module { module: ModuleInfo },
+    /// Lists all registered versions of a module in ascending semver order
+    /// Returns [`ModuleVersionsResponse`]
+    #[returns(ModuleVersionsResponse)]
+    ModuleVersions { provider: String, name: String },
+    /// Queries the dependencies of a module
+    /// Returns [`ModuleDependenciesResponse`]
+    #[returns(ModuleDependenciesResponse)]
+    ModuleDependencies { module: ModuleInfo },
     /// Returns [`ConfigResponse`]
     #[returns(ConfigResponse)]
     Config {},
@@ -92,6 +117,16 @@ pub struct ModuleResponse {
     pub module: Module,
 }
 
+#[cosmwasm_schema::cw_serde]
+pub struct ModuleVersionsResponse {
+    pub versions: Vec<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ModuleDependenciesResponse {
+    pub dependencies: Vec<Dependency>,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct ModulesResponse {
     pub modules: Vec<(ModuleInfo, ModuleReference)>,
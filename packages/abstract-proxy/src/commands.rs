@@ -0,0 +1,255 @@
+use abstract_os::objects::proxy_asset::ProxyAsset;
+use abstract_os::{
+    objects::{proxy_asset::UncheckedProxyAsset, AssetEntry},
+    proxy::state::{
+        NavAccumulator, NavSnapshot, ADMIN, LATEST_NAV, LIMITS, MEMORY, STATE, VAULT_ASSETS,
+        VAULT_VALUE,
+    },
+};
+use abstract_sdk::{manager::query_os_status, ResolveCache};
+use cosmwasm_std::{
+    CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult, Uint128,
+};
+
+use crate::{
+    error::ProxyError, prices::PriceSource, queries::query_proxy_asset_validity,
+    valuation::Valuation, ProxyResult,
+};
+
+/// Executes actions forwarded by whitelisted contracts, unless the OS is suspended by its manager.
+/// Actions are blocked during a NAV computation as they can change the balances that are being valued.
+/// This contracts acts as a proxy contract for the dApps
+pub fn execute_action(deps: DepsMut, msg_info: MessageInfo, msgs: Vec<CosmosMsg>) -> ProxyResult {
+    let state = STATE.load(deps.storage)?;
+    if !state
+        .modules
+        .contains(&deps.api.addr_validate(msg_info.sender.as_str())?)
+    {
+        return Err(ProxyError::SenderNotWhitelisted {});
+    }
+    if VAULT_VALUE.status.load(deps.storage)?.is_locked {
+        return Err(ProxyError::ActionDuringValuation);
+    }
+    if let Some(manager) = ADMIN.get(deps.as_ref())? {
+        if !query_os_status(&deps.querier, &manager)? {
+            return Err(ProxyError::OsSuspended {});
+        }
+    }
+
+    Ok(Response::new().add_messages(msgs))
+}
+
+/// Update the stored vault asset information
+pub fn update_assets(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    to_add: Vec<UncheckedProxyAsset>,
+    to_remove: Vec<String>,
+) -> ProxyResult {
+    // Only Admin can call this method
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    // The assets are iterated over by the NAV computation
+    if VAULT_VALUE.status.load(deps.storage)?.is_locked {
+        return Err(ProxyError::ValuationInProgress);
+    }
+    let memory = &MEMORY.load(deps.storage)?;
+    // Check the vault size to be within the size limit to prevent running out of gas when doing lookups
+    let current_vault_size = VAULT_ASSETS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    let limits = LIMITS.may_load(deps.storage)?.unwrap_or_default();
+    let delta: i128 = to_add.len() as i128 - to_remove.len() as i128;
+    if current_vault_size as i128 + delta > limits.max_assets as i128 {
+        return Err(ProxyError::AssetsLimitReached {});
+    }
+
+    for new_asset in to_add.into_iter() {
+        let checked_asset = new_asset.check(deps.as_ref(), memory)?;
+        VAULT_ASSETS.save(deps.storage, checked_asset.asset.clone(), &checked_asset)?;
+    }
+
+    for asset_id in to_remove {
+        VAULT_ASSETS.remove(deps.storage, asset_id.into());
+    }
+
+    // Check validity of new configuration
+    let validity_result = query_proxy_asset_validity(deps.as_ref())?;
+    if validity_result.missing_dependencies.is_some()
+        || validity_result.unresolvable_assets.is_some()
+    {
+        return Err(ProxyError::BadUpdate(format!("{:?}", validity_result)));
+    }
+
+    Ok(Response::new().add_attribute("action", "update_proxy_assets"))
+}
+
+/// Add a contract to the whitelist
+pub fn add_module(deps: DepsMut, msg_info: MessageInfo, module: String) -> ProxyResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    if state.modules.contains(&deps.api.addr_validate(&module)?) {
+        return Err(ProxyError::AlreadyInList {});
+    }
+
+    // This is a limit to prevent potentially running out of gas when doing lookups on the modules list
+    let limits = LIMITS.may_load(deps.storage)?.unwrap_or_default();
+    if state.modules.len() >= limits.max_modules as usize {
+        return Err(ProxyError::ModuleLimitReached {});
+    }
+
+    // Add contract to whitelist.
+    state.modules.push(deps.api.addr_validate(&module)?);
+    STATE.save(deps.storage, &state)?;
+
+    // Respond and note the change
+    Ok(Response::new().add_attribute("Added contract to whitelist: ", module))
+}
+
+/// Remove a contract from the whitelist
+pub fn remove_module(deps: DepsMut, msg_info: MessageInfo, module: String) -> ProxyResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    if !state.modules.contains(&deps.api.addr_validate(&module)?) {
+        return Err(ProxyError::NotInList {});
+    }
+
+    // Remove contract from whitelist.
+    let module_address = deps.api.addr_validate(&module)?;
+    state.modules.retain(|addr| *addr != module_address);
+    STATE.save(deps.storage, &state)?;
+
+    // Respond and note the change
+    Ok(Response::new().add_attribute("Removed contract from whitelist: ", module))
+}
+
+/// Update the asset and module limits of the proxy
+pub fn update_limits(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    max_assets: Option<u32>,
+    max_modules: Option<u32>,
+) -> ProxyResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let mut limits = LIMITS.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(max_assets) = max_assets {
+        let current = VAULT_ASSETS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count() as u32;
+        if max_assets < current {
+            return Err(ProxyError::LimitBelowCurrent {
+                kind: "assets".into(),
+                current,
+            });
+        }
+        limits.max_assets = max_assets;
+    }
+    if let Some(max_modules) = max_modules {
+        let current = STATE.load(deps.storage)?.modules.len() as u32;
+        if max_modules < current {
+            return Err(ProxyError::LimitBelowCurrent {
+                kind: "modules".into(),
+                current,
+            });
+        }
+        limits.max_modules = max_modules;
+    }
+    LIMITS.save(deps.storage, &limits)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_limits")
+        .add_attribute("max_assets", limits.max_assets.to_string())
+        .add_attribute("max_modules", limits.max_modules.to_string()))
+}
+
+/// Value the next page of proxy assets, stores a NAV snapshot when all assets are processed.
+/// Only the admin and whitelisted modules can compute the NAV, as the assets are locked while it's in progress.
+pub fn compute_nav(
+    mut deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    limit: Option<u32>,
+    prices: &dyn PriceSource,
+) -> ProxyResult {
+    let state = STATE.load(deps.storage)?;
+    if !ADMIN.is_admin(deps.as_ref(), &msg_info.sender)?
+        && !state.modules.contains(&msg_info.sender)
+    {
+        return Err(ProxyError::SenderNotWhitelisted {});
+    }
+    let memory = MEMORY.load(deps.storage)?;
+    // Resolve the assets of the page in one batch
+    let cache = ResolveCache::new();
+    let page = VAULT_VALUE.peek_page(deps.storage, limit)?;
+    cache.prefetch_assets(
+        deps.as_ref(),
+        &memory,
+        page.into_iter().map(|asset| asset.asset),
+    )?;
+    let valuation = Valuation {
+        memory: &memory,
+        env: &env,
+        cache: &cache,
+        prices,
+    };
+    let (nav, valued) =
+        VAULT_VALUE.page_query_with_accumulator(deps.branch(), limit, &valuation, value_asset)?;
+
+    let mut response = Response::new().add_attribute("action", "compute_nav");
+    for (asset, value) in valued {
+        response = response.add_attribute(asset.to_string(), value.to_string());
+    }
+    if let Some(NavAccumulator {
+        value,
+        per_asset,
+        start_block,
+    }) = nav
+    {
+        LATEST_NAV.save(
+            deps.storage,
+            &NavSnapshot {
+                value,
+                per_asset,
+                // no page was processed when there are no assets
+                start_block: start_block.unwrap_or_else(|| env.block.clone()),
+                block: env.block,
+            },
+        )?;
+        response = response.add_attribute("nav", value.to_string());
+    }
+    Ok(response)
+}
+
+/// Cancel the NAV computation, the last snapshot is kept
+pub fn cancel_nav(deps: DepsMut, msg_info: MessageInfo) -> ProxyResult {
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    VAULT_VALUE.reset(deps.storage)?;
+    Ok(Response::new().add_attribute("action", "cancel_nav"))
+}
+
+/// Adds the value of a proxy asset to the NAV
+fn value_asset(
+    _key: &[u8],
+    deps: Deps,
+    asset: ProxyAsset,
+    nav: &mut NavAccumulator,
+    valuation: &Valuation,
+) -> StdResult<Option<(AssetEntry, Uint128)>> {
+    let value = valuation.value(deps, &asset, None)?;
+    nav.start_block
+        .get_or_insert_with(|| valuation.env.block.clone());
+    nav.value = nav.value.checked_add(value)?;
+    nav.per_asset.push((asset.asset.clone(), value));
+    Ok(Some((asset.asset, value)))
+}
+
+pub fn set_admin(deps: DepsMut, info: MessageInfo, admin: String) -> ProxyResult {
+    let admin_addr = deps.api.addr_validate(&admin)?;
+    let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
+    ADMIN.execute_update_admin::<Empty, Empty>(deps, info, Some(admin_addr))?;
+    Ok(Response::default()
+        .add_attribute("previous admin", previous_admin)
+        .add_attribute("admin", admin))
+}
@@ -0,0 +1,57 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProxyError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Admin(#[from] ::cw_controllers::AdminError),
+
+    #[error(transparent)]
+    SemVer(#[from] ::semver::Error),
+
+    #[error("DApp is already whitelisted")]
+    AlreadyInList {},
+
+    #[error("DApp not found in whitelist")]
+    NotInList {},
+
+    #[error("Sender is not whitelisted")]
+    SenderNotWhitelisted {},
+
+    #[error("OS is suspended, modules can't execute actions")]
+    OsSuspended {},
+
+    #[error("Max amount of assets registered")]
+    AssetsLimitReached,
+
+    #[error("Max amount of modules registered")]
+    ModuleLimitReached,
+
+    #[error("Limit can't be lower than the {current} registered {kind}")]
+    LimitBelowCurrent { kind: String, current: u32 },
+
+    #[error("ICA responses are only accepted from the IBC acknowledgement of the proxy")]
+    UnauthorizedIcaResponse {},
+
+    #[error("Assets can't be updated while the total value is being computed")]
+    ValuationInProgress,
+
+    #[error("Modules can't execute actions while the total value is being computed")]
+    ActionDuringValuation,
+
+    #[error("The proposed update resulted in a bad configuration: {0}")]
+    BadUpdate(String),
+
+    #[error(
+        "Treasury balance too low, {} requested but it only has {}",
+        requested,
+        balance
+    )]
+    Broke {
+        balance: Uint128,
+        requested: Uint128,
+    },
+}
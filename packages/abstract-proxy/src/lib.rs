@@ -0,0 +1,20 @@
+//! # Abstract Proxy
+//!
+//! Implementation of the [`abstract_os::proxy`] messages that is shared by the proxy contracts.
+//!
+//! ## Description
+//! The local proxy holds its assets on the chain of the OS, the IBC proxy holds them on a remote chain.
+//! Both whitelist modules and value their proxy assets the same way, they only differ in where pool prices come from.
+//! Each proxy provides its own [`PriceSource`] to the valuation.
+use cosmwasm_std::Response;
+
+pub use error::ProxyError;
+pub use prices::{LocalPrices, PriceSource};
+
+pub mod commands;
+pub mod error;
+pub mod prices;
+pub mod queries;
+pub mod valuation;
+
+pub type ProxyResult = Result<Response, ProxyError>;
@@ -0,0 +1,140 @@
+//! # Pool prices
+//! Proxy assets that are valued through a pool use the price of that pool.
+//! Where that price comes from depends on the proxy, see [`PriceSource`].
+
+use abstract_os::{
+    api::QueryMsg as ApiQuery,
+    dex::{ApiQueryMsg as DexQueryMsg, SpotPriceResponse},
+    manager::state::OS_MODULES,
+    objects::{memory::Memory, proxy_asset::get_pair_asset_names, AssetEntry, ContractEntry},
+    proxy::state::{ADMIN, POOL_PRICE_MAX_DEVIATION, POOL_SPOT_PRICES, POOL_TWAS, POOL_TWA_PERIOD},
+    EXCHANGE,
+};
+use cosmwasm_std::{Addr, Decimal, Deps, DepsMut, Env, StdError, StdResult};
+
+/// Provides the price of the pools that are used to value proxy assets.
+pub trait PriceSource {
+    /// Price of the first asset of `pair`, denominated in the second asset.
+    fn pair_price(&self, deps: Deps, memory: &Memory, pair: &ContractEntry) -> StdResult<Decimal>;
+}
+
+/// Prices of pools on the chain of the proxy.
+/// Uses the time weighted average price of the pool, pools that haven't been observed for a full period can't be priced.
+pub struct LocalPrices;
+
+impl PriceSource for LocalPrices {
+    fn pair_price(&self, deps: Deps, _memory: &Memory, pair: &ContractEntry) -> StdResult<Decimal> {
+        POOL_TWAS
+            .may_get_value(deps.storage, pair.clone())?
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "pool {} has not been observed for a full period",
+                    pair
+                ))
+            })
+    }
+}
+
+/// Price of `offer_asset` in `ask_asset` on `pair`.
+/// Uses the same price source as the dex API when it's installed on the OS,
+/// the balances of the pair are used when the API isn't installed.
+pub fn pool_spot_price(
+    deps: Deps,
+    memory: &Memory,
+    pair: &ContractEntry,
+    offer_asset: &AssetEntry,
+    ask_asset: &AssetEntry,
+) -> StdResult<Decimal> {
+    if let Some(dex_api) = dex_api_address(deps)? {
+        let response: SpotPriceResponse = deps.querier.query_wasm_smart(
+            dex_api,
+            &ApiQuery::Api(DexQueryMsg::SpotPrice {
+                offer_asset: offer_asset.clone(),
+                ask_asset: ask_asset.clone(),
+                dex: Some(pair.protocol.clone()),
+            }),
+        )?;
+        return Ok(response.price);
+    }
+    let pair_address = memory.query_contract(deps, pair)?;
+    let offer_asset_info = memory.query_asset(deps, offer_asset)?;
+    let ask_asset_info = memory.query_asset(deps, ask_asset)?;
+
+    // query assets held in pool, gives price
+    let pool_info = (
+        ask_asset_info.query_balance(&deps.querier, &pair_address)?,
+        offer_asset_info.query_balance(&deps.querier, pair_address)?,
+    );
+    if pool_info.1.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "pool {} has no liquidity",
+            pair
+        )));
+    }
+    Ok(Decimal::from_ratio(pool_info.0.u128(), pool_info.1.u128()))
+}
+
+/// Accumulates the spot price of `pair` into its TWA.
+/// The spot price of the previous observation is used for the time since that observation,
+/// so moving the price right before an observation doesn't change the average up to that point.
+/// The pool is observed at most once per block and the observed price moves at most
+/// [`POOL_PRICE_MAX_DEVIATION`] from the previous observation, limiting the weight of a manipulated spot price.
+pub fn observe_pool_price(
+    deps: DepsMut,
+    env: &Env,
+    memory: &Memory,
+    pair: ContractEntry,
+) -> StdResult<Decimal> {
+    let (first, second) = pair_assets(&pair)?;
+    let spot_price = pool_spot_price(deps.as_ref(), memory, &pair, &first, &second)?;
+
+    let observed_price = match POOL_SPOT_PRICES.may_load(deps.storage, pair.clone())? {
+        Some(last_price) if POOL_TWAS.has(deps.storage, pair.clone()) => {
+            if POOL_TWAS
+                .accumulate(env, deps.storage, pair.clone(), last_price)?
+                .is_none()
+            {
+                // already observed in this block
+                return Ok(last_price);
+            }
+            POOL_TWAS.try_update_value(env, deps.storage, pair.clone())?;
+            cap_deviation(last_price, spot_price)
+        }
+        _ => {
+            POOL_TWAS.instantiate(deps.storage, env, pair.clone(), None, POOL_TWA_PERIOD)?;
+            spot_price
+        }
+    };
+    POOL_SPOT_PRICES.save(deps.storage, pair, &observed_price)?;
+    Ok(observed_price)
+}
+
+/// `price`, moved to at most [`POOL_PRICE_MAX_DEVIATION`] away from `last_price`.
+fn cap_deviation(last_price: Decimal, price: Decimal) -> Decimal {
+    let max_move = last_price * POOL_PRICE_MAX_DEVIATION;
+    if price > last_price + max_move {
+        last_price + max_move
+    } else if price + max_move < last_price {
+        last_price - max_move
+    } else {
+        price
+    }
+}
+
+/// Address of the dex API if it's installed on the OS that owns this proxy.
+pub fn dex_api_address(deps: Deps) -> StdResult<Option<Addr>> {
+    match ADMIN.get(deps)? {
+        Some(manager) => OS_MODULES.query(&deps.querier, manager, EXCHANGE),
+        None => Ok(None),
+    }
+}
+
+fn pair_assets(pair: &ContractEntry) -> StdResult<(AssetEntry, AssetEntry)> {
+    match get_pair_asset_names(&pair.contract)[..] {
+        [first, second] => Ok((first.into(), second.into())),
+        _ => Err(StdError::generic_err(format!(
+            "pair contract {} must be composed of two assets.",
+            pair
+        ))),
+    }
+}
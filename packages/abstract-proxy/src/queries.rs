@@ -1,16 +1,26 @@
 use std::collections::HashSet;
 use std::convert::TryInto;
 
-use abstract_os::objects::{AssetEntry, UncheckedContractEntry};
-
+use abstract_os::{
+    objects::{
+        proxy_asset::{get_pair_asset_names, other_asset_name, ProxyAsset, ValueRef},
+        AssetEntry, UncheckedContractEntry,
+    },
+    proxy::{
+        state::{LATEST_NAV, LIMITS, MEMORY, STATE, VAULT_ASSETS, VAULT_VALUE},
+        AssetsResponse, BaseAssetResponse, ConfigResponse, HoldingAmountResponse,
+        LatestNavResponse, LimitsResponse, ValidityResponse,
+    },
+};
+use abstract_sdk::{Resolve, ResolveCache};
 use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult, Uint128};
 use cw_storage_plus::Bound;
 
-use crate::msg::{AssetsResponse, ConfigResponse, ValidityResponse};
-use crate::proxy_asset::{get_pair_asset_names, other_asset_name, ProxyAsset, ValueRef};
-use crate::state::{MEMORY, STATE, VAULT_ASSETS};
+use crate::{prices::PriceSource, valuation::Valuation};
+
 const DEFAULT_LIMIT: u8 = 5;
 const MAX_LIMIT: u8 = 20;
+
 pub fn query_proxy_assets(
     deps: Deps,
     last_asset_name: Option<String>,
@@ -43,30 +53,101 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(resp)
 }
 
+/// Returns the amount of the asset that's held by the proxy
+pub fn query_holding_amount(
+    deps: Deps,
+    env: Env,
+    identifier: String,
+) -> StdResult<HoldingAmountResponse> {
+    let vault_asset: AssetEntry = identifier.into();
+    let memory = MEMORY.load(deps.storage)?;
+    let asset_info = vault_asset.resolve(deps, &memory)?;
+    Ok(HoldingAmountResponse {
+        amount: asset_info.query_balance(&deps.querier, env.contract.address)?,
+    })
+}
+
 /// Returns the value of a specified asset.
-pub fn compute_holding_value(deps: Deps, env: &Env, asset_entry: String) -> StdResult<Uint128> {
-    let mut vault_asset: ProxyAsset = VAULT_ASSETS.load(deps.storage, asset_entry.into())?;
+pub fn compute_holding_value(
+    deps: Deps,
+    env: &Env,
+    asset_entry: String,
+    prices: &dyn PriceSource,
+) -> StdResult<Uint128> {
+    let vault_asset: ProxyAsset = VAULT_ASSETS.load(deps.storage, asset_entry.into())?;
     let memory = MEMORY.load(deps.storage)?;
-    let value = vault_asset.value(deps, env, &memory, None)?;
-    Ok(value)
+    let valuation = Valuation {
+        memory: &memory,
+        env,
+        cache: &ResolveCache::new(),
+        prices,
+    };
+    valuation.value(deps, &vault_asset, None)
 }
 
 /// Computes the total value locked in this contract
-pub fn compute_total_value(deps: Deps, env: Env) -> StdResult<Uint128> {
+pub fn compute_total_value(deps: Deps, env: Env, prices: &dyn PriceSource) -> StdResult<Uint128> {
     // Get all assets from storage
-    let mut all_assets = VAULT_ASSETS
+    let all_assets = VAULT_ASSETS
         .range(deps.storage, None, None, Order::Ascending)
         .collect::<StdResult<Vec<(AssetEntry, ProxyAsset)>>>()?;
 
     let mut total_value = Uint128::zero();
     let memory = MEMORY.load(deps.storage)?;
+    // Resolve all the assets in one batch
+    let cache = ResolveCache::new();
+    cache.prefetch_assets(
+        deps,
+        &memory,
+        all_assets.iter().map(|(entry, _)| entry.clone()),
+    )?;
+    let valuation = Valuation {
+        memory: &memory,
+        env: &env,
+        cache: &cache,
+        prices,
+    };
     // Calculate their value iteratively
-    for vault_asset_entry in all_assets.iter_mut() {
-        total_value += vault_asset_entry.1.value(deps, &env, &memory, None)?;
+    for (_, vault_asset) in all_assets.iter() {
+        total_value += valuation.value(deps, vault_asset, None)?;
     }
     Ok(total_value)
 }
 
+pub fn query_base_asset(deps: Deps) -> StdResult<BaseAssetResponse> {
+    let res: Result<Vec<(AssetEntry, ProxyAsset)>, _> = VAULT_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+    let maybe_base_asset: Vec<(AssetEntry, ProxyAsset)> = res?
+        .into_iter()
+        .filter(|(_, p)| p.value_reference.is_none())
+        .collect();
+    if maybe_base_asset.len() != 1 {
+        Err(StdError::generic_err("No base asset configured."))
+    } else {
+        Ok(BaseAssetResponse {
+            base_asset: maybe_base_asset[0].1.to_owned(),
+        })
+    }
+}
+
+pub fn query_limits(deps: Deps) -> StdResult<LimitsResponse> {
+    Ok(LimitsResponse {
+        limits: LIMITS.may_load(deps.storage)?.unwrap_or_default(),
+        assets: VAULT_ASSETS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count() as u32,
+        modules: STATE.load(deps.storage)?.modules.len() as u32,
+    })
+}
+
+pub fn query_latest_nav(deps: Deps) -> StdResult<LatestNavResponse> {
+    Ok(LatestNavResponse {
+        nav: LATEST_NAV.may_load(deps.storage)?,
+        in_progress: VAULT_VALUE.status.load(deps.storage)?.is_locked,
+    })
+}
+
 pub fn query_proxy_asset_validity(deps: Deps) -> StdResult<ValidityResponse> {
     // assets that resolve and have valid value-references
     let mut checked_assets: HashSet<String> = HashSet::new();
@@ -202,14 +283,14 @@ pub fn try_load_asset(
 
 pub fn get_value_ref_dependencies(value_reference: &ValueRef, entry: String) -> Vec<AssetEntry> {
     match value_reference {
-        crate::proxy_asset::ValueRef::Pool { pair } => {
+        ValueRef::Pool { pair } => {
             // Check if the other asset in the pool resolves
             let other_pool_asset: AssetEntry = other_asset_name(entry.as_str(), &pair.contract)
                 .unwrap()
                 .into();
             vec![other_pool_asset]
         }
-        crate::proxy_asset::ValueRef::LiquidityToken {} => {
+        ValueRef::LiquidityToken {} => {
             // check if both tokens of pool resolve
             let maybe_pair: UncheckedContractEntry = entry.try_into().unwrap();
             let other_pool_asset_names = get_pair_asset_names(maybe_pair.contract.as_str());
@@ -217,10 +298,11 @@ pub fn get_value_ref_dependencies(value_reference: &ValueRef, entry: String) ->
             let asset2: AssetEntry = other_pool_asset_names[1].into();
             vec![asset1, asset2]
         }
-        crate::proxy_asset::ValueRef::ValueAs {
+        ValueRef::ValueAs {
             asset,
             multiplier: _,
         } => vec![asset.clone()],
-        crate::proxy_asset::ValueRef::External { api_name: _ } => todo!(),
+        // The value is provided by an external contract
+        ValueRef::External { api_name: _ } => vec![],
     }
 }
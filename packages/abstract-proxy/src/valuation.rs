@@ -0,0 +1,282 @@
+//! # Valuation
+//! Values [`ProxyAsset`]s relative to the base asset by recursively following their [`ValueRef`].
+
+use abstract_os::{
+    api::QueryMsg as ApiQuery,
+    dex::{ApiQueryMsg as DexQueryMsg, LpTokenValueResponse},
+    manager::state::OS_MODULES,
+    objects::{
+        memory::Memory,
+        proxy_asset::{get_pair_asset_names, other_asset_name, ProxyAsset, ValueRef},
+        AssetEntry, ContractEntry, UncheckedContractEntry,
+    },
+    proxy::{
+        state::{ADMIN, VAULT_ASSETS},
+        ExternalValueResponse, ValueQueryMsg,
+    },
+};
+use abstract_sdk::{Resolve, ResolveCache};
+use cosmwasm_std::{
+    to_binary, Addr, Decimal256, Deps, Env, QuerierWrapper, QueryRequest, StdError, StdResult,
+    Uint128, Uint256, WasmQuery,
+};
+use cw_asset::{Asset, AssetInfo};
+use std::convert::TryInto;
+
+use crate::prices::{dex_api_address, PriceSource};
+
+/// Everything that's needed to value the proxy assets.
+/// Memory entries are resolved through the cache so each entry is queried at most once.
+pub struct Valuation<'a> {
+    pub memory: &'a Memory,
+    pub env: &'a Env,
+    pub cache: &'a ResolveCache,
+    pub prices: &'a dyn PriceSource,
+}
+
+impl<'a> Valuation<'a> {
+    /// Calculates the value of the asset through the optionally provided ValueReference
+    /// The amount held by the proxy is valued if `set_holding` is None.
+    pub fn value(
+        &self,
+        deps: Deps,
+        proxy_asset: &ProxyAsset,
+        set_holding: Option<Uint128>,
+    ) -> StdResult<Uint128> {
+        // Query how many of these tokens are held in the contract if not set.
+        let asset_info = proxy_asset
+            .asset
+            .resolve_cached(deps, self.memory, self.cache)?;
+        let holding: Uint128 = match set_holding {
+            Some(setter) => setter,
+            None => asset_info.query_balance(&deps.querier, self.env.contract.address.clone())?,
+        };
+
+        let valued_asset = Asset::new(asset_info, holding);
+
+        // Is there a reference to calculate the value?
+        match &proxy_asset.value_reference {
+            // If there is no valueref, it means this token is the base token.
+            None => Ok(holding),
+            // A Pool refers to a swap pair that recursively leads to an asset/base_asset pool.
+            Some(ValueRef::Pool { pair }) => {
+                self.trade_pair_value(deps, &proxy_asset.asset, valued_asset, pair)
+            }
+            // Liquidity is an LP token, value() fn is called recursively on both assets in the pool
+            Some(ValueRef::LiquidityToken {}) => {
+                // We map the LP token to its pair address.
+                // lp tokens are stored as "dex/asset1_asset2" in the asset store.
+                // pairs are stored as ContractEntry{protocol: dex, contract: asset1_asset2} in the contract store.
+                let maybe_pair: UncheckedContractEntry =
+                    proxy_asset.asset.to_string().try_into()?;
+                self.lp_value(deps, &proxy_asset.asset, valued_asset, maybe_pair.check())
+            }
+            // A proxy asset is used instead
+            Some(ValueRef::ValueAs { asset, multiplier }) => {
+                let replacement_asset = VAULT_ASSETS.load(deps.storage, asset.clone())?;
+                self.value(deps, &replacement_asset, Some(holding * *multiplier))
+            }
+            Some(ValueRef::External { api_name }) => {
+                let manager = ADMIN.get(deps)?.unwrap();
+                let maybe_api_addr = OS_MODULES.query(&deps.querier, manager, api_name)?;
+                if let Some(api_addr) = maybe_api_addr {
+                    let response: ExternalValueResponse =
+                        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                            contract_addr: api_addr.to_string(),
+                            msg: to_binary(&ValueQueryMsg {
+                                asset: proxy_asset.asset.clone(),
+                                amount: valued_asset.amount,
+                            })?,
+                        }))?;
+                    Ok(response.value)
+                } else {
+                    Err(StdError::generic_err(format!(
+                        "external contract api {} must be enabled on OS",
+                        api_name
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Calculates the value of an asset compared to some base asset through the provided trading pair.
+    pub fn trade_pair_value(
+        &self,
+        deps: Deps,
+        asset: &AssetEntry,
+        valued_asset: Asset,
+        pair: &ContractEntry,
+    ) -> StdResult<Uint128> {
+        let other_pool_asset: AssetEntry = other_asset_name(asset.as_str(), &pair.contract)?.into();
+
+        // other / this, the price source provides the price of the first asset of the pair
+        let price = self.prices.pair_price(deps, self.memory, pair)?;
+        let ratio = if get_pair_asset_names(&pair.contract)[0] == asset.as_str() {
+            price
+        } else {
+            price.inv().unwrap_or_default()
+        };
+
+        // Get the value of the current asset in the denom of the other asset
+        let recursive_vault_asset = VAULT_ASSETS.load(deps.storage, other_pool_asset)?;
+
+        // #other = #this * (pool_other/pool_this)
+        let amount_in_other_denom = valued_asset.amount * ratio;
+        // Call value on this other asset.
+        self.value(deps, &recursive_vault_asset, Some(amount_in_other_denom))
+    }
+
+    /// Calculate the value of an LP token
+    /// Uses the dex API to get the assets the LP tokens are redeemable for, or the reserves of the pair
+    /// when the API isn't installed. Those amounts are rebalanced to the price of the pair's [`PriceSource`].
+    pub fn lp_value(
+        &self,
+        deps: Deps,
+        asset: &AssetEntry,
+        lp_asset: Asset,
+        pair: ContractEntry,
+    ) -> StdResult<Uint128> {
+        let pool_asset_names = get_pair_asset_names(pair.contract.as_str());
+        if pool_asset_names.len() != 2 {
+            return Err(StdError::generic_err(format!(
+                "lp pair contract {} must be composed of two assets.",
+                pair
+            )));
+        }
+        let pool_assets = pool_asset_names
+            .into_iter()
+            .map(|name| {
+                let entry = AssetEntry::from(name);
+                let info = entry.resolve_cached(deps, self.memory, self.cache)?;
+                Ok((entry, info))
+            })
+            .collect::<StdResult<Vec<(AssetEntry, AssetInfo)>>>()?;
+
+        let redeemable = match dex_lp_token_value(deps, asset, &lp_asset, &pair)? {
+            Some(assets) => assets,
+            None => self.lp_reserve_share(deps, &lp_asset, &pair, &pool_assets)?,
+        };
+        let redeemable = self.rebalance_to_price(deps, &pair, &pool_assets, &redeemable)?;
+
+        // Call value on these assets.
+        let mut value = Uint128::zero();
+        for redeemable_asset in redeemable {
+            let (entry, _) = pool_assets
+                .iter()
+                .find(|(_, info)| *info == redeemable_asset.info)
+                .ok_or_else(|| {
+                    StdError::generic_err(format!(
+                        "asset {} of pair {} is not a proxy asset",
+                        redeemable_asset.info, pair
+                    ))
+                })?;
+            let vault_asset: ProxyAsset = VAULT_ASSETS.load(deps.storage, entry.clone())?;
+            value += self.value(deps, &vault_asset, Some(redeemable_asset.amount))?;
+        }
+        Ok(value)
+    }
+
+    /// Amounts the LP tokens would be redeemable for if the pool traded at the price of the price source.
+    /// Swaps move the reserves of a constant product pool but not their product, so unlike the
+    /// redeemable amounts these can't be inflated by moving the pool right before the valuation.
+    fn rebalance_to_price(
+        &self,
+        deps: Deps,
+        pair: &ContractEntry,
+        pool_assets: &[(AssetEntry, AssetInfo)],
+        redeemable: &[Asset],
+    ) -> StdResult<Vec<Asset>> {
+        let amount_of = |info: &AssetInfo| {
+            redeemable
+                .iter()
+                .find(|asset| asset.info == *info)
+                .map(|asset| Decimal256::from_ratio(asset.amount, 1u8))
+                .ok_or_else(|| {
+                    StdError::generic_err(format!(
+                        "LP tokens of pair {} are not redeemable for {}",
+                        pair, info
+                    ))
+                })
+        };
+        let (first, second) = (&pool_assets[0].1, &pool_assets[1].1);
+        let invariant_sqrt = amount_of(first)?.sqrt() * amount_of(second)?.sqrt();
+        // price of the first asset, denominated in the second
+        let price_sqrt = Decimal256::from(self.prices.pair_price(deps, self.memory, pair)?).sqrt();
+        if price_sqrt.is_zero() {
+            return Err(StdError::generic_err(format!("pair {} has no price", pair)));
+        }
+        Ok(vec![
+            Asset::new(first.clone(), whole_amount(invariant_sqrt / price_sqrt)?),
+            Asset::new(second.clone(), whole_amount(invariant_sqrt * price_sqrt)?),
+        ])
+    }
+
+    /// Share of the pair's balances that the LP tokens represent
+    fn lp_reserve_share(
+        &self,
+        deps: Deps,
+        lp_asset: &Asset,
+        pair: &ContractEntry,
+        pool_assets: &[(AssetEntry, AssetInfo)],
+    ) -> StdResult<Vec<Asset>> {
+        let supply = match &lp_asset.info {
+            AssetInfo::Cw20(addr) => query_cw20_supply(&deps.querier, addr)?,
+            _ => return Err(StdError::generic_err("Can't have a native LP token")),
+        };
+        if supply.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "pair {} has no liquidity",
+                pair
+            )));
+        }
+        let pair_address = pair.resolve_cached(deps, self.memory, self.cache)?;
+        pool_assets
+            .iter()
+            .map(|(_, info)| {
+                let reserve = info.query_balance(&deps.querier, &pair_address)?;
+                Ok(Asset::new(
+                    info.clone(),
+                    reserve.multiply_ratio(lp_asset.amount, supply),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Assets that the LP tokens are redeemable for according to the dex API.
+/// None if the API isn't installed.
+fn dex_lp_token_value(
+    deps: Deps,
+    asset: &AssetEntry,
+    lp_asset: &Asset,
+    pair: &ContractEntry,
+) -> StdResult<Option<Vec<Asset>>> {
+    let dex_api = match dex_api_address(deps)? {
+        Some(dex_api) => dex_api,
+        None => return Ok(None),
+    };
+    let response: LpTokenValueResponse = deps.querier.query_wasm_smart(
+        dex_api,
+        &ApiQuery::Api(DexQueryMsg::LpTokenValue {
+            lp_token: (asset.clone(), lp_asset.amount),
+            dex: Some(pair.protocol.clone()),
+        }),
+    )?;
+    Ok(Some(response.assets))
+}
+
+/// Rounds to the nearest whole amount, as the square roots it's computed from are truncated
+fn whole_amount(amount: Decimal256) -> StdResult<Uint128> {
+    let whole: Uint256 =
+        (amount.atomics() + Decimal256::percent(50).atomics()) / Decimal256::one().atomics();
+    Ok(whole.try_into()?)
+}
+
+fn query_cw20_supply(querier: &QuerierWrapper, contract_addr: &Addr) -> StdResult<Uint128> {
+    let response: cw20::TokenInfoResponse =
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(&cw20::Cw20QueryMsg::TokenInfo {})?,
+        }))?;
+    Ok(response.total_supply)
+}
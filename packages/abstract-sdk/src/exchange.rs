@@ -1,30 +1,173 @@
-use abstract_os::{dex::OfferAsset, objects::AssetEntry, EXCHANGE};
-use cosmwasm_std::{CosmosMsg, Deps, StdResult};
+use abstract_os::{
+    api::QueryMsg,
+    dex::{ApiQueryMsg, OfferAsset, SimulateSwapResponse, SpotPriceResponse},
+    objects::AssetEntry,
+    EXCHANGE,
+};
+use cosmwasm_std::{CosmosMsg, Decimal, Deps, StdResult, Uint128};
+use serde::de::DeserializeOwned;
 
 use crate::Dependency;
 use abstract_os::dex::RequestMsg;
 
 /// Perform actions on an exchange API.
-/// WIP
+/// Requires the exchange API to be a dependency of the implementing module.
 pub trait Exchange: Dependency {
+    /// Swap `offer_asset` for `ask_asset` on `dex`.
     fn swap(
         &self,
         deps: Deps,
         dex: String,
         offer_asset: OfferAsset,
         ask_asset: AssetEntry,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
     ) -> StdResult<CosmosMsg> {
-        self.call_api_dependency(
+        self.request_exchange(
             deps,
-            EXCHANGE,
-            &RequestMsg::Swap {
+            RequestMsg::Swap {
                 offer_asset,
                 ask_asset,
                 dex: Some(dex),
-                max_spread: None,
-                belief_price: None,
+                max_spread,
+                belief_price,
             },
-            vec![],
         )
     }
+
+    /// Swap `offer_asset` through each asset in `path`.
+    fn route_swap(
+        &self,
+        deps: Deps,
+        dex: String,
+        offer_asset: OfferAsset,
+        path: Vec<AssetEntry>,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<CosmosMsg> {
+        self.request_exchange(
+            deps,
+            RequestMsg::RouteSwap {
+                offer_asset,
+                path,
+                dex: Some(dex),
+                max_spread,
+            },
+        )
+    }
+
+    /// Provide liquidity with the exact amounts of `assets`.
+    fn provide_liquidity(
+        &self,
+        deps: Deps,
+        dex: String,
+        assets: Vec<OfferAsset>,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<CosmosMsg> {
+        self.request_exchange(
+            deps,
+            RequestMsg::ProvideLiquidity {
+                assets,
+                dex: Some(dex),
+                max_spread,
+            },
+        )
+    }
+
+    /// Provide liquidity with `offer_asset` and the amounts of `paired_assets` that match the pool ratio.
+    /// Fails when the pool price of `offer_asset` deviates more than `max_spread` from `belief_price`.
+    #[allow(clippy::too_many_arguments)]
+    fn provide_liquidity_symmetric(
+        &self,
+        deps: Deps,
+        dex: String,
+        offer_asset: OfferAsset,
+        paired_assets: Vec<AssetEntry>,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+    ) -> StdResult<CosmosMsg> {
+        self.request_exchange(
+            deps,
+            RequestMsg::ProvideLiquiditySymmetric {
+                offer_asset,
+                paired_assets,
+                dex: Some(dex),
+                max_spread,
+                belief_price,
+            },
+        )
+    }
+
+    /// Withdraw `amount` of `lp_token` from its pool.
+    /// Fails when less than `min_assets` would be returned.
+    fn withdraw_liquidity(
+        &self,
+        deps: Deps,
+        dex: String,
+        lp_token: AssetEntry,
+        amount: Uint128,
+        min_assets: Option<Vec<OfferAsset>>,
+    ) -> StdResult<CosmosMsg> {
+        self.request_exchange(
+            deps,
+            RequestMsg::WithdrawLiquidity {
+                lp_token,
+                amount,
+                dex: Some(dex),
+                min_assets,
+            },
+        )
+    }
+
+    /// Simulate a swap of `offer_asset` for `ask_asset` on `dex`.
+    fn simulate_swap(
+        &self,
+        deps: Deps,
+        dex: String,
+        offer_asset: OfferAsset,
+        ask_asset: AssetEntry,
+    ) -> StdResult<SimulateSwapResponse> {
+        self.query_exchange(
+            deps,
+            ApiQueryMsg::SimulateSwap {
+                offer_asset,
+                ask_asset,
+                dex: Some(dex),
+            },
+        )
+    }
+
+    /// Spot price of `offer_asset` in `ask_asset` on `dex`.
+    fn spot_price(
+        &self,
+        deps: Deps,
+        dex: String,
+        offer_asset: AssetEntry,
+        ask_asset: AssetEntry,
+    ) -> StdResult<Decimal> {
+        let response: SpotPriceResponse = self.query_exchange(
+            deps,
+            ApiQueryMsg::SpotPrice {
+                offer_asset,
+                ask_asset,
+                dex: Some(dex),
+            },
+        )?;
+        Ok(response.price)
+    }
+
+    /// Construct a request message to the exchange API.
+    fn request_exchange(&self, deps: Deps, request: RequestMsg) -> StdResult<CosmosMsg> {
+        self.call_api_dependency(deps, EXCHANGE, &request, vec![])
+    }
+
+    /// Query the exchange API.
+    fn query_exchange<R: DeserializeOwned>(
+        &self,
+        deps: Deps,
+        query: ApiQueryMsg,
+    ) -> StdResult<R> {
+        let exchange_address = self.dependency_address(deps, EXCHANGE)?;
+        deps.querier
+            .query_wasm_smart(exchange_address, &QueryMsg::Api(query))
+    }
 }
@@ -21,10 +21,12 @@ pub mod memory {
 
 pub use api::{api_request, configure_api};
 pub use manager::{query_module_address, query_module_version};
-pub use memory_traits::Resolve;
+pub use memory_traits::{Resolve, ResolveCache};
 pub use module_traits::{Dependency, MemoryOperation, OsExecute};
-pub use proxy::{query_total_value, send_to_proxy};
-pub use version_control::{get_module, get_os_core, verify_os_manager, verify_os_proxy};
+pub use proxy::{query_latest_nav, query_total_value, send_to_proxy};
+pub use version_control::{
+    get_module, get_module_dependencies, get_os_core, verify_os_manager, verify_os_proxy,
+};
 
 pub use abstract_os::{
     objects::common_namespace::{ADMIN, BASE_STATE, CONTRACT_VERSION},
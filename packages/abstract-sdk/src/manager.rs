@@ -6,11 +6,11 @@
 
 use cosmwasm_std::{QuerierWrapper, StdResult};
 
-use abstract_os::manager::state::OS_ID;
+use abstract_os::manager::state::{OS_ID, STATUS};
 
 use std::collections::BTreeMap;
 
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{from_slice, Addr, Binary};
 
 use cosmwasm_storage::to_length_prefixed;
 
@@ -22,6 +22,14 @@ pub fn query_os_id(querier: &QuerierWrapper, core_contract_addr: &Addr) -> StdRe
     OS_ID.query(querier, core_contract_addr.clone())
 }
 
+/// RawQuery the subscription status of an OS, an OS without a status is active.
+pub fn query_os_status(querier: &QuerierWrapper, manager_addr: &Addr) -> StdResult<bool> {
+    match querier.query_wasm_raw(manager_addr, STATUS.as_slice())? {
+        Some(status) => from_slice(&status),
+        None => Ok(true),
+    }
+}
+
 /// RawQuery the version of an enabled module
 pub fn query_module_version(deps: &Deps, module_addr: Addr) -> StdResult<ContractVersion> {
     let req = QueryRequest::Wasm(WasmQuery::Raw {
@@ -1,5 +1,9 @@
 //! # Memory Entry
 //! An entry (value) in the memory key-value store.
+//!
+//! Entries that are resolved multiple times during an execution can be memoized in a [`ResolveCache`].
+
+use std::{cell::RefCell, collections::BTreeMap};
 
 use cosmwasm_std::{Addr, Deps, StdResult};
 use cw_asset::AssetInfo;
@@ -9,6 +13,16 @@ use abstract_os::objects::{memory::Memory, AssetEntry, ContractEntry};
 pub trait Resolve {
     type Output;
     fn resolve(&self, deps: Deps, memory: &Memory) -> StdResult<Self::Output>;
+    /// Resolves the entry through `cache`, memory is only queried on a cache miss.
+    /// Entries that aren't cached are resolved with [`Resolve::resolve`].
+    fn resolve_cached(
+        &self,
+        deps: Deps,
+        memory: &Memory,
+        _cache: &ResolveCache,
+    ) -> StdResult<Self::Output> {
+        self.resolve(deps, memory)
+    }
 }
 
 impl Resolve for AssetEntry {
@@ -16,6 +30,20 @@ impl Resolve for AssetEntry {
     fn resolve(&self, deps: Deps, memory: &Memory) -> StdResult<Self::Output> {
         memory.query_asset(deps, self)
     }
+
+    fn resolve_cached(
+        &self,
+        deps: Deps,
+        memory: &Memory,
+        cache: &ResolveCache,
+    ) -> StdResult<Self::Output> {
+        if let Some(info) = cache.assets.borrow().get(self) {
+            return Ok(info.clone());
+        }
+        let info = self.resolve(deps, memory)?;
+        cache.assets.borrow_mut().insert(self.clone(), info.clone());
+        Ok(info)
+    }
 }
 
 impl Resolve for ContractEntry {
@@ -23,4 +51,83 @@ impl Resolve for ContractEntry {
     fn resolve(&self, deps: Deps, memory: &Memory) -> StdResult<Self::Output> {
         memory.query_contract(deps, self)
     }
+
+    fn resolve_cached(
+        &self,
+        deps: Deps,
+        memory: &Memory,
+        cache: &ResolveCache,
+    ) -> StdResult<Self::Output> {
+        if let Some(addr) = cache.contracts.borrow().get(self) {
+            return Ok(addr.clone());
+        }
+        let addr = self.resolve(deps, memory)?;
+        cache
+            .contracts
+            .borrow_mut()
+            .insert(self.clone(), addr.clone());
+        Ok(addr)
+    }
+}
+
+/// Memoizes resolved memory entries for the duration of an execution.
+/// Entries that are known to be needed can be prefetched in one batched query.
+#[derive(Default)]
+pub struct ResolveCache {
+    assets: RefCell<BTreeMap<AssetEntry, AssetInfo>>,
+    contracts: RefCell<BTreeMap<ContractEntry, Addr>>,
+}
+
+impl ResolveCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves the uncached `assets` with a single (batched) memory query.
+    pub fn prefetch_assets(
+        &self,
+        deps: Deps,
+        memory: &Memory,
+        assets: impl IntoIterator<Item = AssetEntry>,
+    ) -> StdResult<()> {
+        let missing = {
+            let cached = self.assets.borrow();
+            let mut missing: Vec<AssetEntry> = assets
+                .into_iter()
+                .filter(|a| !cached.contains_key(a))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+        if !missing.is_empty() {
+            let resolved = memory.query_assets(deps, missing)?;
+            self.assets.borrow_mut().extend(resolved);
+        }
+        Ok(())
+    }
+
+    /// Resolves the uncached `contracts` with a single (batched) memory query.
+    pub fn prefetch_contracts(
+        &self,
+        deps: Deps,
+        memory: &Memory,
+        contracts: impl IntoIterator<Item = ContractEntry>,
+    ) -> StdResult<()> {
+        let missing = {
+            let cached = self.contracts.borrow();
+            let mut missing: Vec<ContractEntry> = contracts
+                .into_iter()
+                .filter(|c| !cached.contains_key(c))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+        if !missing.is_empty() {
+            let resolved = memory.query_contracts(deps, missing)?;
+            self.contracts.borrow_mut().extend(resolved);
+        }
+        Ok(())
+    }
 }
@@ -2,13 +2,14 @@
 use abstract_os::{
     objects::{proxy_asset::ProxyAsset, AssetEntry},
     proxy::{
-        state::VAULT_ASSETS, AssetsResponse, BaseAssetResponse, ExecuteMsg, HoldingValueResponse,
+        state::{NavSnapshot, VAULT_ASSETS},
+        AssetsResponse, BaseAssetResponse, ExecuteMsg, HoldingValueResponse, LatestNavResponse,
         QueryMsg, TotalValueResponse,
     },
 };
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, Deps, Empty, QuerierWrapper, QueryRequest, StdError, StdResult,
-    Uint128, WasmMsg, WasmQuery,
+    to_binary, Addr, CosmosMsg, Deps, Empty, Env, QuerierWrapper, QueryRequest, StdError,
+    StdResult, Uint128, WasmMsg, WasmQuery,
 };
 use cw_storage_plus::Item;
 
@@ -42,6 +43,24 @@ pub fn query_total_value(deps: Deps, proxy_address: &Addr) -> StdResult<Uint128>
     Ok(response.value)
 }
 
+/// Query the last NAV snapshot of the proxy
+/// Errors if no snapshot was computed yet, or if it is older than `max_age` seconds.
+pub fn query_latest_nav(
+    deps: Deps,
+    env: &Env,
+    proxy_address: &Addr,
+    max_age: u64,
+) -> StdResult<NavSnapshot> {
+    let response: LatestNavResponse = deps
+        .querier
+        .query_wasm_smart(proxy_address, &QueryMsg::LatestNav {})?;
+    let nav = response
+        .nav
+        .ok_or_else(|| StdError::generic_err("No NAV snapshot available on the proxy."))?;
+    nav.check_consistent(&env.block, max_age)?;
+    Ok(nav)
+}
+
 /// Query the holding value denominated in the base asset
 /// The provided address must implement the HoldingValue Query
 pub fn query_holding_value(
@@ -1,7 +1,12 @@
 use abstract_os::{
     manager::state::OS_ID,
-    objects::module::{Module, ModuleInfo},
-    version_control::{state::OS_ADDRESSES, Core, ModuleResponse, QueryMsg},
+    objects::{
+        dependency::Dependency,
+        module::{Module, ModuleInfo},
+    },
+    version_control::{
+        state::OS_ADDRESSES, Core, ModuleDependenciesResponse, ModuleResponse, QueryMsg,
+    },
 };
 use cosmwasm_std::{Addr, QuerierWrapper, StdError};
 
@@ -87,3 +92,18 @@ pub fn get_module(
     )?;
     Ok(resp.module)
 }
+
+/// Query the declared dependencies of a module
+pub fn get_module_dependencies(
+    querier: &QuerierWrapper,
+    module_info: ModuleInfo,
+    version_control_addr: &Addr,
+) -> StdResult<Vec<Dependency>> {
+    let resp: ModuleDependenciesResponse = querier.query_wasm_smart(
+        version_control_addr,
+        &QueryMsg::ModuleDependencies {
+            module: module_info,
+        },
+    )?;
+    Ok(resp.dependencies)
+}
@@ -4,6 +4,8 @@ use schemars::JsonSchema;
 
 use serde::{Deserialize, Serialize};
 
+use crate::TwapRequest;
+
 /// This is the message we send over the IBC channel
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -18,6 +20,11 @@ pub enum PacketMsg {
         msgs: Vec<QueryRequest<OsmosisQuery>>,
         callback_id: Option<String>,
     },
+    TwapQuery {
+        sender: String,
+        requests: Vec<TwapRequest>,
+        callback_id: Option<String>,
+    },
     WhoAmI {},
     Balances {},
     SendAllBack {
@@ -1,4 +1,4 @@
-use cosmwasm_std::{from_slice, to_binary, Binary, Coin};
+use cosmwasm_std::{from_slice, to_binary, Binary, Coin, Decimal, Timestamp};
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -77,3 +77,54 @@ pub struct BalancesResponse {
     pub account: String,
     pub balances: Vec<Coin>,
 }
+
+/// Averaging method of a TWAP
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TwapKind {
+    Arithmetic,
+    Geometric,
+}
+
+/// TWAP of a pool over `[start_time, end_time]`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TwapRequest {
+    pub pool_id: u64,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub kind: TwapKind,
+    pub start_time: Timestamp,
+    /// Defaults to the block time of the host
+    pub end_time: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TwapResult {
+    pub pool_id: u64,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub kind: TwapKind,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Price of the base asset in the quote asset
+    pub twap: Decimal,
+}
+
+impl TwapResult {
+    /// Whether this is the result of `request`
+    pub fn answers(&self, request: &TwapRequest) -> bool {
+        self.pool_id == request.pool_id
+            && self.base_asset == request.base_asset
+            && self.quote_asset == request.quote_asset
+            && self.kind == request.kind
+            && self.start_time == request.start_time
+            && request.end_time.map_or(true, |end| end == self.end_time)
+    }
+}
+
+/// This is the success response we send on ack for PacketMsg::TwapQuery.
+/// The results are in the order of the requests.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TwapQueryResponse {
+    pub results: Vec<TwapResult>,
+}
@@ -3,15 +3,17 @@ mod checks;
 pub mod client_ibc_msg;
 mod ibc_msg;
 pub mod osmosis_router_msg;
+pub mod result_store;
 
 use cosmwasm_std::IbcOrder;
 
 pub use crate::callback::ReceiveIcaResponseMsg;
 pub use crate::checks::{check_order, check_version, SimpleIcaError};
 pub use crate::ibc_msg::{
-    BalancesResponse, DispatchResponse, IbcQueryResponse, SendAllBackResponse, StdAck,
-    WhoAmIResponse,
+    BalancesResponse, DispatchResponse, IbcQueryResponse, SendAllBackResponse, StdAck, TwapKind,
+    TwapQueryResponse, TwapRequest, TwapResult, WhoAmIResponse,
 };
+pub use crate::result_store::{AsyncResultStore, StoredResult};
 
 #[cfg(feature = "host")]
 pub mod host_ibc_msg;
@@ -0,0 +1,290 @@
+//! # Async Result Store
+//! Stores the [`StdAck`] of every ICA callback under the namespace of the caller that requested it.
+//!
+//! Modules that dispatch IBC requests can embed an [`AsyncResultStore`] and call
+//! [`AsyncResultStore::request_id`] when constructing the `callback_id` of their request.
+//! The id that is returned in the [`crate::ReceiveIcaResponseMsg`] can then be passed
+//! to [`AsyncResultStore::save`] as-is.
+
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use cosmwasm_std::{from_binary, Empty, Env, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::StdAck;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+/// Separates the namespace from the callback id in a request id.
+const NAMESPACE_SEPARATOR: char = '/';
+
+/// A received callback result
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StoredResult {
+    pub ack: StdAck,
+    pub received_at: Timestamp,
+    /// The result can be pruned after this time
+    pub expires_at: Option<Timestamp>,
+}
+
+impl StoredResult {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.map(|expiry| expiry <= now).unwrap_or(false)
+    }
+
+    /// Decode the result into `T`, returns an error if the remote execution failed.
+    pub fn decode<T: DeserializeOwned>(&self) -> StdResult<T> {
+        match &self.ack {
+            StdAck::Result(data) => from_binary(data),
+            StdAck::Error(err) => Err(StdError::generic_err(format!(
+                "remote execution failed: {}",
+                err
+            ))),
+        }
+    }
+}
+
+/// Namespaced storage for ICA callback results.
+pub struct AsyncResultStore<'a> {
+    // (namespace, callback_id) -> result
+    results: Map<'a, (&'a str, &'a str), StoredResult>,
+    // Time in seconds before a result can be pruned. None means results never expire.
+    ttl: Item<'a, Option<u64>>,
+    // (expiry in nanos, namespace, callback_id) of every result that expires
+    expiries: Map<'a, (u64, &'a str, &'a str), Empty>,
+}
+
+impl<'a> Default for AsyncResultStore<'a> {
+    fn default() -> Self {
+        Self::new(
+            "async_results",
+            "async_results_ttl",
+            "async_results_expiries",
+        )
+    }
+}
+
+impl<'a> AsyncResultStore<'a> {
+    pub const fn new(
+        results_namespace: &'a str,
+        ttl_namespace: &'a str,
+        expiries_namespace: &'a str,
+    ) -> Self {
+        Self {
+            results: Map::new(results_namespace),
+            ttl: Item::new(ttl_namespace),
+            expiries: Map::new(expiries_namespace),
+        }
+    }
+
+    /// Set the time (in seconds) after which results can be pruned.
+    pub fn set_ttl(&self, store: &mut dyn Storage, ttl: Option<u64>) -> StdResult<()> {
+        self.ttl.save(store, &ttl)
+    }
+
+    /// Constructs the id that should be passed as `callback_id` to the ICA client.
+    pub fn request_id(namespace: &str, callback_id: &str) -> StdResult<String> {
+        if namespace.contains(NAMESPACE_SEPARATOR) {
+            return Err(StdError::generic_err(format!(
+                "namespace {} can not contain '{}'",
+                namespace, NAMESPACE_SEPARATOR
+            )));
+        }
+        Ok(format!(
+            "{}{}{}",
+            namespace, NAMESPACE_SEPARATOR, callback_id
+        ))
+    }
+
+    /// Splits a request id into its namespace and callback id.
+    pub fn parse_request_id(request_id: &str) -> StdResult<(&str, &str)> {
+        request_id
+            .split_once(NAMESPACE_SEPARATOR)
+            .ok_or_else(|| StdError::generic_err(format!("invalid request id {}", request_id)))
+    }
+
+    /// Store the ack of a callback with the id returned by the ICA client.
+    pub fn save(
+        &self,
+        store: &mut dyn Storage,
+        env: &Env,
+        request_id: &str,
+        ack: StdAck,
+    ) -> StdResult<StoredResult> {
+        let (namespace, callback_id) = Self::parse_request_id(request_id)?;
+        let ttl = self.ttl.may_load(store)?.flatten();
+        let result = StoredResult {
+            ack,
+            received_at: env.block.time,
+            expires_at: ttl.map(|ttl| env.block.time.plus_seconds(ttl)),
+        };
+        // a result that is received again replaces the previous one and its expiry
+        self.take(store, namespace, callback_id)?;
+        self.results
+            .save(store, (namespace, callback_id), &result)?;
+        if let Some(expiry) = result.expires_at {
+            self.expiries
+                .save(store, (expiry.nanos(), namespace, callback_id), &Empty {})?;
+        }
+        Ok(result)
+    }
+
+    pub fn may_load(
+        &self,
+        store: &dyn Storage,
+        namespace: &str,
+        callback_id: &str,
+    ) -> StdResult<Option<StoredResult>> {
+        self.results.may_load(store, (namespace, callback_id))
+    }
+
+    pub fn load(
+        &self,
+        store: &dyn Storage,
+        namespace: &str,
+        callback_id: &str,
+    ) -> StdResult<StoredResult> {
+        self.results.load(store, (namespace, callback_id))
+    }
+
+    /// Load and decode a result
+    pub fn load_as<T: DeserializeOwned>(
+        &self,
+        store: &dyn Storage,
+        namespace: &str,
+        callback_id: &str,
+    ) -> StdResult<T> {
+        self.load(store, namespace, callback_id)?.decode()
+    }
+
+    /// Remove a result, returns the removed result if it existed.
+    pub fn take(
+        &self,
+        store: &mut dyn Storage,
+        namespace: &str,
+        callback_id: &str,
+    ) -> StdResult<Option<StoredResult>> {
+        let result = self.may_load(store, namespace, callback_id)?;
+        self.results.remove(store, (namespace, callback_id));
+        if let Some(expiry) = result.as_ref().and_then(|result| result.expires_at) {
+            self.expiries
+                .remove(store, (expiry.nanos(), namespace, callback_id));
+        }
+        Ok(result)
+    }
+
+    /// Page over the results of a namespace, ordered by callback id.
+    pub fn page(
+        &self,
+        store: &dyn Storage,
+        namespace: &str,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(String, StoredResult)>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        self.results
+            .prefix(namespace)
+            .range(store, start, None, Order::Ascending)
+            .take(limit)
+            .collect()
+    }
+
+    /// Removes up to `limit` expired results, returns the amount of removed results.
+    /// Only the expired entries of the expiry index are read.
+    pub fn prune(&self, store: &mut dyn Storage, now: Timestamp, limit: u32) -> StdResult<usize> {
+        let expired: Vec<(u64, String, String)> = self
+            .expiries
+            .keys(store, None, None, Order::Ascending)
+            .take_while(|key| {
+                key.as_ref()
+                    .map(|(expiry, ..)| *expiry <= now.nanos())
+                    .unwrap_or(true)
+            })
+            .take(limit as usize)
+            .collect::<StdResult<_>>()?;
+        for (expiry, namespace, callback_id) in &expired {
+            self.results
+                .remove(store, (namespace.as_str(), callback_id.as_str()));
+            self.expiries
+                .remove(store, (*expiry, namespace.as_str(), callback_id.as_str()));
+        }
+        Ok(expired.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    const STORE: AsyncResultStore = AsyncResultStore::new("results", "ttl", "expiries");
+
+    fn ack(data: &str) -> StdAck {
+        StdAck::Error(data.to_string())
+    }
+
+    #[test]
+    fn prunes_in_expiry_order() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        STORE.set_ttl(&mut deps.storage, Some(60)).unwrap();
+        STORE
+            .save(&mut deps.storage, &env, "bob/first", ack("1"))
+            .unwrap();
+        env.block.time = env.block.time.plus_seconds(30);
+        STORE
+            .save(&mut deps.storage, &env, "alice/first", ack("2"))
+            .unwrap();
+        // a result that is received again expires later
+        STORE
+            .save(&mut deps.storage, &env, "bob/first", ack("3"))
+            .unwrap();
+        STORE.set_ttl(&mut deps.storage, None).unwrap();
+        STORE
+            .save(&mut deps.storage, &env, "carl/first", ack("4"))
+            .unwrap();
+
+        let expiry = env.block.time.plus_seconds(60);
+        let pruned = STORE
+            .prune(&mut deps.storage, env.block.time.plus_seconds(59), 10)
+            .unwrap();
+        assert_eq!(pruned, 0);
+        // the limit is applied on the expired results
+        let pruned = STORE.prune(&mut deps.storage, expiry, 1).unwrap();
+        assert_eq!(pruned, 1);
+        let pruned = STORE.prune(&mut deps.storage, expiry, 10).unwrap();
+        assert_eq!(pruned, 1);
+        for namespace in ["alice", "bob"] {
+            assert_eq!(
+                STORE.may_load(&deps.storage, namespace, "first").unwrap(),
+                None
+            );
+        }
+        // results without expiry are kept
+        let kept = STORE.load(&deps.storage, "carl", "first").unwrap();
+        assert_eq!(kept.ack, ack("4"));
+        assert_eq!(kept.expires_at, None);
+        let pruned = STORE
+            .prune(&mut deps.storage, expiry.plus_seconds(1000), 10)
+            .unwrap();
+        assert_eq!(pruned, 0);
+    }
+
+    #[test]
+    fn taken_results_leave_the_index() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        STORE.set_ttl(&mut deps.storage, Some(60)).unwrap();
+        STORE
+            .save(&mut deps.storage, &env, "bob/first", ack("1"))
+            .unwrap();
+        let taken = STORE.take(&mut deps.storage, "bob", "first").unwrap();
+        assert_eq!(taken.unwrap().ack, ack("1"));
+        let pruned = STORE
+            .prune(&mut deps.storage, env.block.time.plus_seconds(60), 10)
+            .unwrap();
+        assert_eq!(pruned, 0);
+    }
+}